@@ -2,97 +2,358 @@ use std::path::Path;
 
 use anyhow::{bail, Error};
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 use fehler::throws;
-use trident_client::___private::{Commander, TestGenerator};
+use trident_client::___private::{Commander, Engine, TestGenerator};
 
 use crate::{_discover, show_howto};
 
 pub const TRIDENT_TOML: &str = "Trident.toml";
 
+/// Options of `trident fuzz run`, shared with its `run-hfuzz`, `run-afl` and `run-libfuzzer` aliases.
+#[derive(Args)]
+pub struct RunArgs {
+    #[arg(
+        required = true,
+        help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+    )]
+    target: String,
+    #[arg(
+        short,
+        long,
+        required = false,
+        help = "Run the fuzzing engine with exit code, i.e. if it discovers crash the Trident will exit with exit code 99."
+    )]
+    with_exit_code: bool,
+    #[arg(
+        short,
+        long,
+        required = false,
+        help = "Show the live dashboard with the Trident statistics, the output of the fuzzing engine is written into the log file within its workspace."
+    )]
+    dashboard: bool,
+    #[arg(
+        long,
+        required = false,
+        requires = "with_exit_code",
+        value_name = "PATH",
+        help = "Write the JUnit XML report of the session, one testcase per instruction and invariant."
+    )]
+    junit: Option<String>,
+    #[arg(
+        long,
+        required = false,
+        requires = "with_exit_code",
+        value_name = "PATH",
+        help = "Write the SARIF report of the crashes found during the session."
+    )]
+    sarif: Option<String>,
+}
+
+/// Options of `trident fuzz debug`, shared with its `debug-hfuzz`, `debug-afl` and `debug-libfuzzer` aliases.
+#[derive(Args)]
+pub struct DebugArgs {
+    #[arg(
+        required = true,
+        help = "Name of the desired fuzz template to execute (for example fuzz_0)"
+    )]
+    target: String,
+    #[arg(required = true, help = "Path to the crash found during fuzzing")]
+    crash_file_path: String,
+}
+
 #[derive(Subcommand)]
 #[allow(non_camel_case_types)]
 pub enum FuzzCommand {
     #[command(about = "Generate new Fuzz Test template.")]
     Add,
-    // #[command(
-    //     about = "Run the AFL on desired fuzz test.",
-    //     override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
-    //         \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to execute (for example fuzz_0).\
-    //         \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-    //         \n      trident fuzz run-afl fuzz_0"
-    // )]
-    // Run_Afl {
-    //     #[arg(
-    //         required = true,
-    //         help = "Name of the desired fuzz template to execute (for example fuzz_0)."
-    //     )]
-    //     target: String,
-    // },
     #[command(
-        about = "Run the Honggfuzz on desired fuzz test.",
-        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+        about = "List the fuzz tests of the workspace with their programs, instructions, corpus, crashes, last run and fuzzing engine."
+    )]
+    List {
+        #[arg(long, help = "Print the fuzz tests as JSON.")]
+        json: bool,
+    },
+    #[command(
+        about = "Run the desired fuzz test with the selected fuzzing engine.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and optionally the fuzzing \x1b[92m<ENGINE>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to execute (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<ENGINE>:\x1b[0m Fuzzing engine: honggfuzz, afl or libfuzzer (default: from the Trident.toml or honggfuzz).\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-            \n      trident fuzz run-hfuzz fuzz_0"
+            \n      trident fuzz run fuzz_0 --engine libfuzzer"
     )]
-    Run_Hfuzz {
+    Run {
+        #[command(flatten)]
+        args: RunArgs,
         #[arg(
-            required = true,
-            help = "Name of the desired fuzz template to execute (for example fuzz_0)."
+            short,
+            long,
+            help = "Fuzzing engine: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
         )]
-        target: String,
+        engine: Option<Engine>,
+    },
+    #[command(
+        about = "Run all fuzz tests of the workspace with the selected fuzzing engine and print a combined summary.",
+        override_usage = "Optionally specify the fuzzing \x1b[92m<ENGINE>\x1b[0m and the time budgets.\
+            \n      \x1b[1m\x1b[4m<ENGINE>:\x1b[0m Fuzzing engine: honggfuzz, afl or libfuzzer (default: from the Trident.toml or honggfuzz).\
+            \n      \x1b[1m\x1b[4m<SECONDS>:\x1b[0m Total time budget split between the fuzz targets.\
+            \n      \x1b[1m\x1b[4m<TARGET=SECONDS>:\x1b[0m Time budget of the fuzz target, excluded from the split.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz run-all --total-time 3600 --target-time fuzz_0=1800"
+    )]
+    Run_All {
+        #[arg(
+            short,
+            long,
+            help = "Fuzzing engine: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
+        )]
+        engine: Option<Engine>,
+        #[arg(
+            short,
+            long,
+            required = false,
+            value_name = "SECONDS",
+            help = "Total time budget, split evenly between the fuzz targets in sequence, the time limit of every fuzz target in parallel."
+        )]
+        total_time: Option<u64>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "TARGET=SECONDS",
+            value_parser = parse_target_time,
+            help = "Time budget of the fuzz target, can be specified multiple times."
+        )]
+        target_time: Vec<(String, u64)>,
         #[arg(
             short,
             long,
             required = false,
-            help = "Run the Honggfuzz with exit code, i.e. if it discovers crash the Trident will exit with exit code 1."
+            help = "Fuzz the targets in parallel, the output of the fuzzing engine is written into the log file of each target."
+        )]
+        parallel: bool,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Exit with exit code 99 if any of the fuzz targets discovers new crashes."
         )]
         with_exit_code: bool,
     },
-
-    // #[command(
-    //     about = "Debug found crash using the AFL on desired fuzz test.",
-    //     override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
-    //         \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to debug (for example fuzz_0).\
-    //         \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
-    //         \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
-    //         \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>\
-    //         \n      \x1b[1m\x1b[4mAFL:\x1b[0m trident-tests/fuzz_tests/fuzzing/afl/afl_workspace/out/default/crashes\
-    //         \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-    //         \n      trident fuzz debug-afl fuzz_0 trident-tests/fuzz_tests/fuzzing/afl/afl_workspace/out/default/crashes/id...\
-    //         \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
-    //         \n      Do not mix fuzz templates and crashfiles. If the crash was found with fuzz_0, then debug it with fuzz_0."
-    // )]
-    // Debug_Afl {
-    //     #[arg(
-    //         required = true,
-    //         help = "Name of the desired fuzz template to execute (for example fuzz_0)"
-    //     )]
-    //     target: String,
-    //     #[arg(required = true, help = "Path to the crash found during fuzzing")]
-    //     crash_file_path: String,
-    // },
     #[command(
-        about = "Debug found crash using the Honggfuzz on desired fuzz test.",
-        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
+        about = "Debug found crash using the selected fuzzing engine on desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m, \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m and optionally the fuzzing \x1b[92m<ENGINE>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to debug (for example fuzz_0).\
             \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
-            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
-            \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>\
+            \n      \x1b[1m\x1b[4m<ENGINE>:\x1b[0m Fuzzing engine the crash was found with: honggfuzz, afl or libfuzzer (default: from the Trident.toml or honggfuzz).\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
-            \n      trident fuzz debug-hfuzz fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR...\
+            \n      trident fuzz debug fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR...\
             \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
             \n      Do not mix fuzz templates and crashfiles. If the crash was found with fuzz_0, then debug it with fuzz_0."
     )]
-    Debug_Hfuzz {
+    Debug {
+        #[command(flatten)]
+        args: DebugArgs,
+        #[arg(
+            short,
+            long,
+            help = "Fuzzing engine: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
+        )]
+        engine: Option<Engine>,
+    },
+    #[command(hide = true, about = "Alias of `trident fuzz run --engine afl`.")]
+    Run_Afl(RunArgs),
+    #[command(hide = true, about = "Alias of `trident fuzz run --engine honggfuzz`.")]
+    Run_Hfuzz(RunArgs),
+
+    #[command(hide = true, about = "Alias of `trident fuzz run --engine libfuzzer`.")]
+    Run_Libfuzzer(RunArgs),
+    #[command(
+        about = "Merge corpus directories into the libFuzzer corpus of desired fuzz test, keeping only the inputs extending the coverage.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<CORPUS_PATHS>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<CORPUS_PATHS>:\x1b[0m Directories of the inputs to merge, e.g. the Honggfuzz workspace of the target.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz merge-libfuzzer fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0"
+    )]
+    Merge_Libfuzzer {
         #[arg(
             required = true,
-            help = "Name of the desired fuzz template to execute (for example fuzz_0)"
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(required = true, help = "Directories of the inputs to merge")]
+        corpus_paths: Vec<String>,
+    },
+
+    #[command(hide = true, about = "Alias of `trident fuzz debug --engine afl`.")]
+    Debug_Afl(DebugArgs),
+    #[command(
+        hide = true,
+        about = "Alias of `trident fuzz debug --engine honggfuzz`."
+    )]
+    Debug_Hfuzz(DebugArgs),
+    #[command(
+        hide = true,
+        about = "Alias of `trident fuzz debug --engine libfuzzer`."
+    )]
+    Debug_Libfuzzer(DebugArgs),
+    #[command(
+        about = "Generate the source coverage report of the programs from the corpus and crashes of the desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m The LLVM tools are required, install them with:\
+            \n      rustup component add llvm-tools-preview\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz coverage fuzz_0"
+    )]
+    Coverage {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            help = "Fuzzing engine whose corpus and crash files are used: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
+        )]
+        engine: Option<Engine>,
+    },
+    #[command(
+        about = "Minimize the crash found by the desired fuzz test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz minimize fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR..."
+    )]
+    Minimize {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
         )]
         target: String,
         #[arg(required = true, help = "Path to the crash found during fuzzing")]
         crash_file_path: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Path of the minimized crash file (default: <PATH_TO_CRASHFILE>.min)."
+        )]
+        output: Option<String>,
+    },
+    #[command(
+        about = "Convert the crash found by the desired fuzz test into a regression test.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz crash-to-test fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR..."
+    )]
+    CrashToTest {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(required = true, help = "Path to the crash found during fuzzing")]
+        crash_file_path: String,
+        #[arg(
+            short,
+            long,
+            required = false,
+            help = "Name of the generated test function (default: crash_<hash of the crash file>)."
+        )]
+        name: Option<String>,
+    },
+    #[command(
+        about = "Replay the corpus and crashes of the desired fuzz test once, failing on any crash.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and optionally \x1b[92m<INPUT_PATHS>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<INPUT_PATHS>:\x1b[0m Input files or directories of them (default: corpus and crash files of the target found by the engine).\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz replay fuzz_0"
+    )]
+    Replay {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            help = "Fuzzing engine whose corpus and crash files are used: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
+        )]
+        engine: Option<Engine>,
+        #[arg(
+            required = false,
+            help = "Input files or directories of them (default: corpus and crash files of the target found by the engine)"
+        )]
+        input_paths: Vec<String>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "PATH",
+            help = "Write the JUnit XML report of the replay, one testcase per instruction and invariant."
+        )]
+        junit: Option<String>,
+        #[arg(
+            long,
+            required = false,
+            value_name = "PATH",
+            help = "Write the SARIF report of the crashes found during the replay."
+        )]
+        sarif: Option<String>,
+    },
+    #[command(
+        about = "Group the crashes found by the desired fuzz test into buckets of unique bugs.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and optionally \x1b[92m<CRASH_PATHS>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<CRASH_PATHS>:\x1b[0m Crash files or directories of them (default: crash files of the target found by the engine).\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz triage fuzz_0"
+    )]
+    Triage {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            short,
+            long,
+            help = "Fuzzing engine whose corpus and crash files are used: honggfuzz, afl or libfuzzer (default: the engine from the Trident.toml, honggfuzz otherwise)."
+        )]
+        engine: Option<Engine>,
+        #[arg(
+            required = false,
+            help = "Crash files or directories of them (default: crash files of the target found by the engine)"
+        )]
+        crash_paths: Vec<String>,
+    },
+    #[command(
+        about = "Decode the corpus or crash file into the instruction sequence, without executing it.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template (for example fuzz_0).\
+            \n      \x1b[1m\x1b[4m<PATH>:\x1b[0m Path to the corpus or crash file, or to the directory of them.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz decode fuzz_0 trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0 --json"
+    )]
+    Decode {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template (for example fuzz_0)."
+        )]
+        target: String,
+        #[arg(
+            required = true,
+            help = "Path to the corpus or crash file, or to the directory of them"
+        )]
+        path: String,
+        #[arg(long, help = "Print the decoded instruction sequences as JSON.")]
+        json: bool,
     },
 }
 
@@ -108,32 +369,106 @@ pub async fn fuzz(subcmd: FuzzCommand) {
     let commander = Commander::with_root(&Path::new(&root).to_path_buf());
 
     match subcmd {
-        // FuzzCommand::Run_Afl { target } => {
-        //     commander.run_afl(target).await?;
-        // }
-        FuzzCommand::Run_Hfuzz {
-            target,
+        FuzzCommand::Run { args, engine } => {
+            let engine = commander.resolve_engine(engine, &args.target)?;
+            run_fuzzer(&commander, engine, args).await?;
+        }
+        FuzzCommand::Run_All {
+            engine,
+            total_time,
+            target_time,
+            parallel,
             with_exit_code,
         } => {
-            if with_exit_code {
-                commander.run_honggfuzz_with_exit_code(target).await?;
-            } else {
-                commander.run_honggfuzz(target).await?;
-            }
-        }
-        // FuzzCommand::Debug_Afl {
-        //     target,
-        //     crash_file_path,
-        // } => {
-        //     commander.run_afl_debug(target, crash_file_path).await?;
-        // }
-        FuzzCommand::Debug_Hfuzz {
+            commander
+                .run_all(engine, total_time, target_time, parallel, with_exit_code)
+                .await?;
+        }
+        FuzzCommand::Run_Afl(args) => {
+            run_fuzzer(&commander, Engine::Afl, args).await?;
+        }
+        FuzzCommand::Run_Hfuzz(args) => {
+            run_fuzzer(&commander, Engine::Honggfuzz, args).await?;
+        }
+        FuzzCommand::Run_Libfuzzer(args) => {
+            run_fuzzer(&commander, Engine::Libfuzzer, args).await?;
+        }
+        FuzzCommand::Merge_Libfuzzer {
+            target,
+            corpus_paths,
+        } => {
+            commander.run_libfuzzer_merge(target, corpus_paths).await?;
+        }
+        FuzzCommand::Debug { args, engine } => {
+            let engine = commander.resolve_engine(engine, &args.target)?;
+            commander
+                .run_fuzzer_debug(engine, args.target, args.crash_file_path)
+                .await?;
+        }
+        FuzzCommand::Debug_Afl(args) => {
+            commander
+                .run_fuzzer_debug(Engine::Afl, args.target, args.crash_file_path)
+                .await?;
+        }
+        FuzzCommand::Debug_Hfuzz(args) => {
+            commander
+                .run_fuzzer_debug(Engine::Honggfuzz, args.target, args.crash_file_path)
+                .await?;
+        }
+        FuzzCommand::Debug_Libfuzzer(args) => {
+            commander
+                .run_fuzzer_debug(Engine::Libfuzzer, args.target, args.crash_file_path)
+                .await?;
+        }
+        FuzzCommand::Coverage { target, engine } => {
+            let engine = commander.resolve_engine(engine, &target)?;
+            commander.run_coverage(engine, target).await?;
+        }
+        FuzzCommand::Minimize {
+            target,
+            crash_file_path,
+            output,
+        } => {
+            commander
+                .run_minimize(target, crash_file_path, output)
+                .await?;
+        }
+        FuzzCommand::Replay {
+            target,
+            engine,
+            input_paths,
+            junit,
+            sarif,
+        } => {
+            let engine = commander.resolve_engine(engine, &target)?;
+            commander
+                .run_replay(engine, target, input_paths, junit, sarif)
+                .await?;
+        }
+        FuzzCommand::Triage {
+            target,
+            engine,
+            crash_paths,
+        } => {
+            let engine = commander.resolve_engine(engine, &target)?;
+            commander.run_triage(engine, target, crash_paths).await?;
+        }
+        FuzzCommand::Decode { target, path, json } => {
+            commander.run_decode(target, path, json).await?;
+        }
+        FuzzCommand::CrashToTest {
             target,
             crash_file_path,
+            name,
         } => {
-            commander.run_hfuzz_debug(target, crash_file_path).await?;
+            commander
+                .run_crash_to_test(target, crash_file_path, name)
+                .await?;
         }
 
+        FuzzCommand::List { json } => {
+            commander.list_fuzz_targets(json).await?;
+        }
         FuzzCommand::Add => {
             let mut generator = TestGenerator::new_with_root(&root)?;
             generator.add_fuzz_test().await?;
@@ -141,3 +476,28 @@ pub async fn fuzz(subcmd: FuzzCommand) {
         }
     };
 }
+
+#[throws]
+async fn run_fuzzer(commander: &Commander, engine: Engine, args: RunArgs) {
+    commander
+        .run_fuzzer(
+            engine,
+            args.target,
+            args.with_exit_code,
+            args.dashboard,
+            args.junit,
+            args.sarif,
+        )
+        .await?;
+}
+
+/// Parses the `TARGET=SECONDS` time budget of the fuzz target.
+fn parse_target_time(value: &str) -> Result<(String, u64), String> {
+    let (target, seconds) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected TARGET=SECONDS, found `{value}`"))?;
+    let seconds = seconds
+        .parse()
+        .map_err(|_| format!("invalid number of seconds `{seconds}`"))?;
+    Ok((target.to_string(), seconds))
+}
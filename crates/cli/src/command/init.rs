@@ -2,7 +2,7 @@ use std::path::Path;
 
 use anyhow::{bail, Error};
 use fehler::throws;
-use trident_client::___private::TestGenerator;
+use trident_client::___private::{cargo_workspace_root, TestGenerator};
 
 use crate::{_discover, show_howto};
 
@@ -12,11 +12,13 @@ pub const SKIP: &str = "\x1b[33mSkip\x1b[0m";
 
 #[throws]
 pub async fn init(force: bool) {
-    // look for Anchor.toml
+    // look for Anchor.toml, the workspaces of native programs are recognized by the Cargo workspace
     let root = if let Some(r) = _discover(ANCHOR_TOML)? {
         r
+    } else if let Some(r) = cargo_workspace_root() {
+        r.to_string_lossy().to_string()
     } else {
-        bail!("It does not seem that Anchor is initialized because the Anchor.toml file was not found in any parent directory!");
+        bail!("It does not seem that Anchor or Cargo workspace is initialized because neither the Anchor.toml nor the Cargo.toml file was found in any parent directory!");
     };
 
     let mut generator: TestGenerator = TestGenerator::new_with_root(&root)?;
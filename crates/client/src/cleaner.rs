@@ -39,7 +39,8 @@ impl Cleaner {
         };
         self.clean_anchor_target().await?;
         self.clean_hfuzz_target(&root).await?;
-        // self.clean_afl_target(&root).await?;
+        self.clean_afl_target(&root).await?;
+        self.clean_libfuzzer_target(&root).await?;
     }
 
     #[throws]
@@ -60,7 +61,6 @@ impl Cleaner {
     }
 
     #[throws]
-    #[allow(dead_code)]
     async fn clean_afl_target(&self, root: &PathBuf) {
         let afl_target_path = Path::new(root).join(CARGO_TARGET_DIR_DEFAULT_AFL);
         if afl_target_path.exists() {
@@ -72,10 +72,23 @@ impl Cleaner {
             )
         }
     }
+
+    #[throws]
+    async fn clean_libfuzzer_target(&self, root: &PathBuf) {
+        let libfuzzer_target_path = Path::new(root).join(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER);
+        if libfuzzer_target_path.exists() {
+            fs::remove_dir_all(libfuzzer_target_path).await?;
+        } else {
+            println!(
+                "{SKIP} [{}] directory not found",
+                CARGO_TARGET_DIR_DEFAULT_LIBFUZZER
+            )
+        }
+    }
 }
 
-/// Tries to find the root directory with the `Anchor.toml` file.
-/// Throws an error when there is no directory with the `Anchor.toml` file
+/// Tries to find the root directory with the `Trident.toml` or the `Anchor.toml` file.
+/// Throws an error when there is no directory with any of these files
 pub fn discover_root() -> Result<PathBuf, Error> {
     let current_dir = env::current_dir()?;
     let mut dir = Some(current_dir.as_path());
@@ -89,7 +102,7 @@ pub fn discover_root() -> Result<PathBuf, Error> {
                 })?
                 .path();
             if let Some(filename) = path.file_name() {
-                if filename.to_str() == Some(ANCHOR_TOML) {
+                if let Some(TRIDENT_TOML | ANCHOR_TOML) = filename.to_str() {
                     return Ok(PathBuf::from(cwd));
                 }
             }
@@ -1,29 +1,56 @@
-use crate::constants::*;
 use fehler::{throw, throws};
-use std::io::{Read, Write};
-use std::process::Stdio;
-use std::{fs::File, path::Path};
-use tokio::{io::AsyncWriteExt, process::Command};
+use std::io::Write;
+use std::process::{self, Stdio};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
 use trident_fuzz::config::afl::AflSeed;
 use trident_fuzz::config::Config;
 
-use super::{Commander, Error};
+use super::engine::{engine_rustflags, FuzzingEngine};
+use super::Error;
 
-impl Commander {
-    /// Runs fuzzer on the given target.
-    #[throws]
-    pub async fn run_afl(&self, target: String) {
-        let config = Config::new();
+/// Name of the AFL++ fuzzer instance, i.e. the directory within the AFL output directory.
+const AFL_FUZZER_NAME: &str = "default";
+
+/// AFL++ run through the `cargo afl` subcommand.
+pub(super) struct AflEngine {
+    root: PathBuf,
+    config: Config,
+    /// Seconds replacing the `seconds` option of the Trident Manifest.
+    time_budget: Option<u64>,
+}
+
+impl AflEngine {
+    pub(super) fn new(root: &Path, config: Config) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config,
+            time_budget: None,
+        }
+    }
+
+    /// AFL output directory of the target, i.e. `<afl_workspace_out>/<target>`.
+    fn output_dir(&self, target: &str) -> PathBuf {
+        Path::new(&self.config.get_afl_workspace_out()).join(target)
+    }
+}
 
-        let build_args = config.get_afl_build_args();
-        let fuzz_args = config.get_afl_fuzz_args();
+impl FuzzingEngine for AflEngine {
+    fn name(&self) -> &'static str {
+        "afl"
+    }
 
-        let mut target_path = config.get_afl_target_path();
-        target_path.push_str(&target);
+    #[throws]
+    fn prepare(&self, _target: &str) {
+        // AFL creates only the output directory of the target, not its parents
+        std::fs::create_dir_all(self.config.get_afl_workspace_out())?;
 
-        let afl_workspace_in = config.get_afl_workspace_in();
+        let afl_workspace_in = self.config.get_afl_workspace_in();
         let afl_workspace_in_path = Path::new(&afl_workspace_in);
-        let initial_seeds = config.get_initial_seed();
+        let initial_seeds = self.config.get_initial_seed();
 
         if !afl_workspace_in_path.exists() {
             std::fs::create_dir_all(afl_workspace_in_path)?;
@@ -38,67 +65,137 @@ impl Commander {
         } else {
             throw!(Error::BadAFLWorkspace)
         }
+    }
 
-        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
-
-        rustflags.push_str("--cfg afl");
+    fn set_time_budget(&mut self, seconds: u64) {
+        self.time_budget = Some(seconds);
+    }
 
-        let mut child = Command::new("cargo")
-            .env("RUSTFLAGS", rustflags)
+    #[throws]
+    fn build_command(&self, target: &str) -> Option<Command> {
+        let mut command = Command::new("cargo");
+        command
+            .env("RUSTFLAGS", engine_rustflags("afl"))
             .arg("afl")
             .arg("build")
-            .args(build_args)
-            .args(["--bin", &target])
-            .spawn()?;
-        Self::handle_child(&mut child).await?;
-
-        let mut child = Command::new("cargo")
-            .arg("afl")
-            .arg("fuzz")
-            .args(fuzz_args)
-            .arg(&target_path)
-            .spawn()?;
-
-        Self::handle_child(&mut child).await?;
+            .args(self.config.get_afl_build_args())
+            .args(["--bin", target]);
+        Some(command)
     }
 
-    /// Runs fuzzer on the given target.
     #[throws]
-    pub async fn run_afl_debug(&self, target: String, crash_file_path: String) {
-        let config = Config::new();
-
-        let crash_file = self.root.join(crash_file_path);
-
-        let cargo_target_dir = config.get_afl_cargo_build_dir();
+    fn fuzz_command(&self, target: &str, dashboard: bool) -> Command {
+        let mut target_path = self.config.get_afl_target_path();
+        target_path.push_str(target);
 
-        if !crash_file.try_exists()? {
-            println!("{ERROR} The crash file [{:?}] not found", crash_file);
-            throw!(Error::CrashFileNotFound);
+        // every target has its own output directory, so the crashes and the corpus of the targets are not mixed
+        let output_dir = self.output_dir(target);
+        let mut fuzz_args = with_afl_arg(
+            self.config.get_afl_fuzz_args(),
+            "-o",
+            &output_dir.to_string_lossy(),
+        );
+        if let Some(seconds) = self.time_budget {
+            fuzz_args = with_afl_arg(fuzz_args, "-V", &seconds.to_string());
         }
 
-        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
-
-        rustflags.push_str("--cfg afl");
+        let mut command = Command::new("cargo");
+        command
+            .arg("afl")
+            .arg("fuzz")
+            .args(fuzz_args)
+            .arg(&target_path);
+        if dashboard {
+            // the output is redirected into the log file, the status screen would only flood it
+            command.env("AFL_NO_UI", "1");
+        }
+        command
+    }
 
-        let mut file = File::open(crash_file)?;
-        let mut file_contents = Vec::new();
-        file.read_to_end(&mut file_contents)?;
+    #[throws]
+    fn debug(&self, target: &str, crash_file: &Path) {
+        let cargo_target_dir = self.config.get_afl_cargo_build_dir();
+        let file_contents = std::fs::read(crash_file)?;
 
-        // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
-        let mut child = Command::new("cargo")
-            .env("RUSTFLAGS", rustflags)
+        let mut child = process::Command::new("cargo")
+            .env("RUSTFLAGS", engine_rustflags("afl"))
             .arg("afl")
             .arg("run")
             .args(["--target-dir", &cargo_target_dir])
-            .args(["--bin", &target])
+            .args(["--bin", target])
             .stdin(Stdio::piped())
             .spawn()?;
 
         if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(&file_contents).await?;
+            stdin.write_all(&file_contents)?;
         }
-        child.wait().await?;
+        child.wait()?;
     }
+
+    fn crash_dir(&self, target: &str) -> PathBuf {
+        self.output_dir(target)
+            .join(AFL_FUZZER_NAME)
+            .join("crashes")
+    }
+
+    fn crash_files(&self, target: &str) -> Vec<PathBuf> {
+        get_afl_crash_files(&self.crash_dir(target))
+    }
+
+    fn corpus_dir(&self, target: &str) -> Option<PathBuf> {
+        let corpus_dir = self.output_dir(target).join(AFL_FUZZER_NAME).join("queue");
+        corpus_dir.is_dir().then_some(corpus_dir)
+    }
+
+    fn log_file(&self, target: &str) -> PathBuf {
+        self.root
+            .join(self.config.get_afl_workspace_out())
+            .join(format!("{target}.log"))
+    }
+
+    fn crash_dir_hint(&self) -> &'static str {
+        "change the AFL output directory using the afl_workspace_out option within the Trident.toml"
+    }
+}
+
+/// Replaces the option within the AFL fuzz arguments with the given value. The arguments are scanned as a list,
+/// the same way as the Honggfuzz arguments, so the flags without a value (e.g. `-D`) are kept as they are.
+fn with_afl_arg(fuzz_args: Vec<String>, option: &str, value: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut fuzz_args = fuzz_args.into_iter();
+    while let Some(arg) = fuzz_args.next() {
+        match arg.strip_prefix(option) {
+            // the value of the replaced option follows it, e.g. `-V 600`
+            Some("") => {
+                fuzz_args.next();
+            }
+            // the value is attached to the replaced option, e.g. `-V600`
+            Some(_) => {}
+            None => args.push(arg),
+        }
+    }
+    args.extend([option.to_string(), value.to_string()]);
+    args
+}
+
+/// Returns the crash files within the AFL crash directory, i.e. the `id:...` files (the directory contains also a README).
+fn get_afl_crash_files(crash_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(crash_dir) else {
+        return vec![];
+    };
+    let mut crash_files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| name.starts_with("id:"))
+        })
+        .collect();
+    crash_files.sort();
+    crash_files
 }
 
 fn create_seed_file(path: &Path, seed: &AflSeed) -> std::io::Result<()> {
@@ -116,3 +213,96 @@ fn create_seed_file(path: &Path, seed: &AflSeed) -> std::io::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_afl_crash_files() {
+        let dir = std::env::temp_dir().join(format!("trident-afl-crashes-{}", process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(get_afl_crash_files(&dir).is_empty());
+
+        std::fs::write(
+            dir.join("README.txt"),
+            "Command line used to find this crash",
+        )
+        .unwrap();
+        std::fs::write(dir.join("id:000001,sig:06,src:000000,op:havoc,rep:2"), [1]).unwrap();
+        std::fs::write(dir.join("id:000000,sig:06,src:000000,op:flip1,pos:0"), [0]).unwrap();
+        let crash_files = get_afl_crash_files(&dir);
+        assert_eq!(
+            crash_files,
+            vec![
+                dir.join("id:000000,sig:06,src:000000,op:flip1,pos:0"),
+                dir.join("id:000001,sig:06,src:000000,op:havoc,rep:2")
+            ]
+        );
+        assert!(get_afl_crash_files(&dir.join("missing")).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_with_afl_arg() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            with_afl_arg(args(&["-i", "in", "-V", "600", "-o", "out"]), "-V", "60"),
+            args(&["-i", "in", "-o", "out", "-V", "60"])
+        );
+        assert_eq!(
+            with_afl_arg(args(&["-i", "in"]), "-V", "60"),
+            args(&["-i", "in", "-V", "60"])
+        );
+        assert_eq!(
+            with_afl_arg(args(&["-i", "in", "-o", "out"]), "-o", "out/fuzz_0"),
+            args(&["-i", "in", "-o", "out/fuzz_0"])
+        );
+        // the flag without a value does not shift the following options
+        assert_eq!(
+            with_afl_arg(
+                args(&["-D", "-i", "in", "-V", "600", "-o", "out"]),
+                "-V",
+                "60"
+            ),
+            args(&["-D", "-i", "in", "-o", "out", "-V", "60"])
+        );
+        assert_eq!(
+            with_afl_arg(args(&["-i", "in", "-D", "-o", "out"]), "-o", "out/fuzz_0"),
+            args(&["-i", "in", "-D", "-o", "out/fuzz_0"])
+        );
+        assert_eq!(
+            with_afl_arg(args(&["-V600", "-D"]), "-V", "60"),
+            args(&["-D", "-V", "60"])
+        );
+    }
+
+    #[test]
+    fn test_output_dir_per_target() {
+        use trident_fuzz::config::{
+            afl::_Afl, fuzz::_Fuzz, honggfuzz::_HonggFuzz, libfuzzer::_LibFuzzer,
+        };
+
+        let config = Config {
+            honggfuzz: _HonggFuzz::default().into(),
+            afl: _Afl::default().into(),
+            libfuzzer: _LibFuzzer::default().into(),
+            fuzz: _Fuzz::default().try_into().unwrap(),
+        };
+        let engine = AflEngine::new(Path::new("."), config);
+        let out = PathBuf::from(engine.config.get_afl_workspace_out());
+
+        assert_eq!(
+            engine.crash_dir("fuzz_0"),
+            out.join("fuzz_0").join("default").join("crashes")
+        );
+        assert_ne!(engine.crash_dir("fuzz_0"), engine.crash_dir("fuzz_1"));
+
+        let command = engine.fuzz_command("fuzz_0", false).unwrap();
+        let args: Vec<_> = command.as_std().get_args().collect();
+        let output = args.iter().position(|arg| *arg == "-o").unwrap();
+        assert_eq!(args[output + 1], out.join("fuzz_0").as_os_str());
+    }
+}
@@ -0,0 +1,163 @@
+use fehler::{throw, throws};
+use std::path::PathBuf;
+use tokio::process::Command;
+use trident_fuzz::replay::REPLAY_INPUTS_ENV;
+
+use crate::constants::*;
+use crate::utils::program_packages;
+
+use super::{Commander, Engine, Error};
+
+impl Commander {
+    /// Builds the fuzz target with the LLVM source-based coverage instrumentation, replays the corpus
+    /// and crash files of the target found by the given fuzzing engine and writes the lcov and HTML
+    /// coverage reports restricted to the program crates.
+    #[throws]
+    pub async fn run_coverage(&self, engine: Engine, target: String) {
+        let llvm_tools = llvm_tools_dir().await?;
+
+        let (mut inputs, corpus_dir) = self.fuzzer_inputs(engine, &target)?;
+        inputs.extend(corpus_dir);
+        if inputs.is_empty() {
+            println!("{ERROR} No corpus or crash files of the {target} fuzz target found, run the fuzzer first");
+            throw!(Error::CoverageFailed);
+        }
+
+        let coverage_dir = self.root.join(COVERAGE_DIR_DEFAULT);
+        let cargo_target_dir = coverage_dir.join("target");
+        let output_dir = coverage_dir.join(&target);
+        let profraw_dir = output_dir.join("profraw");
+        if output_dir.exists() {
+            std::fs::remove_dir_all(&output_dir)?;
+        }
+        std::fs::create_dir_all(&profraw_dir)?;
+
+        // the fuzz test built without a fuzzer replays the inputs provided through the environment variable
+        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+        rustflags.push_str(" -C instrument-coverage");
+        let mut child = Command::new("cargo")
+            .env("RUSTFLAGS", rustflags)
+            .env("CARGO_TARGET_DIR", &cargo_target_dir)
+            .args(["build", "--bin", &target])
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+
+        let binary = cargo_target_dir.join("debug").join(&target);
+        let status = Command::new(&binary)
+            .env(REPLAY_INPUTS_ENV, std::env::join_paths(&inputs).unwrap())
+            .env("LLVM_PROFILE_FILE", profraw_dir.join("%p-%m.profraw"))
+            .status()
+            .await?;
+        if !status.success() {
+            println!("{ERROR} Some of the inputs crashed, the coverage might be incomplete");
+        }
+
+        let profdata = output_dir.join("coverage.profdata");
+        let profraw_files = std::fs::read_dir(&profraw_dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path());
+        run_llvm_tool(
+            Command::new(llvm_tools.join("llvm-profdata"))
+                .args(["merge", "-sparse", "-o"])
+                .arg(&profdata)
+                .args(profraw_files),
+        )
+        .await?;
+
+        // restrict the reports to the sources of the program crates
+        let sources: Vec<PathBuf> = program_packages()
+            .filter_map(|package| {
+                package
+                    .manifest_path
+                    .parent()
+                    .map(|dir| dir.join("src").into_std_path_buf())
+            })
+            .collect();
+
+        let lcov = run_llvm_tool(
+            Command::new(llvm_tools.join("llvm-cov"))
+                .args(["export", "-format=lcov"])
+                .arg(format!("-instr-profile={}", profdata.display()))
+                .arg(&binary)
+                .args(&sources),
+        )
+        .await?;
+        let lcov_path = output_dir.join("lcov.info");
+        std::fs::write(&lcov_path, lcov)?;
+
+        let html_dir = output_dir.join("html");
+        run_llvm_tool(
+            Command::new(llvm_tools.join("llvm-cov"))
+                .args(["show", "-format=html", "-show-line-counts-or-regions"])
+                .arg(format!("-instr-profile={}", profdata.display()))
+                .arg(format!("-output-dir={}", html_dir.display()))
+                .arg(&binary)
+                .args(&sources),
+        )
+        .await?;
+
+        println!(
+            "{FINISH} Coverage of the {target} fuzz target written to {} and {}",
+            lcov_path.display(),
+            html_dir.join("index.html").display()
+        );
+    }
+}
+
+/// Runs the LLVM tool and returns its standard output.
+#[throws]
+async fn run_llvm_tool(command: &mut Command) -> Vec<u8> {
+    let output = command.output().await?;
+    if !output.status.success() {
+        println!("{ERROR} {}", String::from_utf8_lossy(&output.stderr));
+        throw!(Error::CoverageFailed);
+    }
+    output.stdout
+}
+
+/// Returns the directory of the LLVM tools shipped with the active Rust toolchain
+/// (the `llvm-tools-preview` component).
+#[throws]
+async fn llvm_tools_dir() -> PathBuf {
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .await?;
+    let version = Command::new("rustc").arg("-vV").output().await?;
+    let version = String::from_utf8(version.stdout)?;
+    let Some(host) = parse_host(&version) else {
+        throw!(Error::LlvmToolsNotFound);
+    };
+
+    let dir = PathBuf::from(String::from_utf8(sysroot.stdout)?.trim())
+        .join("lib")
+        .join("rustlib")
+        .join(host)
+        .join("bin");
+    if !dir.join("llvm-profdata").exists() || !dir.join("llvm-cov").exists() {
+        throw!(Error::LlvmToolsNotFound);
+    }
+    dir
+}
+
+fn parse_host(rustc_version: &str) -> Option<&str> {
+    rustc_version
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_host() {
+        let version = "rustc 1.81.0 (eeb90cda1 2024-09-04)\n\
+                       binary: rustc\n\
+                       host: x86_64-unknown-linux-gnu\n\
+                       release: 1.81.0\n";
+        assert_eq!(parse_host(version), Some("x86_64-unknown-linux-gnu"));
+        assert_eq!(parse_host("rustc 1.81.0"), None);
+    }
+}
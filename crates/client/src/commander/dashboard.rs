@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use prettytable::{row, Table};
+use trident_fuzz::fuzz_stats::FuzzingStatistics;
+
+/// Refresh interval of the live dashboard.
+pub(super) const DASHBOARD_REFRESH: Duration = Duration::from_millis(1000);
+/// Number of the most frequent errors shown on the dashboard.
+const TOP_ERRORS: usize = 5;
+
+/// Live terminal dashboard showing the Trident statistics of a running fuzzing session.
+/// The statistics are read from the stats channel, the output of the fuzzer itself is
+/// redirected into the log file.
+pub(super) struct Dashboard {
+    target: String,
+    log_file: PathBuf,
+    started: Instant,
+    previous: Option<(Instant, u64)>,
+    iterations_per_sec: f64,
+}
+
+impl Dashboard {
+    pub(super) fn new(target: &str, log_file: PathBuf) -> Self {
+        Self {
+            target: target.to_string(),
+            log_file,
+            started: Instant::now(),
+            previous: None,
+            iterations_per_sec: 0.0,
+        }
+    }
+
+    /// Updates the iteration rate from the latest statistics and redraws the dashboard.
+    pub(super) fn refresh(&mut self, stats: &FuzzingStatistics) {
+        let now = Instant::now();
+        if let Some((previous_at, previous_iterations)) = self.previous {
+            let elapsed = now.duration_since(previous_at).as_secs_f64();
+            if elapsed > 0.0 {
+                self.iterations_per_sec =
+                    stats.iterations.saturating_sub(previous_iterations) as f64 / elapsed;
+            }
+        }
+        self.previous = Some((now, stats.iterations));
+
+        // clear the screen and move the cursor to the top left corner
+        print!(
+            "\x1b[2J\x1b[H{}",
+            self.render(stats, now.duration_since(self.started))
+        );
+    }
+
+    fn render(&self, stats: &FuzzingStatistics, elapsed: Duration) -> String {
+        let mut output = format!(
+            "\x1b[1mTrident\x1b[0m fuzzing \x1b[92m{}\x1b[0m | running {} | {} iterations | {:.1} iterations/s\n",
+            self.target,
+            format_duration(elapsed),
+            stats.iterations,
+            self.iterations_per_sec,
+        );
+
+        match &stats.last_crash {
+            Some(crash) => output.push_str(&format!(
+                "\x1b[31mCrashes: {}\x1b[0m | latest in {} ({} ago)\n\n",
+                stats.crashes,
+                crash.instruction,
+                format_duration(since(crash.timestamp_ms)),
+            )),
+            None => output.push_str("Crashes: 0\n\n"),
+        }
+
+        let mut instructions: Vec<_> = stats.instructions.iter().collect();
+        instructions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut table = Table::new();
+        table.add_row(row![
+            "Instruction",
+            "Invoked Total",
+            "Ix Success",
+            "Check Failed",
+            "Ix Failed"
+        ]);
+        for (instruction, stats) in instructions {
+            table.add_row(row![
+                instruction,
+                stats.invoked,
+                stats.successful,
+                stats.failed_check,
+                stats.failed,
+            ]);
+        }
+        output.push_str(&table.to_string());
+
+        let top_errors = stats.top_errors(TOP_ERRORS);
+        if !top_errors.is_empty() {
+            let mut table = Table::new();
+            table.add_row(row!["Top Errors", "Count"]);
+            for (error, count) in top_errors {
+                table.add_row(row![error, count]);
+            }
+            output.push('\n');
+            output.push_str(&table.to_string());
+        }
+
+        output.push_str(&format!(
+            "\nFuzzer output is written to {}. Press Ctrl+C to stop fuzzing.\n",
+            self.log_file.display()
+        ));
+        output
+    }
+}
+
+fn since(timestamp_ms: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    Duration::from_millis(now.saturating_sub(timestamp_ms))
+}
+
+pub(super) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_iterations(2);
+        stats.increase_invoked("Deposit".to_string());
+        stats.increase_failed_with_error("Deposit".to_string(), "AccountNotFound".to_string());
+        stats.increase_invoked("Withdraw".to_string());
+        stats.increase_successful("Withdraw".to_string());
+        stats.increase_crashes("Withdraw".to_string());
+
+        let dashboard = Dashboard::new("fuzz_0", PathBuf::from("fuzz_0.log"));
+        let output = dashboard.render(&stats, Duration::from_secs(3725));
+
+        assert!(output.contains("running 01:02:05 | 1 iterations"));
+        assert!(output.contains("Crashes: 1\x1b[0m | latest in Withdraw"));
+        assert!(output.find("| Deposit").unwrap() < output.find("| Withdraw").unwrap());
+        assert!(output.contains("| AccountNotFound | 1     |"));
+        assert!(output.contains("fuzz_0.log"));
+    }
+}
@@ -0,0 +1,266 @@
+use fehler::{throw, throws};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::{Instant, SystemTime};
+use tokio::process::Command;
+use trident_fuzz::ci_report::CiReport;
+use trident_fuzz::config::Config;
+use trident_fuzz::fuzz_report::FuzzingReport;
+
+use crate::constants::*;
+
+use super::afl::AflEngine;
+use super::honggfuzz::HonggfuzzEngine;
+use super::libfuzzer::LibFuzzerEngine;
+use super::{Commander, Error};
+
+/// Fuzzing engines the fuzz tests can be built for and run with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    Honggfuzz,
+    Afl,
+    Libfuzzer,
+}
+
+impl Engine {
+    pub const ALL: [Engine; 3] = [Engine::Honggfuzz, Engine::Afl, Engine::Libfuzzer];
+
+    /// Name of the engine, the same as the `--cfg` the `fuzz_trident!` macro branches on.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::Honggfuzz => "honggfuzz",
+            Engine::Afl => "afl",
+            Engine::Libfuzzer => "libfuzzer",
+        }
+    }
+
+    pub(super) fn create(&self, root: &Path, config: Config) -> Box<dyn FuzzingEngine> {
+        match self {
+            Engine::Honggfuzz => Box::new(HonggfuzzEngine::new(root, config)),
+            Engine::Afl => Box::new(AflEngine::new(root, config)),
+            Engine::Libfuzzer => Box::new(LibFuzzerEngine::new(root, config)),
+        }
+    }
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.to_lowercase();
+        match name.as_str() {
+            "hfuzz" => return Ok(Engine::Honggfuzz),
+            "afl++" => return Ok(Engine::Afl),
+            _ => {}
+        }
+        Engine::ALL
+            .into_iter()
+            .find(|engine| engine.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Engine::ALL.iter().map(Engine::name).collect();
+                format!(
+                    "unknown fuzzing engine `{name}`, possible values: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Building and running of the fuzz tests with a particular fuzzing engine, together with the location
+/// of the crash and corpus files the engine writes. The session itself (the statistics, the dashboard,
+/// the reports and the exit code) is driven by the `Commander` the same way for every engine.
+pub(super) trait FuzzingEngine {
+    /// Name of the engine within the fuzzing reports.
+    fn name(&self) -> &'static str;
+
+    /// Prepares the workspace of the engine before the fuzz target is built, e.g. the initial seeds.
+    #[throws]
+    fn prepare(&self, _target: &str) {}
+
+    /// Limits the fuzzing command to the given number of seconds, overriding the limit from the Trident Manifest.
+    fn set_time_budget(&mut self, seconds: u64);
+
+    /// Command building the fuzz target, `None` if the fuzz target is built by the fuzzing command itself.
+    fn build_command(&self, target: &str) -> Result<Option<Command>, Error>;
+
+    /// Command fuzzing the target. With the dashboard, the output of the engine is written into
+    /// the log file, so the engine should not draw its own status screen.
+    fn fuzz_command(&self, target: &str, dashboard: bool) -> Result<Command, Error>;
+
+    /// Runs the fuzz target once with the crash file to show where the crash file found an issue.
+    fn debug(&self, target: &str, crash_file: &Path) -> Result<(), Error>;
+
+    /// Directory of the crash files of the target.
+    fn crash_dir(&self, target: &str) -> PathBuf;
+
+    /// Crash files found by the engine for the target.
+    fn crash_files(&self, target: &str) -> Vec<PathBuf>;
+
+    /// Directory of the corpus kept by the engine for the target, if it exists.
+    fn corpus_dir(&self, target: &str) -> Option<PathBuf>;
+
+    /// Log file of the fuzzing command while the live dashboard is displayed.
+    fn log_file(&self, target: &str) -> PathBuf;
+
+    /// Hint how to change the crash directory, shown if it contains crash files from previous runs.
+    fn crash_dir_hint(&self) -> &'static str;
+}
+
+/// RUSTFLAGS of the fuzz target built for the engine with the given `--cfg`.
+pub(super) fn engine_rustflags(cfg: &str) -> String {
+    let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+    rustflags.push_str(" --cfg ");
+    rustflags.push_str(cfg);
+    rustflags
+}
+
+/// Returns the fuzzing engine configured for the target within the Trident Manifest, Honggfuzz by default.
+#[throws]
+pub(super) fn configured_engine(config: &Config, target: &str) -> Engine {
+    match config.get_engine(target) {
+        Some(name) => name.parse().map_err(Error::InvalidEngine)?,
+        None => Engine::Honggfuzz,
+    }
+}
+
+impl Commander {
+    /// Returns the given fuzzing engine, or the engine configured for the target if none is given.
+    #[throws]
+    pub fn resolve_engine(&self, engine: Option<Engine>, target: &str) -> Engine {
+        match engine {
+            Some(engine) => engine,
+            None => configured_engine(&Config::load()?, target)?,
+        }
+    }
+
+    /// Runs the fuzzing engine on the given target, optionally with exit code, with the live dashboard
+    /// and with the JUnit and SARIF reports of the session.
+    #[throws]
+    pub async fn run_fuzzer(
+        &self,
+        engine: Engine,
+        target: String,
+        with_exit_code: bool,
+        dashboard: bool,
+        junit: Option<String>,
+        sarif: Option<String>,
+    ) {
+        let config = Config::load()?;
+        let fuzzer = engine.create(&self.root, config.clone());
+
+        let crash_dir = fuzzer.crash_dir(&target);
+        if with_exit_code && !fuzzer.crash_files(&target).is_empty() {
+            println!("{ERROR} The crash directory {} already contains crash files from previous runs. \n\nTo run Trident fuzzer with exit code, you must either (backup and) remove the old crash files or alternatively {}", crash_dir.to_string_lossy(), fuzzer.crash_dir_hint());
+            process::exit(1);
+        }
+
+        fuzzer.prepare(&target)?;
+        if let Some(mut build) = fuzzer.build_command(&target)? {
+            let mut child = build.spawn()?;
+            Self::handle_child(&mut child).await?;
+        }
+        let mut command = fuzzer.fuzz_command(&target, dashboard)?;
+
+        // the dashboard and the CI reports are created from the statistics, so they enforce them
+        let with_reports = junit.is_some() || sarif.is_some();
+        match config.get_fuzzing_with_stats() || dashboard || with_reports {
+            true => {
+                let started_at = SystemTime::now();
+                let start = Instant::now();
+                let log_file = dashboard.then(|| fuzzer.log_file(&target));
+                let stats = Self::spawn_with_stats(&mut command, &target, log_file).await?;
+
+                let crash_files = fuzzer.crash_files(&target);
+                let report = FuzzingReport::new(
+                    &target,
+                    fuzzer.name(),
+                    started_at,
+                    start.elapsed(),
+                    &stats,
+                    &crash_files,
+                );
+                Self::write_report(&config, &report);
+
+                if with_reports {
+                    // the crash files are replayed to find out which instructions failed and how
+                    let results = match crash_files.is_empty() {
+                        true => vec![],
+                        false => self.replay_inputs(&target, &crash_files, false).await?.1,
+                    };
+                    let report = CiReport::new(&target, stats, &results);
+                    self.write_ci_reports(&report, junit, sarif)?;
+                }
+            }
+            false => {
+                let mut child = command.spawn()?;
+                Self::handle_child(&mut child).await?;
+            }
+        }
+
+        if with_exit_code && !fuzzer.crash_files(&target).is_empty() {
+            println!(
+                "The crash directory {} contains new fuzz test crashes. Exiting!",
+                crash_dir.to_string_lossy()
+            );
+            process::exit(99);
+        }
+    }
+
+    /// Runs the given target built for the fuzzing engine once with the crash file.
+    #[throws]
+    pub async fn run_fuzzer_debug(&self, engine: Engine, target: String, crash_file_path: String) {
+        let crash_file = self.root.join(crash_file_path);
+
+        if !crash_file.try_exists()? {
+            println!("{ERROR} The crash file [{:?}] not found", crash_file);
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let fuzzer = engine.create(&self.root, Config::load()?);
+        if let Some(mut build) = fuzzer.build_command(&target)? {
+            let mut child = build.spawn()?;
+            Self::handle_child(&mut child).await?;
+        }
+        fuzzer.debug(&target, &crash_file)?;
+    }
+
+    /// Returns the crash files and the corpus directory (if it exists) of the engine workspace of the target.
+    #[throws]
+    pub(super) fn fuzzer_inputs(
+        &self,
+        engine: Engine,
+        target: &str,
+    ) -> (Vec<PathBuf>, Option<PathBuf>) {
+        let fuzzer = engine.create(&self.root, Config::load()?);
+        (fuzzer.crash_files(target), fuzzer.corpus_dir(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_from_str() {
+        for engine in Engine::ALL {
+            assert_eq!(engine.name().parse::<Engine>(), Ok(engine));
+        }
+        assert_eq!("HFUZZ".parse::<Engine>(), Ok(Engine::Honggfuzz));
+        assert_eq!("afl++".parse::<Engine>(), Ok(Engine::Afl));
+        assert_eq!(
+            "jazzer".parse::<Engine>(),
+            Err(
+                "unknown fuzzing engine `jazzer`, possible values: honggfuzz, afl, libfuzzer"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -1,29 +1,28 @@
-use fehler::{throw, throws};
-use std::process;
-use std::{os::unix::process::CommandExt, process::Stdio};
+use fehler::throws;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use trident_fuzz::config::honggfuzz::EnvVariable;
 use trident_fuzz::config::Config;
 
-use crate::constants::*;
+use super::engine::{engine_rustflags, FuzzingEngine};
+use super::{get_crash_dir_and_ext, get_crash_files, Error};
 
-use super::{get_crash_dir_and_ext, get_crash_files, Commander, Error};
-
-impl Commander {
-    /// Runs fuzzer on the given target with exit code option.
-    #[throws]
-    pub async fn run_honggfuzz_with_exit_code(&self, target: String) {
-        let config = Config::new();
+/// Honggfuzz run through the `cargo hfuzz` subcommand, which also builds the fuzz target.
+pub(super) struct HonggfuzzEngine {
+    root: PathBuf,
+    /// Run arguments from the Trident Manifest followed by the `HFUZZ_RUN_ARGS` environment variable.
+    fuzz_args: String,
+    cargo_target_dir: String,
+    hfuzz_workspace: String,
+}
 
+impl HonggfuzzEngine {
+    pub(super) fn new(root: &Path, config: Config) -> Self {
         // obtain hfuzz_run_args from env variable, this variable can contain multiple
         // arguments so we need to parse the variable content.
         let hfuzz_run_args = std::env::var("HFUZZ_RUN_ARGS").unwrap_or_default();
-
-        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
-
-        rustflags.push_str("--cfg honggfuzz");
-
-        let mut fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
+        let fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
 
         let cargo_target_dir = std::env::var("CARGO_TARGET_DIR")
             .unwrap_or_else(|_| config.get_env_arg(&EnvVariable::CargoTargetDir));
@@ -31,128 +30,54 @@ impl Commander {
         let hfuzz_workspace = std::env::var("HFUZZ_WORKSPACE")
             .unwrap_or_else(|_| config.get_env_arg(&EnvVariable::HfuzzWorkspace));
 
-        let (crash_dir, ext) =
-            get_crash_dir_and_ext(&self.root, &target, &fuzz_args, &hfuzz_workspace);
-
-        if let Ok(crash_files) = get_crash_files(&crash_dir, &ext) {
-            if !crash_files.is_empty() {
-                println!("{ERROR} The crash directory {} already contains crash files from previous runs. \n\nTo run Trident fuzzer with exit code, you must either (backup and) remove the old crash files or alternatively change the crash folder using for example the --crashdir option and the HFUZZ_RUN_ARGS env variable such as:\nHFUZZ_RUN_ARGS=\"--crashdir ./new_crash_dir\"", crash_dir.to_string_lossy());
-                process::exit(1);
-            }
-        }
-
-        match config.get_fuzzing_with_stats() {
-            true => {
-                // enforce keep output to be true
-                fuzz_args.push_str("--keep_output");
-                let mut child = Command::new("cargo")
-                    .env("HFUZZ_RUN_ARGS", fuzz_args)
-                    .env("CARGO_TARGET_DIR", cargo_target_dir)
-                    .env("HFUZZ_WORKSPACE", hfuzz_workspace)
-                    .env("RUSTFLAGS", rustflags)
-                    .arg("hfuzz")
-                    .arg("run")
-                    .arg(target)
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                Self::handle_child_with_stats(&mut child).await?;
-            }
-            false => {
-                let mut child = Command::new("cargo")
-                    .env("HFUZZ_RUN_ARGS", fuzz_args)
-                    .env("CARGO_TARGET_DIR", cargo_target_dir)
-                    .env("HFUZZ_WORKSPACE", hfuzz_workspace)
-                    .env("RUSTFLAGS", rustflags)
-                    .arg("hfuzz")
-                    .arg("run")
-                    .arg(target)
-                    .spawn()?;
-                Self::handle_child(&mut child).await?;
-            }
-        }
-
-        if let Ok(crash_files) = get_crash_files(&crash_dir, &ext) {
-            if !crash_files.is_empty() {
-                println!(
-                    "The crash directory {} contains new fuzz test crashes. Exiting!",
-                    crash_dir.to_string_lossy()
-                );
-                process::exit(99);
-            }
+        Self {
+            root: root.to_path_buf(),
+            fuzz_args,
+            cargo_target_dir,
+            hfuzz_workspace,
         }
     }
 
-    /// Runs fuzzer on the given target.
-    #[throws]
-    pub async fn run_honggfuzz(&self, target: String) {
-        let config = Config::new();
-
-        let hfuzz_run_args = std::env::var("HFUZZ_RUN_ARGS").unwrap_or_default();
-
-        let cargo_target_dir = std::env::var("CARGO_TARGET_DIR")
-            .unwrap_or_else(|_| config.get_env_arg(&EnvVariable::CargoTargetDir));
-        let hfuzz_workspace = std::env::var("HFUZZ_WORKSPACE")
-            .unwrap_or_else(|_| config.get_env_arg(&EnvVariable::HfuzzWorkspace));
-
-        let mut fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
-
-        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
-
-        rustflags.push_str("--cfg honggfuzz");
-
-        match config.get_fuzzing_with_stats() {
-            true => {
-                // enforce keep output to be true
-                fuzz_args.push_str("--keep_output");
-                let mut child = Command::new("cargo")
-                    .env("HFUZZ_RUN_ARGS", fuzz_args)
-                    .env("CARGO_TARGET_DIR", cargo_target_dir)
-                    .env("HFUZZ_WORKSPACE", hfuzz_workspace)
-                    .env("RUSTFLAGS", rustflags)
-                    .arg("hfuzz")
-                    .arg("run")
-                    .arg(target)
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                Self::handle_child_with_stats(&mut child).await?;
-            }
-            false => {
-                let mut child = Command::new("cargo")
-                    .env("HFUZZ_RUN_ARGS", fuzz_args)
-                    .env("CARGO_TARGET_DIR", cargo_target_dir)
-                    .env("HFUZZ_WORKSPACE", hfuzz_workspace)
-                    .env("RUSTFLAGS", rustflags)
-                    .arg("hfuzz")
-                    .arg("run")
-                    .arg(target)
-                    .spawn()?;
-                Self::handle_child(&mut child).await?;
-            }
-        }
+    fn crash_dir_and_ext(&self, target: &str) -> (PathBuf, String) {
+        get_crash_dir_and_ext(&self.root, target, &self.fuzz_args, &self.hfuzz_workspace)
     }
-    /// Runs fuzzer on the given target.
-    #[throws]
-    pub async fn run_hfuzz_debug(&self, target: String, crash_file_path: String) {
-        let config = Config::new();
-
-        let crash_file = self.root.join(crash_file_path);
+}
 
-        if !crash_file.try_exists()? {
-            println!("{ERROR} The crash file [{:?}] not found", crash_file);
-            throw!(Error::CrashFileNotFound);
-        }
+impl FuzzingEngine for HonggfuzzEngine {
+    fn name(&self) -> &'static str {
+        "honggfuzz"
+    }
 
-        let cargo_target_dir = std::env::var("CARGO_TARGET_DIR")
-            .unwrap_or_else(|_| config.get_env_arg(&EnvVariable::CargoTargetDir));
+    fn set_time_budget(&mut self, seconds: u64) {
+        // Honggfuzz takes the last occurrence of the option
+        self.fuzz_args.push_str(&format!(" --run_time {seconds}"));
+    }
 
-        let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+    #[throws]
+    fn build_command(&self, _target: &str) -> Option<Command> {
+        None
+    }
 
-        rustflags.push_str("--cfg honggfuzz");
+    #[throws]
+    fn fuzz_command(&self, target: &str, _dashboard: bool) -> Command {
+        let mut command = Command::new("cargo");
+        command
+            .env("HFUZZ_RUN_ARGS", &self.fuzz_args)
+            .env("CARGO_TARGET_DIR", &self.cargo_target_dir)
+            .env("HFUZZ_WORKSPACE", &self.hfuzz_workspace)
+            .env("RUSTFLAGS", engine_rustflags("honggfuzz"))
+            .arg("hfuzz")
+            .arg("run")
+            .arg(target);
+        command
+    }
 
+    #[throws]
+    fn debug(&self, target: &str, crash_file: &Path) {
         // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
         std::process::Command::new("cargo")
-            .env("CARGO_TARGET_DIR", cargo_target_dir)
-            .env("RUSTFLAGS", rustflags)
+            .env("CARGO_TARGET_DIR", &self.cargo_target_dir)
+            .env("RUSTFLAGS", engine_rustflags("honggfuzz"))
             .arg("hfuzz")
             .arg("run-debug")
             .arg(target)
@@ -161,4 +86,28 @@ impl Commander {
 
         eprintln!("cannot execute \"cargo hfuzz run-debug\" command");
     }
+
+    fn crash_dir(&self, target: &str) -> PathBuf {
+        self.crash_dir_and_ext(target).0
+    }
+
+    fn crash_files(&self, target: &str) -> Vec<PathBuf> {
+        let (crash_dir, ext) = self.crash_dir_and_ext(target);
+        get_crash_files(&crash_dir, &ext).unwrap_or_default()
+    }
+
+    fn corpus_dir(&self, target: &str) -> Option<PathBuf> {
+        let corpus_dir = Path::new(&self.hfuzz_workspace).join(target).join("input");
+        corpus_dir.is_dir().then_some(corpus_dir)
+    }
+
+    fn log_file(&self, target: &str) -> PathBuf {
+        self.root
+            .join(&self.hfuzz_workspace)
+            .join(format!("{target}.log"))
+    }
+
+    fn crash_dir_hint(&self) -> &'static str {
+        "change the crash folder using for example the --crashdir option and the HFUZZ_RUN_ARGS env variable such as:\nHFUZZ_RUN_ARGS=\"--crashdir ./new_crash_dir\""
+    }
 }
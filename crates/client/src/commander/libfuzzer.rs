@@ -0,0 +1,249 @@
+use fehler::{throw, throws};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use trident_fuzz::config::Config;
+
+use crate::constants::*;
+
+use super::engine::{engine_rustflags, FuzzingEngine};
+use super::{Commander, Error};
+
+/// Instrumentation of the sanitizer coverage the libFuzzer is guided by, the same as of cargo-fuzz.
+const SANITIZER_COVERAGE_FLAGS: &str = "-Cpasses=sancov-module \
+    -Cllvm-args=-sanitizer-coverage-level=4 \
+    -Cllvm-args=-sanitizer-coverage-inline-8bit-counters \
+    -Cllvm-args=-sanitizer-coverage-pc-table \
+    -Cllvm-args=-sanitizer-coverage-trace-compares";
+
+/// Prefixes of the inputs written by libFuzzer into the artifact directory.
+const ARTIFACT_PREFIXES: [&str; 4] = ["crash-", "timeout-", "oom-", "leak-"];
+
+/// libFuzzer linked into the fuzz target through the `libfuzzer` feature of trident-client.
+pub(super) struct LibFuzzerEngine {
+    root: PathBuf,
+    config: Config,
+    /// Seconds replacing the `max_total_time` option of the Trident Manifest.
+    time_budget: Option<u64>,
+}
+
+impl LibFuzzerEngine {
+    pub(super) fn new(root: &Path, config: Config) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config,
+            time_budget: None,
+        }
+    }
+
+    fn cargo_target_dir(&self) -> PathBuf {
+        self.root.join(self.config.get_libfuzzer_cargo_target_dir())
+    }
+
+    fn corpus_path(&self, target: &str) -> PathBuf {
+        self.root.join(self.config.get_libfuzzer_corpus_dir(target))
+    }
+
+    /// Path of the fuzz target built by the `build_command`.
+    #[throws]
+    fn binary(&self, target: &str) -> PathBuf {
+        self.cargo_target_dir()
+            .join(host_target()?)
+            .join("release")
+            .join(target)
+    }
+}
+
+impl FuzzingEngine for LibFuzzerEngine {
+    fn name(&self) -> &'static str {
+        "libfuzzer"
+    }
+
+    #[throws]
+    fn prepare(&self, target: &str) {
+        std::fs::create_dir_all(self.corpus_path(target))?;
+        std::fs::create_dir_all(self.crash_dir(target))?;
+    }
+
+    fn set_time_budget(&mut self, seconds: u64) {
+        self.time_budget = Some(seconds);
+    }
+
+    #[throws]
+    fn build_command(&self, target: &str) -> Option<Command> {
+        let mut rustflags = engine_rustflags("libfuzzer");
+        rustflags.push(' ');
+        rustflags.push_str(SANITIZER_COVERAGE_FLAGS);
+
+        // the explicit target prevents the instrumentation of the build scripts and the procedural macros
+        let mut command = Command::new("cargo");
+        command
+            .env("CARGO_TARGET_DIR", self.cargo_target_dir())
+            .env("RUSTFLAGS", rustflags)
+            .args(["build", "--release", "--bin", target])
+            .args(["--features", "trident-client/libfuzzer"])
+            .args(["--target", &host_target()?]);
+        Some(command)
+    }
+
+    #[throws]
+    fn fuzz_command(&self, target: &str, _dashboard: bool) -> Command {
+        let mut command = Command::new(self.binary(target)?);
+        command
+            .arg(self.corpus_path(target))
+            .arg(format!(
+                "-artifact_prefix={}/",
+                self.crash_dir(target).display()
+            ))
+            // the crashes are detected from the artifact directory, as with the other fuzzers
+            .args(["-error_exitcode=0", "-timeout_exitcode=0"])
+            .args(self.config.get_libfuzzer_fuzz_args());
+        if let Some(seconds) = self.time_budget {
+            // libFuzzer takes the last occurrence of the flag
+            command.arg(format!("-max_total_time={seconds}"));
+        }
+        command
+    }
+
+    #[throws]
+    fn debug(&self, target: &str, crash_file: &Path) {
+        // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
+        std::process::Command::new(self.binary(target)?)
+            .arg(crash_file)
+            .exec();
+
+        eprintln!("cannot execute the libFuzzer fuzz target");
+    }
+
+    fn crash_dir(&self, target: &str) -> PathBuf {
+        self.root
+            .join(self.config.get_libfuzzer_artifacts_dir(target))
+    }
+
+    fn crash_files(&self, target: &str) -> Vec<PathBuf> {
+        get_libfuzzer_crash_files(&self.crash_dir(target))
+    }
+
+    fn corpus_dir(&self, target: &str) -> Option<PathBuf> {
+        let corpus_dir = self.corpus_path(target);
+        corpus_dir.is_dir().then_some(corpus_dir)
+    }
+
+    fn log_file(&self, target: &str) -> PathBuf {
+        self.root
+            .join(self.config.get_libfuzzer_workspace())
+            .join(format!("{target}.log"))
+    }
+
+    fn crash_dir_hint(&self) -> &'static str {
+        "change the libFuzzer workspace using the libfuzzer_workspace option within the Trident.toml"
+    }
+}
+
+impl Commander {
+    /// Merges the given corpus directories into the libFuzzer corpus of the given target, only the inputs
+    /// extending the coverage of the corpus are kept.
+    #[throws]
+    pub async fn run_libfuzzer_merge(&self, target: String, corpus_paths: Vec<String>) {
+        let fuzzer = LibFuzzerEngine::new(&self.root, Config::load()?);
+        fuzzer.prepare(&target)?;
+        if let Some(mut build) = fuzzer.build_command(&target)? {
+            let mut child = build.spawn()?;
+            Self::handle_child(&mut child).await?;
+        }
+
+        let corpus_dir = fuzzer.corpus_path(&target);
+        let mut child = Command::new(fuzzer.binary(&target)?)
+            .arg("-merge=1")
+            .arg(&corpus_dir)
+            .args(corpus_paths.iter().map(|path| self.root.join(path)))
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+        println!(
+            "{FINISH} Corpus merged into {}",
+            corpus_dir.to_string_lossy()
+        );
+    }
+}
+
+/// Returns the target triple of the host, i.e. the target of the fuzz test binary.
+#[throws]
+fn host_target() -> String {
+    let output = std::process::Command::new("rustc").arg("-vV").output()?;
+    let version = String::from_utf8(output.stdout)?;
+    match version.lines().find_map(|line| line.strip_prefix("host: ")) {
+        Some(host) => host.trim().to_string(),
+        None => throw!(Error::FuzzingFailed),
+    }
+}
+
+/// Returns the crashes, timeouts, out-of-memory and leak inputs within the libFuzzer artifact directory.
+fn get_libfuzzer_crash_files(artifacts_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(artifacts_dir) else {
+        return vec![];
+    };
+    let mut crash_files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| {
+                        ARTIFACT_PREFIXES
+                            .iter()
+                            .any(|prefix| name.starts_with(prefix))
+                    })
+        })
+        .collect();
+    crash_files.sort();
+    crash_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_libfuzzer_crash_files() {
+        let dir = std::env::temp_dir().join(format!(
+            "trident-libfuzzer-artifacts-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(get_libfuzzer_crash_files(&dir).is_empty());
+
+        std::fs::write(dir.join("timeout-2c8e"), [1]).unwrap();
+        std::fs::write(dir.join("crash-ef6b"), [0]).unwrap();
+        std::fs::write(dir.join("fuzz_0.log"), "INFO: Seed: 42").unwrap();
+        assert_eq!(
+            get_libfuzzer_crash_files(&dir),
+            vec![dir.join("crash-ef6b"), dir.join("timeout-2c8e")]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_file_within_root() {
+        use trident_fuzz::config::{
+            afl::_Afl, fuzz::_Fuzz, honggfuzz::_HonggFuzz, libfuzzer::_LibFuzzer,
+        };
+
+        let config = Config {
+            honggfuzz: _HonggFuzz::default().into(),
+            afl: _Afl::default().into(),
+            libfuzzer: _LibFuzzer::default().into(),
+            fuzz: _Fuzz::default().try_into().unwrap(),
+        };
+        let root = Path::new("/workspace");
+        let engine = LibFuzzerEngine::new(root, config);
+
+        assert_eq!(
+            engine.log_file("fuzz_0"),
+            root.join(engine.config.get_libfuzzer_workspace())
+                .join("fuzz_0.log")
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use fehler::throws;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prettytable::{row, Table};
+use trident_fuzz::config::Config;
+
+use crate::constants::*;
+
+use super::engine::configured_engine;
+use super::replay::parse_fuzz_trident;
+use super::{Commander, Engine, Error};
+
+/// Prefix of the constants with the names of the programs under test within the fuzz test.
+const PROGRAM_NAME_PREFIX: &str = "PROGRAM_NAME_";
+
+/// Overview of a fuzz target shown by `trident fuzz list`.
+struct FuzzTargetInfo {
+    target: String,
+    engine: Engine,
+    programs: Vec<String>,
+    instructions: Vec<String>,
+    corpus_size: usize,
+    crash_files: usize,
+    /// UNIX timestamp in seconds of the last fuzzing session.
+    last_run: Option<u64>,
+}
+
+impl Commander {
+    /// Prints every fuzz target of the fuzz tests with its programs, instructions, corpus size, crash files,
+    /// last run and the configured fuzzing engine.
+    #[throws]
+    pub async fn list_fuzz_targets(&self, json: bool) {
+        let config = Config::load()?;
+        let targets = self
+            .fuzz_targets()?
+            .iter()
+            .map(|target| self.fuzz_target_info(&config, target))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        match json {
+            true => {
+                let targets: Vec<serde_json::Value> = targets
+                    .iter()
+                    .map(|info| {
+                        serde_json::json!({
+                            "target": info.target,
+                            "engine": info.engine.name(),
+                            "programs": info.programs,
+                            "instructions": info.instructions,
+                            "corpus_size": info.corpus_size,
+                            "crash_files": info.crash_files,
+                            "last_run": info.last_run,
+                        })
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&targets).unwrap_or_default()
+                );
+            }
+            false => show_fuzz_targets(&targets),
+        }
+    }
+
+    #[throws]
+    fn fuzz_target_info(&self, config: &Config, target: &str) -> FuzzTargetInfo {
+        let engine = configured_engine(config, target)?;
+        let fuzzer = engine.create(&self.root, config.clone());
+
+        let fuzz_test_dir = self
+            .root
+            .join(TESTS_WORKSPACE_DIRECTORY)
+            .join(FUZZ_TEST_DIRECTORY)
+            .join(target);
+        // the fuzz target may be declared in the Cargo.toml without the sources generated by Trident
+        let fuzz_test = std::fs::read_to_string(fuzz_test_dir.join(FUZZ_TEST)).unwrap_or_default();
+        let fuzz_instructions =
+            std::fs::read_to_string(fuzz_test_dir.join(FUZZ_INSTRUCTIONS_FILE_NAME))
+                .unwrap_or_default();
+        let instruction_type = parse_fuzz_trident(&fuzz_test)
+            .map(|(instruction_type, _)| instruction_type)
+            .unwrap_or("FuzzInstruction".to_string());
+
+        let corpus_files = fuzzer
+            .corpus_dir(target)
+            .map(|corpus_dir| files_within(&corpus_dir))
+            .unwrap_or_default();
+        let crash_files = fuzzer.crash_files(target);
+
+        // the fuzzing reports are written only with the statistics, the fuzzer writes the corpus
+        // and the crash files during every session
+        let last_run = last_report(&config.get_report_dir(), target).or_else(|| {
+            corpus_files
+                .iter()
+                .chain(&crash_files)
+                .filter_map(|file| modified_at(file))
+                .max()
+        });
+
+        FuzzTargetInfo {
+            target: target.to_string(),
+            engine,
+            programs: parse_program_names(&fuzz_test),
+            instructions: parse_instruction_variants(&fuzz_instructions, &instruction_type),
+            corpus_size: corpus_files.len(),
+            crash_files: crash_files.len(),
+            last_run,
+        }
+    }
+}
+
+/// Returns the names of the programs under test, i.e. the values of the `PROGRAM_NAME_*` constants of the fuzz test.
+fn parse_program_names(fuzz_test: &str) -> Vec<String> {
+    let Ok(file) = syn::parse_file(fuzz_test) else {
+        return vec![];
+    };
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Const(item) if item.ident.to_string().starts_with(PROGRAM_NAME_PREFIX) => {
+                match item.expr.as_ref() {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(name),
+                        ..
+                    }) => Some(name.value()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the variants of the enum of the fuzz instructions, e.g. `FuzzInstruction`.
+fn parse_instruction_variants(fuzz_instructions: &str, instruction_type: &str) -> Vec<String> {
+    let Ok(file) = syn::parse_file(fuzz_instructions) else {
+        return vec![];
+    };
+    // the type within the fuzz_trident! macro may be a path, e.g. fuzz_instructions::FuzzInstruction
+    let instruction_type = instruction_type.rsplit("::").next().unwrap_or_default();
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Enum(item) if item.ident == instruction_type => Some(
+                item.variants
+                    .iter()
+                    .map(|variant| variant.ident.to_string())
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the start of the latest fuzzing session of the target from the names of its fuzzing reports.
+fn last_report(report_dir: &Path, target: &str) -> Option<u64> {
+    let prefix = format!("{target}-");
+    std::fs::read_dir(report_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_prefix(&prefix)?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()
+        })
+        .max()
+}
+
+fn files_within(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn modified_at(file: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(file).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Formats the time since the given UNIX timestamp in seconds, e.g. `3 hours ago`.
+fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let (value, unit) = match now.saturating_sub(timestamp) {
+        age if age < 60 => return "just now".to_string(),
+        age if age < 3600 => (age / 60, "minute"),
+        age if age < 86400 => (age / 3600, "hour"),
+        age => (age / 86400, "day"),
+    };
+    match value {
+        1 => format!("1 {unit} ago"),
+        _ => format!("{value} {unit}s ago"),
+    }
+}
+
+fn show_fuzz_targets(targets: &[FuzzTargetInfo]) {
+    let mut table = Table::new();
+    table.add_row(row![
+        "Target",
+        "Engine",
+        "Programs",
+        "Instructions",
+        "Corpus",
+        "Crashes",
+        "Last Run"
+    ]);
+    for info in targets {
+        table.add_row(row![
+            info.target,
+            info.engine,
+            info.programs.join("\n"),
+            info.instructions.join("\n"),
+            info.corpus_size,
+            info.crash_files,
+            info.last_run.map_or("never".to_string(), format_age),
+        ]);
+    }
+    table.printstd();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_program_names() {
+        let fuzz_test = r#"
+            use trident_client::fuzzing::*;
+            const PROGRAM_NAME_CALLEE: &str = "callee";
+            const PROGRAM_NAME_CALLER: &str = "caller";
+            const MAX_AMOUNT: u64 = 100;
+        "#;
+        assert_eq!(parse_program_names(fuzz_test), vec!["callee", "caller"]);
+        assert!(parse_program_names("fn main() {").is_empty());
+    }
+
+    #[test]
+    fn test_parse_instruction_variants() {
+        let fuzz_instructions = r#"
+            #[derive(Arbitrary, DisplayIx, FuzzTestExecutor)]
+            pub enum FuzzInstruction {
+                InitializeCallee(InitializeCallee),
+                InitializeCaller(InitializeCaller),
+            }
+            pub enum Other {
+                Variant,
+            }
+        "#;
+        assert_eq!(
+            parse_instruction_variants(fuzz_instructions, "fuzz_instructions::FuzzInstruction"),
+            vec!["InitializeCallee", "InitializeCaller"]
+        );
+        assert!(parse_instruction_variants(fuzz_instructions, "Missing").is_empty());
+    }
+
+    #[test]
+    fn test_last_report() {
+        let dir = std::env::temp_dir().join(format!("trident-reports-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "fuzz_1-1700000000.json",
+            "fuzz_1-1700000500.json",
+            "fuzz_1-1700000500.csv",
+            "fuzz_10-1800000000.json",
+        ] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        assert_eq!(last_report(&dir, "fuzz_1"), Some(1700000500));
+        assert_eq!(last_report(&dir, "fuzz_10"), Some(1800000000));
+        assert_eq!(last_report(&dir, "fuzz_2"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
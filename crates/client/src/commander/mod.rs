@@ -9,10 +9,26 @@ use tokio::{
 };
 
 mod afl;
+mod coverage;
+mod dashboard;
+mod engine;
 mod honggfuzz;
+mod libfuzzer;
+mod list;
+mod replay;
+mod run_all;
+mod rust_literal;
 
-use tokio::io::AsyncBufReadExt;
+use trident_fuzz::config::Config;
+use trident_fuzz::fuzz_report::FuzzingReport;
 use trident_fuzz::fuzz_stats::FuzzingStatistics;
+use trident_fuzz::stats_channel::{self, STATS_DIR_ENV};
+
+use self::dashboard::{Dashboard, DASHBOARD_REFRESH};
+
+pub use self::engine::Engine;
+
+use crate::constants::ERROR;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -20,6 +36,10 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("{0:?}")]
     Utf8(#[from] FromUtf8Error),
+    #[error("{0:?}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid Trident config: {0}")]
+    Config(#[from] trident_fuzz::config::Error),
     #[error("build programs failed")]
     BuildProgramsFailed,
     #[error("fuzzing failed")]
@@ -32,6 +52,26 @@ pub enum Error {
     NoProgramsFound,
     #[error("Incorrect AFL workspace provided")]
     BadAFLWorkspace,
+    #[error("LLVM tools not found, install them with `rustup component add llvm-tools-preview`")]
+    LlvmToolsNotFound,
+    #[error("coverage report failed")]
+    CoverageFailed,
+    #[error("crash minimization failed")]
+    MinimizationFailed,
+    #[error("the fuzz test does not exist")]
+    FuzzTestNotFound,
+    #[error("the crash file cannot be decoded")]
+    DecodingFailed,
+    #[error("crash triage failed")]
+    TriageFailed,
+    #[error("some of the replayed inputs crashed")]
+    ReplayFailed,
+    #[error("invalid fuzzing engine within the Trident.toml: {0}")]
+    InvalidEngine(String),
+    #[error("the fuzz tests do not contain any fuzz targets")]
+    NoFuzzTargets,
+    #[error("the total time budget is shorter than the per-target time budgets or one second per fuzz target")]
+    InvalidTimeBudget,
 }
 
 /// `Commander` allows you to start localnet, build programs,
@@ -127,86 +167,123 @@ impl Commander {
             },
         }
     }
-    /// Asynchronously manages a child fuzzing process, collecting and logging its statistics.
-    /// This function spawns a new task dedicated to reading the process's standard output and logging the fuzzing statistics.
-    /// It waits for either the child process to exit or a Ctrl+C signal to be received. Upon process exit or Ctrl+C signal,
-    /// it stops the logging task and displays the collected statistics in a table format.
+    /// Manages a child fuzzing process the same way as `handle_child` and collects the fuzzing statistics
+    /// the fuzzing threads wrote into the stats directory. The statistics are displayed in a table format
+    /// and the stats directory is removed afterwards.
     ///
-    /// The implementation ensures that the statistics logging task only stops after receiving a signal indicating the end of the fuzzing process
-    /// or an interrupt from the user, preventing premature termination of the logging task if scenarios where reading is faster than fuzzing,
-    /// which should not be common.
+    /// # Arguments
+    /// * `child` - A mutable reference to a `Child` process, representing the child fuzzing process.
+    /// * `stats_dir` - The stats directory passed to the child process, see `stats_dir`.
+    ///
+    /// # Errors
+    /// * `Error::FuzzingFailed` - Thrown if there's an issue with managing the child process, such as failing to wait on the child process.
+    #[throws]
+    async fn handle_child_with_stats(child: &mut Child, stats_dir: &Path) -> FuzzingStatistics {
+        let result = Self::handle_child(child).await;
+
+        let stats = stats_channel::collect(stats_dir);
+        let _ = std::fs::remove_dir_all(stats_dir);
+        result?;
+
+        stats.show_table();
+        stats
+    }
+
+    /// Manages a child fuzzing process the same way as `handle_child_with_stats`, while the process is running
+    /// the statistics from the stats directory are continuously displayed on the live dashboard.
     ///
     /// # Arguments
     /// * `child` - A mutable reference to a `Child` process, representing the child fuzzing process.
+    /// * `stats_dir` - The stats directory passed to the child process, see `stats_dir`.
+    /// * `dashboard` - The dashboard to refresh.
     ///
     /// # Errors
     /// * `Error::FuzzingFailed` - Thrown if there's an issue with managing the child process, such as failing to wait on the child process.
     #[throws]
-    async fn handle_child_with_stats(child: &mut Child) {
-        let stdout = child
-            .stdout
-            .take()
-            .expect("child did not have a handle to stdout");
-
-        let reader = tokio::io::BufReader::new(stdout);
-
-        let fuzz_end = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let fuzz_end_clone = std::sync::Arc::clone(&fuzz_end);
-
-        let stats_handle: tokio::task::JoinHandle<Result<FuzzingStatistics, std::io::Error>> =
-            tokio::spawn(async move {
-                let mut stats_logger = FuzzingStatistics::new();
-
-                let mut lines = reader.lines();
-                loop {
-                    let _line = lines.next_line().await;
-                    match _line {
-                        Ok(__line) => match __line {
-                            Some(content) => {
-                                stats_logger.insert_serialized(&content);
-                            }
-                            None => {
-                                if fuzz_end_clone.load(std::sync::atomic::Ordering::SeqCst) {
-                                    break;
-                                }
-                            }
-                        },
-                        Err(e) => return Err(e),
-                    }
-                }
-                Ok(stats_logger)
-            });
+    async fn handle_child_with_dashboard(
+        child: &mut Child,
+        stats_dir: &Path,
+        mut dashboard: Dashboard,
+    ) -> FuzzingStatistics {
+        let mut refresh = tokio::time::interval(DASHBOARD_REFRESH);
+        let ctrl_c = signal::ctrl_c();
+        tokio::pin!(ctrl_c);
 
-        tokio::select! {
-            res = child.wait() =>{
-                fuzz_end.store(true, std::sync::atomic::Ordering::SeqCst);
+        let result = loop {
+            tokio::select! {
+                res = child.wait() => break match res {
+                    Ok(status) if status.success() => Ok(()),
+                    _ => Err(Error::FuzzingFailed),
+                },
+                _ = &mut ctrl_c => {
+                    let _res = child.wait().await;
+                    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+                    break Ok(());
+                },
+                _ = refresh.tick() => dashboard.refresh(&stats_channel::collect(stats_dir)),
+            }
+        };
 
-                match res {
-                    Ok(status) => {
-                        if !status.success() {
-                            throw!(Error::FuzzingFailed);
-                        }
-                    },
-                    Err(_) => throw!(Error::FuzzingFailed),
+        let stats = stats_channel::collect(stats_dir);
+        let _ = std::fs::remove_dir_all(stats_dir);
+        dashboard.refresh(&stats);
+        result?;
+
+        stats.show_table();
+        stats
+    }
+
+    /// Spawns the fuzzing command with the stats directory and collects the fuzzing statistics. If the log file
+    /// is provided, the output of the fuzzer is redirected into it and the live dashboard is displayed instead.
+    #[throws]
+    async fn spawn_with_stats(
+        command: &mut Command,
+        target: &str,
+        log_file: Option<PathBuf>,
+    ) -> FuzzingStatistics {
+        let stats_dir = Self::stats_dir(target)?;
+        command.env(STATS_DIR_ENV, &stats_dir);
+
+        match log_file {
+            Some(log_file) => {
+                if let Some(parent) = log_file.parent() {
+                    std::fs::create_dir_all(parent)?;
                 }
-            },
-            _ = signal::ctrl_c() => {
-                fuzz_end.store(true, std::sync::atomic::Ordering::SeqCst);
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            },
-        }
-        let stats_result = stats_handle
-            .await
-            .expect("Unable to obtain Statistics Handle");
-        match stats_result {
-            Ok(stats_result) => {
-                stats_result.show_table();
+                let log = std::fs::File::create(&log_file)?;
+                let mut child = command.stdout(log.try_clone()?).stderr(log).spawn()?;
+                let dashboard = Dashboard::new(target, log_file.clone());
+                Self::handle_child_with_dashboard(&mut child, &stats_dir, dashboard)
+                    .await
+                    .inspect_err(|_| {
+                        println!("{ERROR} Fuzzing failed, see {}", log_file.display())
+                    })?
             }
-            Err(e) => {
-                println!("Statistics thread exited with the Error: {}", e);
+            None => {
+                let mut child = command.spawn()?;
+                Self::handle_child_with_stats(&mut child, &stats_dir).await?
             }
         }
     }
+
+    /// Creates an empty directory the fuzzed target writes its statistics into. The directory is passed
+    /// to the fuzzed target through the `TRIDENT_STATS_DIR` environment variable, so the statistics
+    /// do not depend on the output of the fuzzer.
+    #[throws]
+    fn stats_dir(target: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("trident-stats-{}-{}", target, std::process::id()));
+        stats_channel::prepare(&dir)?;
+        dir
+    }
+
+    /// Writes the machine-readable report of the fuzzing session into the report directory
+    /// specified within the Trident Manifest.
+    fn write_report(config: &Config, report: &FuzzingReport) {
+        match report.write(&config.get_report_dir(), config.get_report_csv()) {
+            Ok(path) => println!("Fuzzing report written to {}", path.display()),
+            Err(e) => println!("{ERROR} Failed to write the fuzzing report: {e}"),
+        }
+    }
 }
 
 fn get_crash_dir_and_ext(
@@ -0,0 +1,516 @@
+use fehler::{throw, throws};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::Command;
+use trident_fuzz::ci_report::CiReport;
+use trident_fuzz::fuzz_stats::FuzzingStatistics;
+use trident_fuzz::replay::{
+    DecodedInput, ReplayResult, DECODE_ENV, MINIMIZE_OUTPUT_ENV, REPLAY_INPUTS_ENV,
+    REPLAY_RESULTS_ENV, TRIAGE_ENV,
+};
+use trident_fuzz::stats_channel::{self, STATS_DIR_ENV};
+
+use crate::constants::*;
+
+use super::rust_literal::instruction_expr;
+use super::{Commander, Engine, Error};
+
+impl Commander {
+    /// Builds the fuzz target without a fuzzer, such fuzz test replays the inputs provided
+    /// through the `TRIDENT_REPLAY_INPUTS` environment variable instead of fuzzing.
+    #[throws]
+    async fn build_replay_binary(&self, target: &str) -> PathBuf {
+        let cargo_target_dir = self.root.join(REPLAY_TARGET_DIR_DEFAULT);
+        let mut child = Command::new("cargo")
+            .env("CARGO_TARGET_DIR", &cargo_target_dir)
+            .args(["build", "--bin", target])
+            .spawn()?;
+        Self::handle_child(&mut child).await?;
+        cargo_target_dir.join("debug").join(target)
+    }
+
+    /// Minimizes the crash file of the given target, the minimized crash file and its instruction
+    /// sequence (`<output>.txt`) are written next to the crash file if the output is not specified.
+    #[throws]
+    pub async fn run_minimize(
+        &self,
+        target: String,
+        crash_file_path: String,
+        output_path: Option<String>,
+    ) {
+        let crash_file = self.root.join(crash_file_path);
+
+        if !crash_file.try_exists()? {
+            println!("{ERROR} The crash file [{:?}] not found", crash_file);
+            throw!(Error::CrashFileNotFound);
+        }
+        let output = match output_path {
+            Some(output_path) => self.root.join(output_path),
+            None => PathBuf::from(format!("{}.min", crash_file.display())),
+        };
+
+        let binary = self.build_replay_binary(&target).await?;
+
+        // the output of the fuzz test contains the crash reports of all the executed candidates
+        let status = Command::new(binary)
+            .env(REPLAY_INPUTS_ENV, &crash_file)
+            .env(MINIMIZE_OUTPUT_ENV, &output)
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+        if !status.success() {
+            throw!(Error::MinimizationFailed);
+        }
+        println!(
+            "{FINISH} Minimized crash file written to {}",
+            output.display()
+        );
+    }
+
+    /// Replays every input of the given target exactly once, without mutation, and fails if any of them
+    /// crashes. The corpus and crash files of the target found by the given fuzzing engine are replayed
+    /// if no inputs are specified. Optionally, the JUnit and SARIF reports of the replay are written.
+    #[throws]
+    pub async fn run_replay(
+        &self,
+        engine: Engine,
+        target: String,
+        input_paths: Vec<String>,
+        junit: Option<String>,
+        sarif: Option<String>,
+    ) {
+        let inputs: Vec<PathBuf> = if input_paths.is_empty() {
+            let (mut inputs, corpus_dir) = self.fuzzer_inputs(engine, &target)?;
+            inputs.extend(corpus_dir);
+            inputs
+        } else {
+            input_paths
+                .into_iter()
+                .map(|path| self.root.join(path))
+                .collect()
+        };
+        if inputs.is_empty() {
+            println!("{ERROR} No corpus or crash files of the {target} fuzz target found, run the fuzzer first");
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let with_reports = junit.is_some() || sarif.is_some();
+        let (passed, results, stats) = self.replay_inputs(&target, &inputs, with_reports).await?;
+        if with_reports {
+            let report = CiReport::new(&target, stats, &results);
+            self.write_ci_reports(&report, junit, sarif)?;
+        }
+        if !passed {
+            throw!(Error::ReplayFailed);
+        }
+        println!("{FINISH} All inputs of the {target} fuzz target replayed without a crash");
+    }
+
+    /// Replays the inputs by the fuzz test built without a fuzzer. Returns whether none of the inputs crashed
+    /// and the results of the particular inputs, the statistics of the replay are collected if requested.
+    #[throws]
+    pub(super) async fn replay_inputs(
+        &self,
+        target: &str,
+        inputs: &[PathBuf],
+        with_stats: bool,
+    ) -> (bool, Vec<ReplayResult>, FuzzingStatistics) {
+        let binary = self.build_replay_binary(target).await?;
+        let results_path = std::env::temp_dir().join(format!(
+            "trident-replay-{}-{}.json",
+            target,
+            std::process::id()
+        ));
+        let mut command = Command::new(binary);
+        command
+            .env(REPLAY_INPUTS_ENV, std::env::join_paths(inputs).unwrap())
+            .env(REPLAY_RESULTS_ENV, &results_path);
+        let stats_dir = match with_stats {
+            true => Some(Self::stats_dir(target)?),
+            false => None,
+        };
+        if let Some(stats_dir) = &stats_dir {
+            command.env(STATS_DIR_ENV, stats_dir);
+        }
+        let status = command.status().await?;
+
+        let stats = match &stats_dir {
+            Some(stats_dir) => {
+                let stats = stats_channel::collect(stats_dir);
+                let _ = std::fs::remove_dir_all(stats_dir);
+                stats
+            }
+            None => FuzzingStatistics::new(),
+        };
+        let results = match std::fs::read_to_string(&results_path) {
+            Ok(results) => {
+                let _ = std::fs::remove_file(&results_path);
+                serde_json::from_str(&results).unwrap_or_default()
+            }
+            // the replay was aborted before all the inputs were replayed
+            Err(_) => throw!(Error::ReplayFailed),
+        };
+        (status.success(), results, stats)
+    }
+
+    /// Writes the JUnit and SARIF reports into the specified paths.
+    #[throws]
+    pub(super) fn write_ci_reports(
+        &self,
+        report: &CiReport,
+        junit: Option<String>,
+        sarif: Option<String>,
+    ) {
+        let reports = [
+            (junit, "JUnit", report.to_junit()),
+            (sarif, "SARIF", report.to_sarif()),
+        ];
+        for (path, format, content) in reports {
+            let Some(path) = path else {
+                continue;
+            };
+            let path = self.root.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+            println!("{format} report written to {}", path.display());
+        }
+    }
+
+    /// Replays the crash files of the given target and groups them into buckets by the failed instruction,
+    /// the error and the panic location, one representative per bucket is printed. The crash files of the target
+    /// found by the given fuzzing engine are triaged if no crash files or directories are specified.
+    #[throws]
+    pub async fn run_triage(&self, engine: Engine, target: String, crash_paths: Vec<String>) {
+        let inputs: Vec<PathBuf> = if crash_paths.is_empty() {
+            self.fuzzer_inputs(engine, &target)?.0
+        } else {
+            crash_paths
+                .into_iter()
+                .map(|path| self.root.join(path))
+                .collect()
+        };
+        if inputs.is_empty() {
+            println!("{ERROR} No crash files of the {target} fuzz target found");
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let binary = self.build_replay_binary(&target).await?;
+        let status = Command::new(binary)
+            .env(REPLAY_INPUTS_ENV, std::env::join_paths(&inputs).unwrap())
+            .env(TRIAGE_ENV, "1")
+            .status()
+            .await?;
+        if !status.success() {
+            throw!(Error::TriageFailed);
+        }
+    }
+
+    /// Decodes the corpus or crash file (or all files of the directory) of the given target into the instruction
+    /// sequence and prints it as plain text or JSON. The instructions are not executed.
+    #[throws]
+    pub async fn run_decode(&self, target: String, input_path: String, json: bool) {
+        let input = self.root.join(input_path);
+        if !input.try_exists()? {
+            println!("{ERROR} The input [{:?}] not found", input);
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let binary = self.build_replay_binary(&target).await?;
+        let status = Command::new(binary)
+            .env(REPLAY_INPUTS_ENV, &input)
+            .env(DECODE_ENV, if json { "json" } else { "text" })
+            .status()
+            .await?;
+        if !status.success() {
+            throw!(Error::DecodingFailed);
+        }
+    }
+
+    /// Converts the crash file of the given target into a regression test. The crash input is decoded into
+    /// the instruction sequence, which is written into the test as Rust code and replayed through the
+    /// `fuzz_iteration` of the fuzz test, so it fails until the bug is fixed. The test does not depend on
+    /// how the bytes are decoded, so it keeps reproducing the bug after the `FuzzInstruction` changes.
+    /// The tests are stored in the `regression_tests.rs` module next to the fuzz test and run with `cargo test`.
+    #[throws]
+    pub async fn run_crash_to_test(
+        &self,
+        target: String,
+        crash_file_path: String,
+        name: Option<String>,
+    ) {
+        let crash_file = self.root.join(crash_file_path);
+        if !crash_file.try_exists()? {
+            println!("{ERROR} The crash file [{:?}] not found", crash_file);
+            throw!(Error::CrashFileNotFound);
+        }
+
+        let fuzz_test_dir = self
+            .root
+            .join(TESTS_WORKSPACE_DIRECTORY)
+            .join(FUZZ_TEST_DIRECTORY)
+            .join(&target);
+        let fuzz_test_path = fuzz_test_dir.join(FUZZ_TEST);
+        if !fuzz_test_path.try_exists()? {
+            println!("{ERROR} The fuzz test [{:?}] not found", fuzz_test_path);
+            throw!(Error::FuzzTestNotFound);
+        }
+        let fuzz_test = std::fs::read_to_string(&fuzz_test_path)?;
+        let Some((instruction_type, _)) = parse_fuzz_trident(&fuzz_test) else {
+            println!(
+                "{ERROR} The fuzz_trident! macro not found in {:?}",
+                fuzz_test_path
+            );
+            throw!(Error::FuzzTestNotFound);
+        };
+
+        let binary = self.build_replay_binary(&target).await?;
+        let output = Command::new(binary)
+            .env(REPLAY_INPUTS_ENV, &crash_file)
+            .env(DECODE_ENV, "json")
+            .stderr(Stdio::inherit())
+            .output()
+            .await?;
+        if !output.status.success() {
+            throw!(Error::DecodingFailed);
+        }
+        let decoded: Vec<DecodedInput> =
+            serde_json::from_slice(&output.stdout).map_err(|_| Error::DecodingFailed)?;
+        let Some(decoded) = decoded.into_iter().next() else {
+            throw!(Error::DecodingFailed);
+        };
+
+        let data = std::fs::read(&crash_file)?;
+        let name = name.unwrap_or_else(|| default_test_name(&data));
+        let regression_tests_path = fuzz_test_dir.join(REGRESSION_TESTS_FILE_NAME);
+        let mut regression_tests = match regression_tests_path.try_exists()? {
+            true => std::fs::read_to_string(&regression_tests_path)?,
+            false => REGRESSION_TESTS_HEADER.to_string(),
+        };
+        if regression_tests.contains(&format!("fn {name}()")) {
+            println!("{SKIP} The regression test {name} already exists");
+            return;
+        }
+
+        let crash_file_name = crash_file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Some(test) =
+            regression_test(&name, &crash_file_name, &data, &decoded, &instruction_type)
+        else {
+            println!(
+                "{ERROR} The instructions of the crash file cannot be converted into Rust code, \
+                the data types of the instructions have to derive `Debug`"
+            );
+            throw!(Error::DecodingFailed);
+        };
+        regression_tests.push_str(&test);
+        std::fs::write(&regression_tests_path, regression_tests)?;
+
+        if let Some(fuzz_test) = add_regression_tests_module(&fuzz_test) {
+            std::fs::write(&fuzz_test_path, fuzz_test)?;
+        }
+
+        println!(
+            "{FINISH} Regression test {name} written to {}, run it with `cargo test --bin {target}`",
+            regression_tests_path.display()
+        );
+    }
+}
+
+/// Name of the module (next to the fuzz test) with the regression tests created from the crash files.
+const REGRESSION_TESTS_FILE_NAME: &str = "regression_tests.rs";
+const REGRESSION_TESTS_MODULE: &str = "mod regression_tests;";
+const REGRESSION_TESTS_HEADER: &str = "\
+//! Regression tests created from the crash files by `trident fuzz crash-to-test`.
+//! Each test replays the instruction sequence of the crash through the `fuzz_iteration` of the fuzz test.
+#![allow(clippy::useless_conversion)]
+
+use super::fuzz_instructions::*;
+use super::*;
+";
+
+/// Returns the instruction type and the data builder of the `fuzz_trident!` macro invocation,
+/// e.g. `FuzzInstruction` and `InstructionsSequence`.
+pub(super) fn parse_fuzz_trident(fuzz_test: &str) -> Option<(String, String)> {
+    let (_, invocation) = fuzz_test.split_once("fuzz_trident")?;
+    let invocation: String = invocation.chars().filter(|c| !c.is_whitespace()).collect();
+    // !(fuzz_ix:FuzzInstruction,|fuzz_data:InstructionsSequence|{...
+    let invocation = invocation.strip_prefix("!(")?;
+    let (_, rest) = invocation.split_once(':')?;
+    let (instruction_type, rest) = rest.split_once(",|")?;
+    let (_, rest) = rest.split_once(':')?;
+    let (data_builder, _) = rest.split_once('|')?;
+    Some((instruction_type.to_string(), data_builder.to_string()))
+}
+
+/// Adds the regression tests module into the fuzz test, returns `None` if it is already declared.
+fn add_regression_tests_module(fuzz_test: &str) -> Option<String> {
+    if fuzz_test.contains(REGRESSION_TESTS_MODULE) {
+        return None;
+    }
+    let module = format!("#[cfg(test)]\n{REGRESSION_TESTS_MODULE}\n");
+    let fuzz_test = match fuzz_test.find("mod fuzz_instructions;") {
+        Some(position) => {
+            let (head, tail) = fuzz_test.split_at(position);
+            format!("{head}{module}{tail}")
+        }
+        None => format!("{module}{fuzz_test}"),
+    };
+    Some(fuzz_test)
+}
+
+fn default_test_name(data: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    format!("crash_{:016x}", hasher.finish())
+}
+
+/// Creates the regression test replaying the decoded instruction sequence, the crash input is kept
+/// only as a comment. Returns `None` if any instruction cannot be converted into Rust code.
+fn regression_test(
+    name: &str,
+    crash_file_name: &str,
+    data: &[u8],
+    decoded: &DecodedInput,
+    instruction_type: &str,
+) -> Option<String> {
+    let mut test = format!(
+        "\n/// Replays the instruction sequence of the crash file `{crash_file_name}`.\n#[test]\nfn {name}() {{\n    // crash input:\n"
+    );
+    for chunk in data.chunks(16) {
+        let chunk: Vec<String> = chunk.iter().map(|byte| byte.to_string()).collect();
+        test.push_str(&format!("    // {}\n", chunk.join(", ")));
+    }
+    test.push_str(&format!(
+        "    let fuzz_data: FuzzData<{instruction_type}, _> = FuzzData {{\n"
+    ));
+    for (field, ixs) in [
+        ("pre_ixs", &decoded.pre_ixs),
+        ("ixs", &decoded.ixs),
+        ("post_ixs", &decoded.post_ixs),
+    ] {
+        if ixs.is_empty() {
+            test.push_str(&format!("        {field}: vec![],\n"));
+            continue;
+        }
+        test.push_str(&format!("        {field}: vec![\n"));
+        for ix in ixs {
+            let ix = instruction_expr(instruction_type, ix, 3)?;
+            test.push_str(&format!("            {ix},\n"));
+        }
+        test.push_str("        ],\n");
+    }
+    test.push_str(
+        "        accounts: RefCell::new(Default::default()),
+    };
+    fuzz_iteration(fuzz_data, &Config::new());
+}
+",
+    );
+    Some(test)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUZZ_TEST: &str = "use trident_client::fuzzing::*;
+mod fuzz_instructions;
+use fuzz_instructions::FuzzInstruction;
+
+fn main() {
+    let config = Config::new();
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence | { fuzz_iteration (fuzz_data , & config) ; });
+}
+";
+
+    #[test]
+    fn test_parse_fuzz_trident() {
+        assert_eq!(
+            parse_fuzz_trident(FUZZ_TEST),
+            Some((
+                "FuzzInstruction".to_string(),
+                "InstructionsSequence".to_string()
+            ))
+        );
+        assert_eq!(parse_fuzz_trident("fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_add_regression_tests_module() {
+        let fuzz_test = add_regression_tests_module(FUZZ_TEST).unwrap();
+        assert!(fuzz_test.contains(
+            "use trident_client::fuzzing::*;\n#[cfg(test)]\nmod regression_tests;\nmod fuzz_instructions;"
+        ));
+        assert_eq!(add_regression_tests_module(&fuzz_test), None);
+    }
+
+    #[test]
+    fn test_regression_test() {
+        let decoded = DecodedInput {
+            file: PathBuf::from("SIGABRT.fuzz"),
+            error: None,
+            pre_ixs: vec![],
+            ixs: vec![
+                "Deposit(Deposit {\n    amount: 5,\n})".to_string(),
+                "Withdraw(Withdraw {\n    amounts: [\n        1,\n        2,\n    ],\n})"
+                    .to_string(),
+            ],
+            post_ixs: vec![],
+        };
+        let test = regression_test(
+            "crash_deposit",
+            "SIGABRT.fuzz",
+            &[1, 0, 5],
+            &decoded,
+            "FuzzInstruction",
+        )
+        .unwrap();
+        assert_eq!(
+            test,
+            "
+/// Replays the instruction sequence of the crash file `SIGABRT.fuzz`.
+#[test]
+fn crash_deposit() {
+    // crash input:
+    // 1, 0, 5
+    let fuzz_data: FuzzData<FuzzInstruction, _> = FuzzData {
+        pre_ixs: vec![],
+        ixs: vec![
+            FuzzInstruction::Deposit(Deposit {
+                amount: 5,
+            }),
+            FuzzInstruction::Withdraw(Withdraw {
+                amounts: [1, 2].into(),
+            }),
+        ],
+        post_ixs: vec![],
+        accounts: RefCell::new(Default::default()),
+    };
+    fuzz_iteration(fuzz_data, &Config::new());
+}
+"
+        );
+        assert!(syn::parse_file(&format!("{REGRESSION_TESTS_HEADER}{test}")).is_ok());
+
+        let undecodable = DecodedInput {
+            ixs: vec!["Deposit(Deposit { .. })".to_string()],
+            ..decoded
+        };
+        assert_eq!(
+            regression_test(
+                "crash_deposit",
+                "SIGABRT.fuzz",
+                &[1, 0, 5],
+                &undecodable,
+                "FuzzInstruction",
+            ),
+            None
+        );
+    }
+}
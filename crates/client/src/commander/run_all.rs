@@ -0,0 +1,481 @@
+use fehler::{throw, throws};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime};
+
+use prettytable::{row, Table};
+use tokio::process::Command;
+use trident_fuzz::config::Config;
+use trident_fuzz::fuzz_report::FuzzingReport;
+use trident_fuzz::fuzz_stats::FuzzingStatistics;
+use trident_fuzz::stats_channel::{self, STATS_DIR_ENV};
+
+use crate::constants::*;
+
+use super::dashboard::format_duration;
+use super::engine::configured_engine;
+use super::{Commander, Engine, Error};
+
+/// Outcome of the fuzzing session of one fuzz target within `run_all`.
+struct TargetSummary {
+    target: String,
+    engine: Engine,
+    time_budget: Option<u64>,
+    duration: Duration,
+    result: Result<TargetSession, Error>,
+}
+
+/// Statistics and the number of new crash files of a finished fuzzing session.
+struct TargetSession {
+    stats: FuzzingStatistics,
+    new_crashes: usize,
+}
+
+impl Commander {
+    /// Runs the fuzzing engine (or the engine configured for each target) on all fuzz targets of the fuzz tests,
+    /// in sequence or in parallel, and prints one combined summary. The total time budget is split between
+    /// the targets without their own budget.
+    #[throws]
+    pub async fn run_all(
+        &self,
+        engine: Option<Engine>,
+        total_time: Option<u64>,
+        target_times: Vec<(String, u64)>,
+        parallel: bool,
+        with_exit_code: bool,
+    ) {
+        self.run_all_with_config(
+            Config::load()?,
+            engine,
+            total_time,
+            target_times,
+            parallel,
+            with_exit_code,
+        )
+        .await?
+    }
+
+    #[throws]
+    async fn run_all_with_config(
+        &self,
+        config: Config,
+        engine: Option<Engine>,
+        total_time: Option<u64>,
+        target_times: Vec<(String, u64)>,
+        parallel: bool,
+        with_exit_code: bool,
+    ) {
+        let targets = self.fuzz_targets()?;
+        if targets.is_empty() {
+            throw!(Error::NoFuzzTargets);
+        }
+        let engines = targets
+            .iter()
+            .map(|target| match engine {
+                Some(engine) => Ok(engine),
+                None => configured_engine(&config, target),
+            })
+            .collect::<Result<Vec<Engine>, Error>>()?;
+        let target_times: HashMap<String, u64> = target_times.into_iter().collect();
+        if let Some(unknown) = target_times.keys().find(|target| !targets.contains(target)) {
+            println!("{ERROR} The fuzz target [{unknown}] not found");
+            throw!(Error::FuzzTestNotFound);
+        }
+        let time_budgets = split_time_budget(&targets, total_time, &target_times, parallel)?;
+
+        let summaries = match parallel {
+            true => {
+                println!(
+                    "Fuzzing {} targets in parallel, the output of the fuzzer is written into the log file of each target.",
+                    targets.len()
+                );
+                let sessions = targets.iter().zip(&engines).zip(&time_budgets).map(
+                    |((target, engine), budget)| {
+                        self.run_all_target(*engine, &config, target, *budget, true)
+                    },
+                );
+                futures::future::join_all(sessions).await
+            }
+            false => {
+                let mut summaries = vec![];
+                let sessions = targets.iter().zip(&engines).zip(&time_budgets);
+                for (i, ((target, engine), budget)) in sessions.enumerate() {
+                    println!(
+                        "Fuzzing {target} with {engine} for {} ({}/{})",
+                        budget.map_or("the limits from the Trident.toml".to_string(), |s| {
+                            format_duration(Duration::from_secs(s))
+                        }),
+                        i + 1,
+                        targets.len()
+                    );
+                    summaries.push(
+                        self.run_all_target(*engine, &config, target, *budget, false)
+                            .await,
+                    );
+                }
+                summaries
+            }
+        };
+
+        show_summary(&summaries);
+
+        if summaries.iter().any(|summary| summary.result.is_err()) {
+            throw!(Error::FuzzingFailed);
+        }
+        let new_crashes: usize = summaries
+            .iter()
+            .filter_map(|summary| summary.result.as_ref().ok())
+            .map(|session| session.new_crashes)
+            .sum();
+        if with_exit_code && new_crashes > 0 {
+            println!("The fuzz targets found {new_crashes} new crash files. Exiting!");
+            process::exit(99);
+        }
+    }
+
+    /// Returns the names of the fuzz targets, i.e. the binary targets of the fuzz tests package.
+    #[throws]
+    pub(super) fn fuzz_targets(&self) -> Vec<String> {
+        let cargo_toml = self
+            .root
+            .join(TESTS_WORKSPACE_DIRECTORY)
+            .join(FUZZ_TEST_DIRECTORY)
+            .join(CARGO_TOML);
+        if !cargo_toml.try_exists()? {
+            throw!(Error::NotInitialized);
+        }
+        parse_bin_targets(&std::fs::read_to_string(cargo_toml)?)?
+    }
+
+    async fn run_all_target(
+        &self,
+        engine: Engine,
+        config: &Config,
+        target: &str,
+        time_budget: Option<u64>,
+        parallel: bool,
+    ) -> TargetSummary {
+        let start = Instant::now();
+        let result = self
+            .fuzz_target_session(engine, config, target, time_budget, parallel)
+            .await;
+        if let Err(e) = &result {
+            println!("{ERROR} Fuzzing of {target} failed: {e}");
+        }
+        TargetSummary {
+            target: target.to_string(),
+            engine,
+            time_budget,
+            duration: start.elapsed(),
+            result,
+        }
+    }
+
+    /// Builds and fuzzes the target the same way as `run_fuzzer` with the fuzzing statistics. In parallel,
+    /// the output of the fuzzer is written into its log file.
+    #[throws]
+    async fn fuzz_target_session(
+        &self,
+        engine: Engine,
+        config: &Config,
+        target: &str,
+        time_budget: Option<u64>,
+        parallel: bool,
+    ) -> TargetSession {
+        let mut fuzzer = engine.create(&self.root, config.clone());
+        if let Some(seconds) = time_budget {
+            fuzzer.set_time_budget(seconds);
+        }
+        // the crash directory may be shared by the targets or contain crashes from previous runs
+        let previous_crashes: HashSet<PathBuf> = fuzzer.crash_files(target).into_iter().collect();
+
+        fuzzer.prepare(target)?;
+        if let Some(mut build) = fuzzer.build_command(target)? {
+            let mut child = build.spawn()?;
+            Self::handle_child(&mut child).await?;
+        }
+        let mut command = fuzzer.fuzz_command(target, parallel)?;
+
+        let started_at = SystemTime::now();
+        let start = Instant::now();
+        let stats = match parallel {
+            true => Self::spawn_with_log(&mut command, target, &fuzzer.log_file(target)).await?,
+            false => Self::spawn_with_stats(&mut command, target, None).await?,
+        };
+
+        let crash_files = fuzzer.crash_files(target);
+        let report = FuzzingReport::new(
+            target,
+            fuzzer.name(),
+            started_at,
+            start.elapsed(),
+            &stats,
+            &crash_files,
+        );
+        Self::write_report(config, &report);
+
+        let new_crashes = crash_files
+            .iter()
+            .filter(|crash_file| !previous_crashes.contains(*crash_file))
+            .count();
+        TargetSession { stats, new_crashes }
+    }
+
+    /// Spawns the fuzzing command with the stats directory and collects the fuzzing statistics, the output
+    /// of the fuzzer is written into the log file.
+    #[throws]
+    async fn spawn_with_log(
+        command: &mut Command,
+        target: &str,
+        log_file: &Path,
+    ) -> FuzzingStatistics {
+        let stats_dir = Self::stats_dir(target)?;
+        command.env(STATS_DIR_ENV, &stats_dir);
+
+        if let Some(parent) = log_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let log = std::fs::File::create(log_file)?;
+        let mut child = command.stdout(log.try_clone()?).stderr(log).spawn()?;
+        let result = Self::handle_child(&mut child).await;
+
+        let stats = stats_channel::collect(&stats_dir);
+        let _ = std::fs::remove_dir_all(&stats_dir);
+        result?;
+        stats
+    }
+}
+
+/// Returns the names of the `[[bin]]` targets of the Cargo.toml.
+#[throws]
+fn parse_bin_targets(cargo_toml: &str) -> Vec<String> {
+    let cargo_toml: toml::Value = toml::from_str(cargo_toml)?;
+    cargo_toml
+        .get("bin")
+        .and_then(|bins| bins.as_array())
+        .map(|bins| {
+            bins.iter()
+                .filter_map(|bin| bin.get("name").and_then(|name| name.as_str()))
+                .map(|name| name.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the time budget of every target. The targets without their own budget split the rest of the total
+/// budget in sequence, in parallel every target can use the whole total budget.
+#[throws]
+fn split_time_budget(
+    targets: &[String],
+    total_time: Option<u64>,
+    target_times: &HashMap<String, u64>,
+    parallel: bool,
+) -> Vec<Option<u64>> {
+    let Some(total_time) = total_time else {
+        return Ok(targets
+            .iter()
+            .map(|target| target_times.get(target).copied())
+            .collect());
+    };
+
+    let shared_by = targets
+        .iter()
+        .filter(|target| !target_times.contains_key(*target))
+        .count() as u64;
+    let shared = match parallel {
+        true => total_time,
+        false => {
+            let reserved: u64 = target_times.values().sum();
+            match total_time.checked_sub(reserved) {
+                Some(rest) if shared_by > 0 => rest / shared_by,
+                Some(rest) => rest,
+                None => throw!(Error::InvalidTimeBudget),
+            }
+        }
+    };
+    // the shared time is used only by the targets without their own time budget
+    if (shared_by > 0 && shared == 0) || target_times.values().any(|time| *time > total_time) {
+        throw!(Error::InvalidTimeBudget);
+    }
+
+    targets
+        .iter()
+        .map(|target| Some(target_times.get(target).copied().unwrap_or(shared)))
+        .collect()
+}
+
+/// Prints the combined summary of the fuzzing sessions.
+fn show_summary(summaries: &[TargetSummary]) {
+    let mut table = Table::new();
+    table.add_row(row![
+        "Target",
+        "Engine",
+        "Time Budget",
+        "Duration",
+        "Iterations",
+        "New Crashes",
+        "Status"
+    ]);
+    for summary in summaries {
+        let time_budget = summary.time_budget.map_or("-".to_string(), |seconds| {
+            format_duration(Duration::from_secs(seconds))
+        });
+        let duration = format_duration(summary.duration);
+        match &summary.result {
+            Ok(session) => table.add_row(row![
+                summary.target,
+                summary.engine,
+                time_budget,
+                duration,
+                session.stats.iterations,
+                session.new_crashes,
+                if session.new_crashes > 0 {
+                    "crashed"
+                } else {
+                    "ok"
+                },
+            ]),
+            Err(e) => table.add_row(row![
+                summary.target,
+                summary.engine,
+                time_budget,
+                duration,
+                "-",
+                "-",
+                format!("failed: {e}"),
+            ]),
+        };
+    }
+    table.printstd();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets() -> Vec<String> {
+        vec![
+            "fuzz_0".to_string(),
+            "fuzz_1".to_string(),
+            "fuzz_2".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_parse_bin_targets() {
+        let cargo_toml = r#"
+            [package]
+            name = "fuzz_tests"
+
+            [[bin]]
+            name = "fuzz_0"
+            path = "fuzz_0/test_fuzz.rs"
+
+            [[bin]]
+            name = "fuzz_1"
+            path = "fuzz_1/test_fuzz.rs"
+        "#;
+        assert_eq!(
+            parse_bin_targets(cargo_toml).unwrap(),
+            vec!["fuzz_0", "fuzz_1"]
+        );
+        assert!(parse_bin_targets("[package]\nname = \"fuzz_tests\"")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_run_all_afl_in_parallel() {
+        use trident_fuzz::config::{
+            afl::_Afl, fuzz::_Fuzz, honggfuzz::_HonggFuzz, libfuzzer::_LibFuzzer,
+        };
+
+        let root = std::env::temp_dir().join(format!("trident-run-all-{}", process::id()));
+        let fuzz_tests = root
+            .join(TESTS_WORKSPACE_DIRECTORY)
+            .join(FUZZ_TEST_DIRECTORY);
+        std::fs::create_dir_all(&fuzz_tests).unwrap();
+        std::fs::write(
+            fuzz_tests.join(CARGO_TOML),
+            "[[bin]]\nname = \"fuzz_0\"\n\n[[bin]]\nname = \"fuzz_1\"\n",
+        )
+        .unwrap();
+        // the seeds directory is a file, so the sessions fail before AFL is built
+        let afl_workspace_in = root.join("afl_in");
+        std::fs::write(&afl_workspace_in, "").unwrap();
+        let config = Config {
+            honggfuzz: _HonggFuzz::default().into(),
+            afl: _Afl {
+                afl_workspace_in: Some(afl_workspace_in.to_string_lossy().to_string()),
+                afl_workspace_out: Some(root.join("afl_out").to_string_lossy().to_string()),
+                ..Default::default()
+            }
+            .into(),
+            libfuzzer: _LibFuzzer::default().into(),
+            fuzz: _Fuzz::default().try_into().unwrap(),
+        };
+
+        // every target has its own AFL output directory, so the targets are fuzzed in parallel
+        let result = futures::executor::block_on(Commander::with_root(&root).run_all_with_config(
+            config,
+            Some(Engine::Afl),
+            Some(60),
+            vec![],
+            true,
+            false,
+        ));
+        assert!(matches!(result, Err(Error::FuzzingFailed)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_split_time_budget() {
+        let targets = targets();
+        let mut target_times = HashMap::new();
+
+        assert_eq!(
+            split_time_budget(&targets, None, &target_times, false).unwrap(),
+            vec![None, None, None]
+        );
+        assert_eq!(
+            split_time_budget(&targets, Some(900), &target_times, false).unwrap(),
+            vec![Some(300), Some(300), Some(300)]
+        );
+        assert_eq!(
+            split_time_budget(&targets, Some(900), &target_times, true).unwrap(),
+            vec![Some(900), Some(900), Some(900)]
+        );
+
+        target_times.insert("fuzz_1".to_string(), 500);
+        assert_eq!(
+            split_time_budget(&targets, Some(900), &target_times, false).unwrap(),
+            vec![Some(200), Some(500), Some(200)]
+        );
+        assert_eq!(
+            split_time_budget(&targets, None, &target_times, false).unwrap(),
+            vec![None, Some(500), None]
+        );
+        assert!(matches!(
+            split_time_budget(&targets, Some(400), &target_times, false),
+            Err(Error::InvalidTimeBudget)
+        ));
+        assert!(matches!(
+            split_time_budget(&targets, Some(501), &target_times, false),
+            Err(Error::InvalidTimeBudget)
+        ));
+
+        // the time budgets of all the targets sum exactly to the total time
+        target_times.insert("fuzz_0".to_string(), 300);
+        target_times.insert("fuzz_2".to_string(), 100);
+        assert_eq!(
+            split_time_budget(&targets, Some(900), &target_times, false).unwrap(),
+            vec![Some(300), Some(500), Some(100)]
+        );
+        assert!(matches!(
+            split_time_budget(&targets, Some(899), &target_times, false),
+            Err(Error::InvalidTimeBudget)
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+//! Converts the `Debug` output of the fuzz instructions into Rust expressions, so the instruction
+//! sequence of a crash can be written into a regression test as code instead of raw bytes.
+//!
+//! The types of the values are not known, so the collections and strings are converted by `.into()`
+//! and the target type is inferred from the field the value is assigned to, e.g. `[1, 2].into()`
+//! works for `Vec<u8>` as well as for `[u8; 2]`.
+
+/// Maximal length of a collection rendered on a single line.
+const MAX_INLINE_LENGTH: usize = 80;
+const INDENT: &str = "    ";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// Number, string or character literal as printed by `Debug`.
+    Literal(String),
+    Str(String),
+    Punct(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Struct(String, Vec<(String, Value)>),
+    Tuple(Option<String>, Vec<Value>),
+    List(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Ident(String),
+    Literal(String),
+    Str(String),
+}
+
+/// Converts the instruction formatted by `DisplayIx` (i.e. `Variant(<pretty Debug of the data>)`)
+/// into the Rust expression of the given instruction type, e.g. `FuzzInstruction::Deposit(Deposit { .. })`.
+/// Returns `None` if the output cannot be converted, e.g. if a custom `Debug` implementation is used.
+pub(super) fn instruction_expr(
+    instruction_type: &str,
+    instruction: &str,
+    indent: usize,
+) -> Option<String> {
+    let value = parse(instruction)?;
+    match &value {
+        Value::Tuple(Some(_), _) | Value::Ident(_) => {
+            Some(format!("{instruction_type}::{}", render(&value, indent)))
+        }
+        _ => None,
+    }
+}
+
+fn parse(debug: &str) -> Option<Value> {
+    let tokens = tokenize(debug)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let value = parser.value()?;
+    (parser.position == parser.tokens.len()).then_some(value)
+}
+
+fn tokenize(debug: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = debug.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '{' | '}' | '(' | ')' | '[' | ']' | ',' => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+            ':' if chars.get(i + 1) == Some(&':') => {
+                // paths printed by custom `Debug` implementations are kept as a part of the identifier
+                match tokens.last_mut() {
+                    Some(Token::Ident(ident)) => ident.push_str("::"),
+                    _ => return None,
+                }
+                i += 2;
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                match tokens.last_mut() {
+                    Some(Token::Ident(ident)) if i > start => {
+                        ident.extend(&chars[start..i]);
+                    }
+                    _ => return None,
+                }
+            }
+            ':' => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+            '"' | '\'' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return None;
+                }
+                i += 1;
+                let literal: String = chars[start..i].iter().collect();
+                tokens.push(match c {
+                    '"' => Token::Str(literal),
+                    _ => Token::Literal(literal),
+                });
+            }
+            _ if c.is_ascii_digit() || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric()
+                        || chars[i] == '.'
+                        || chars[i] == '_'
+                        || (matches!(chars[i], '-' | '+') && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Literal(chars[start..i].iter().collect()));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Option<()> {
+        self.eat(punct).then_some(())
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        match self.next()? {
+            Token::Ident(name) => {
+                if self.eat('{') {
+                    let fields = self.separated('}', |parser| {
+                        let Token::Ident(field) = parser.next()? else {
+                            return None;
+                        };
+                        parser.expect(':')?;
+                        Some((field, parser.value()?))
+                    })?;
+                    Some(Value::Struct(name, fields))
+                } else if self.eat('(') {
+                    let items = self.separated(')', Self::value)?;
+                    Some(Value::Tuple(Some(name), items))
+                } else {
+                    Some(Value::Ident(name))
+                }
+            }
+            Token::Punct('(') => Some(Value::Tuple(None, self.separated(')', Self::value)?)),
+            Token::Punct('[') => Some(Value::List(self.separated(']', Self::value)?)),
+            Token::Punct('{') => {
+                // maps are printed as `{key: value}`, sets as `{value}`
+                let elements = self.separated('}', |parser| {
+                    let key = parser.value()?;
+                    let value = match parser.eat(':') {
+                        true => Some(parser.value()?),
+                        false => None,
+                    };
+                    Some((key, value))
+                })?;
+                if elements.iter().all(|(_, value)| value.is_some()) {
+                    Some(Value::Map(
+                        elements
+                            .into_iter()
+                            .filter_map(|(key, value)| Some((key, value?)))
+                            .collect(),
+                    ))
+                } else if elements.iter().all(|(_, value)| value.is_none()) {
+                    Some(Value::List(
+                        elements.into_iter().map(|(key, _)| key).collect(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            Token::Literal(literal) => Some(Value::Literal(literal)),
+            Token::Str(literal) => Some(Value::Str(literal)),
+            Token::Punct(_) => None,
+        }
+    }
+
+    /// Parses the elements separated by commas until the closing delimiter, a trailing comma is allowed.
+    fn separated<T>(
+        &mut self,
+        close: char,
+        mut element: impl FnMut(&mut Self) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        let mut elements = vec![];
+        loop {
+            if self.eat(close) {
+                return Some(elements);
+            }
+            elements.push(element(self)?);
+            if !self.eat(',') {
+                self.expect(close)?;
+                return Some(elements);
+            }
+        }
+    }
+}
+
+fn render(value: &Value, indent: usize) -> String {
+    match value {
+        Value::Struct(name, fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(field, value)| {
+                    format!("{}: {}", field_name(field), render(value, indent + 1))
+                })
+                .collect();
+            format!("{name} {}", block('{', '}', &fields, indent, true))
+        }
+        // `Some(..)` and newtypes stay on the same line as their content
+        Value::Tuple(Some(name), items) if items.len() == 1 => {
+            format!("{name}({})", render(&items[0], indent))
+        }
+        Value::Tuple(name, items) => {
+            let items: Vec<String> = items.iter().map(|item| render(item, indent + 1)).collect();
+            let name = name.as_deref().unwrap_or_default();
+            format!("{name}{}", block('(', ')', &items, indent, false))
+        }
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| render(item, indent + 1)).collect();
+            format!("{}.into()", block('[', ']', &items, indent, false))
+        }
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "({}, {})",
+                        render(key, indent + 1),
+                        render(value, indent + 1)
+                    )
+                })
+                .collect();
+            format!("{}.into()", block('[', ']', &entries, indent, false))
+        }
+        Value::Ident(ident) => match ident.as_str() {
+            "NaN" => "f64::NAN as _".to_string(),
+            "inf" => "f64::INFINITY as _".to_string(),
+            _ => ident.clone(),
+        },
+        Value::Literal(literal) => match literal.as_str() {
+            "-inf" => "f64::NEG_INFINITY as _".to_string(),
+            _ => literal.clone(),
+        },
+        Value::Str(literal) => format!("{literal}.into()"),
+    }
+}
+
+/// Renders the elements within the delimiters, on a single line if they are short enough.
+/// Structs with fields are always rendered on multiple lines.
+fn block(open: char, close: char, elements: &[String], indent: usize, multiline: bool) -> String {
+    if elements.is_empty() {
+        return format!("{open}{close}");
+    }
+    let inline = elements.join(", ");
+    if !multiline && !inline.contains('\n') && inline.len() <= MAX_INLINE_LENGTH {
+        return format!("{open}{inline}{close}");
+    }
+    let nested = INDENT.repeat(indent + 1);
+    let mut output = format!("{open}\n");
+    for element in elements {
+        output.push_str(&format!("{nested}{element},\n"));
+    }
+    output.push_str(&format!("{}{close}", INDENT.repeat(indent)));
+    output
+}
+
+/// `Debug` prints the raw identifiers without the `r#` prefix.
+fn field_name(field: &str) -> String {
+    match syn::parse_str::<syn::Ident>(field) {
+        Ok(_) => field.to_string(),
+        Err(_) => format!("r#{field}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_expr() {
+        let instruction = r#"Deposit(Deposit {
+    accounts: DepositAccounts {
+        user: 0,
+    },
+    data: DepositData {
+        amount: 5,
+        ratio: NaN,
+        name: "alice \"a\"",
+        type: Some(
+            [
+                1,
+                -2,
+            ],
+        ),
+        limits: {
+            1: 2,
+        },
+        empty: [],
+        kind: Unit,
+        pair: (
+            1,
+            'x',
+        ),
+    },
+})"#;
+
+        assert_eq!(
+            instruction_expr("FuzzInstruction", instruction, 2).unwrap(),
+            "FuzzInstruction::Deposit(Deposit {
+            accounts: DepositAccounts {
+                user: 0,
+            },
+            data: DepositData {
+                amount: 5,
+                ratio: f64::NAN as _,
+                name: \"alice \\\"a\\\"\".into(),
+                r#type: Some([1, -2].into()),
+                limits: [(1, 2)].into(),
+                empty: [].into(),
+                kind: Unit,
+                pair: (1, 'x'),
+            },
+        })"
+        );
+        assert!(syn::parse_str::<syn::Expr>(
+            &instruction_expr("FuzzInstruction", instruction, 2).unwrap()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_debug_output() {
+        assert_eq!(
+            instruction_expr("FuzzInstruction", "Deposit(Deposit { .. })", 0),
+            None
+        );
+        assert_eq!(instruction_expr("FuzzInstruction", "[1, 2]", 0), None);
+        assert_eq!(
+            instruction_expr("FuzzInstruction", "Deposit(Deposit { amount: 5 }", 0),
+            None
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Entry point of the fuzz tests into the fuzzing engine they are built for. The Trident CLI builds
+//! the fuzz tests with the `--cfg` of the selected engine, so the engine is known at compile time.
+#![allow(unexpected_cfgs)]
+
+use std::panic::RefUnwindSafe;
+
+/// Name of the fuzzing engine the fuzz test is built for, `None` if it is built without any engine
+/// (e.g. to replay the inputs).
+pub const FUZZING_ENGINE: Option<&str> = if cfg!(honggfuzz) {
+    Some("honggfuzz")
+} else if cfg!(afl) {
+    Some("afl")
+} else if cfg!(libfuzzer) {
+    Some("libfuzzer")
+} else {
+    None
+};
+
+/// Fuzzes the closure with the fuzzing engine the fuzz test is built for.
+pub fn fuzz<F>(mut closure: F)
+where
+    F: FnMut(&[u8]) + RefUnwindSafe,
+{
+    match FUZZING_ENGINE {
+        Some("honggfuzz") => loop {
+            honggfuzz::fuzz(&mut closure);
+        },
+        Some("afl") => afl::fuzz(true, closure),
+        Some("libfuzzer") => super::libfuzzer::fuzz(closure),
+        _ => panic!(
+            "the fuzz test is not built for any fuzzing engine, run it with `trident fuzz run`"
+        ),
+    }
+}
@@ -7,8 +7,10 @@
 pub mod fuzzing {
     /// anchor_lang
     pub use anchor_lang;
+    pub use anchor_lang::prelude::borsh;
     pub use anchor_lang::solana_program::hash::Hash;
     pub use anchor_lang::AccountDeserialize;
+    pub use anchor_lang::AnchorSerialize;
     pub use anchor_lang::InstructionData;
     pub use anchor_lang::Key;
     pub use anchor_lang::ToAccountInfo;
@@ -33,6 +35,11 @@ pub mod fuzzing {
     pub use arbitrary::Arbitrary;
     pub use honggfuzz::fuzz as fuzz_honggfuzz;
 
+    pub use super::libfuzzer::fuzz as fuzz_libfuzzer;
+
+    pub use super::fuzzer::fuzz as fuzz_engine;
+    pub use super::fuzzer::FUZZING_ENGINE;
+
     /// trident derive
     pub use trident_derive_displayix::DisplayIx;
     pub use trident_derive_fuzz_test_executor::FuzzTestExecutor;
@@ -53,13 +60,17 @@ pub mod fuzzing {
     pub use super::temp_clone::*;
     /// trident methods
     pub use trident_fuzz::accounts_storage::*;
+    pub use trident_fuzz::compute_budget::ComputeBudget;
     pub use trident_fuzz::config::Config;
+    pub use trident_fuzz::crash_classifier::CrashKind;
+    pub use trident_fuzz::differential::Divergence;
     pub use trident_fuzz::error::*;
     pub use trident_fuzz::fuzz_client::FuzzClient;
     pub use trident_fuzz::fuzz_data::build_ix_fuzz_data;
     pub use trident_fuzz::fuzz_data::*;
     pub use trident_fuzz::fuzz_stats::FuzzingStatistics;
     pub use trident_fuzz::fuzz_test_executor::FuzzTestExecutor;
+    pub use trident_fuzz::ix_data::{RawData, ToIxData};
     pub use trident_fuzz::ix_ops::IxOps;
     pub use trident_fuzz::program_test_client_blocking::ProgramTestClientBlocking;
     pub use trident_fuzz::snapshot::Snapshot;
@@ -81,6 +92,9 @@ pub mod fuzzing {
 mod anchor_idl;
 mod cleaner;
 mod commander;
+mod fuzzer;
+mod libfuzzer;
+mod native_idl;
 mod source_code_generators;
 mod temp_clone;
 mod test_generator;
@@ -91,10 +105,13 @@ pub mod ___private {
     pub use super::anchor_idl::*;
     pub use super::cleaner::*;
     pub use super::commander::Commander;
+    pub use super::commander::Engine;
     pub use super::commander::Error;
+    pub use super::native_idl::*;
     pub use super::source_code_generators::*;
     pub use super::temp_clone::TempClone;
     pub use super::test_generator::TestGenerator;
+    pub use super::utils::cargo_workspace_root;
 }
 
 mod constants {
@@ -119,6 +136,16 @@ mod constants {
     pub const CARGO_TARGET_DIR_DEFAULT_AFL: &str =
         "trident-tests/fuzz_tests/fuzzing/afl/afl_target";
 
+    // libfuzzer
+    pub const CARGO_TARGET_DIR_DEFAULT_LIBFUZZER: &str =
+        "trident-tests/fuzz_tests/fuzzing/libfuzzer/libfuzzer_target";
+
+    // coverage
+    pub const COVERAGE_DIR_DEFAULT: &str = "trident-tests/fuzz_tests/fuzzing/coverage";
+
+    // fuzz tests built without a fuzzer, used to replay and minimize inputs
+    pub const REPLAY_TARGET_DIR_DEFAULT: &str = "trident-tests/fuzz_tests/fuzzing/replay_target";
+
     // workspace
     pub const GIT_IGNORE: &str = ".gitignore";
 
@@ -0,0 +1,82 @@
+//! libFuzzer support of the fuzz tests. The fuzz tests keep their own `main` function, so libFuzzer
+//! is started through the `LLVMFuzzerRunDriver` rather than through the `main` of libFuzzer.
+
+#[cfg(feature = "libfuzzer")]
+mod driver {
+    use std::cell::Cell;
+    use std::ffi::{c_char, c_int, c_void, CString};
+    use std::panic::{self, AssertUnwindSafe};
+
+    // the crate is not referenced otherwise, so it would not be linked
+    use libfuzzer_sys as _;
+
+    extern "C" {
+        fn LLVMFuzzerRunDriver(
+            argc: *mut c_int,
+            argv: *mut *mut *mut c_char,
+            callback: extern "C" fn(*const u8, usize) -> c_int,
+        ) -> c_int;
+    }
+
+    /// Type-erased closure of the fuzz test, the closure borrows from `main` so it cannot be `'static`.
+    type Callback = (*mut c_void, unsafe fn(*mut c_void, &[u8]));
+
+    thread_local! {
+        static CALLBACK: Cell<Option<Callback>> = const { Cell::new(None) };
+    }
+
+    unsafe fn call<F: FnMut(&[u8])>(closure: *mut c_void, data: &[u8]) {
+        (*closure.cast::<F>())(data)
+    }
+
+    /// Fuzzes the closure with libFuzzer, the command line arguments are passed to libFuzzer.
+    pub fn fuzz<F: FnMut(&[u8])>(mut closure: F) -> ! {
+        CALLBACK.set(Some((
+            (&mut closure as *mut F).cast::<c_void>(),
+            call::<F> as unsafe fn(*mut c_void, &[u8]),
+        )));
+
+        let args: Vec<CString> = std::env::args()
+            .map(|arg| CString::new(arg).expect("command line argument contains a nul byte"))
+            .collect();
+        let mut argv: Vec<*mut c_char> = args.iter().map(|arg| arg.as_ptr().cast_mut()).collect();
+        argv.push(std::ptr::null_mut());
+        let mut argc = args.len() as c_int;
+        let mut argv = argv.as_mut_ptr();
+
+        let code = unsafe { LLVMFuzzerRunDriver(&mut argc, &mut argv, test_one_input) };
+        CALLBACK.set(None);
+        std::process::exit(code)
+    }
+
+    extern "C" fn test_one_input(data: *const u8, size: usize) -> c_int {
+        let data = match size {
+            0 => &[],
+            _ => unsafe { std::slice::from_raw_parts(data, size) },
+        };
+        rust_fuzzer_test_input(data)
+    }
+
+    /// Required by the `LLVMFuzzerTestOneInput` of libfuzzer-sys, which is otherwise defined by its `fuzz_target!`.
+    #[no_mangle]
+    #[allow(improper_ctypes_definitions)]
+    pub extern "C" fn rust_fuzzer_test_input(data: &[u8]) -> i32 {
+        let Some((closure, call)) = CALLBACK.get() else {
+            return 0;
+        };
+        // the panic must not unwind into libFuzzer, the abort is reported by libFuzzer as a crash
+        if panic::catch_unwind(AssertUnwindSafe(|| unsafe { call(closure, data) })).is_err() {
+            std::process::abort();
+        }
+        0
+    }
+}
+
+#[cfg(feature = "libfuzzer")]
+pub use driver::fuzz;
+
+/// Fuzzes the closure with libFuzzer, which is linked only with the `libfuzzer` feature enabled.
+#[cfg(not(feature = "libfuzzer"))]
+pub fn fuzz<F: FnMut(&[u8])>(_closure: F) -> ! {
+    panic!("libFuzzer is not linked, build the fuzz test with `trident fuzz run-libfuzzer`");
+}
@@ -0,0 +1,700 @@
+use anchor_lang_idl_spec::{
+    Idl, IdlArrayLen, IdlDefinedFields, IdlEnumVariant, IdlField, IdlInstruction,
+    IdlInstructionAccount, IdlInstructionAccountItem, IdlMetadata, IdlSerialization, IdlType,
+    IdlTypeDef, IdlTypeDefTy, IDL_SPEC,
+};
+use cargo_metadata::Package;
+use convert_case::{Case, Casing};
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Loads the IDL of the native program package from `idl/<program>.json` within the workspace or
+/// the package directory, or from `idl.json` within the package directory.
+///
+/// Shank and Codama IDLs (as well as the Anchor IDLs of the programs not built with Anchor) are converted
+/// into the Anchor IDL, so the same fuzz test template is generated for them.
+pub fn load_native_idl(root: &Path, package: &Package, program_name: &str) -> Option<Idl> {
+    let package_dir = package.manifest_path.parent()?.as_std_path();
+    let idl_file = format!("{program_name}.json");
+
+    let path = [
+        root.join("idl").join(&idl_file),
+        package_dir.join("idl").join(&idl_file),
+        package_dir.join("idl.json"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())?;
+
+    let json_content = match fs::read_to_string(&path) {
+        Ok(json_content) => json_content,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    match parse_native_idl(&json_content) {
+        Ok(mut idl) => {
+            // the IDL names the program by its own convention, the fuzz test imports the crate
+            idl.metadata.name = program_name.to_string();
+            Some(idl)
+        }
+        Err(e) => {
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Parses the Shank, Codama or Anchor IDL into the Anchor IDL.
+pub fn parse_native_idl(json_content: &str) -> Result<Idl, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(json_content)?;
+
+    if let Some("codama" | "kinobi") = value.get("standard").and_then(Value::as_str) {
+        return codama_to_idl(serde_json::from_value(value)?);
+    }
+    if value.pointer("/metadata/spec").is_some() {
+        return Ok(serde_json::from_value(value)?);
+    }
+    let is_shank = value.pointer("/metadata/origin").and_then(Value::as_str) == Some("shank")
+        || value.pointer("/instructions/0/discriminant").is_some();
+    if is_shank {
+        return shank_to_idl(serde_json::from_value(value)?);
+    }
+    Err("unknown IDL format, expected a Shank, Codama or Anchor IDL".into())
+}
+
+#[derive(Deserialize)]
+struct ShankIdl {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    instructions: Vec<ShankInstruction>,
+    #[serde(default)]
+    types: Vec<ShankTypeDef>,
+    #[serde(default)]
+    metadata: ShankMetadata,
+}
+
+#[derive(Deserialize, Default)]
+struct ShankMetadata {
+    #[serde(default)]
+    address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ShankInstruction {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    accounts: Vec<ShankAccount>,
+    args: Vec<ShankField>,
+    discriminant: ShankDiscriminant,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShankAccount {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    #[serde(default)]
+    is_mut: bool,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default, alias = "isOptional")]
+    optional: bool,
+}
+
+#[derive(Deserialize)]
+struct ShankField {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    #[serde(rename = "type")]
+    ty: Value,
+}
+
+#[derive(Deserialize)]
+struct ShankDiscriminant {
+    #[serde(rename = "type")]
+    ty: String,
+    value: u64,
+}
+
+#[derive(Deserialize)]
+struct ShankTypeDef {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    #[serde(rename = "type")]
+    ty: ShankTypeDefTy,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum ShankTypeDefTy {
+    Struct { fields: Vec<ShankField> },
+    Enum { variants: Vec<ShankEnumVariant> },
+}
+
+#[derive(Deserialize)]
+struct ShankEnumVariant {
+    name: String,
+    /// Named fields or the types of the tuple fields.
+    #[serde(default)]
+    fields: Option<Vec<Value>>,
+}
+
+fn shank_to_idl(shank: ShankIdl) -> Result<Idl, Box<dyn Error>> {
+    let instructions = shank
+        .instructions
+        .into_iter()
+        .map(|instruction| {
+            Ok(IdlInstruction {
+                name: instruction.name.to_case(Case::Snake),
+                docs: instruction.docs,
+                discriminator: number_bytes(
+                    &instruction.discriminant.ty,
+                    instruction.discriminant.value,
+                )?,
+                accounts: instruction
+                    .accounts
+                    .into_iter()
+                    .map(|account| {
+                        instruction_account(
+                            account.name,
+                            account.docs,
+                            account.is_mut,
+                            account.is_signer,
+                            account.optional,
+                        )
+                    })
+                    .collect(),
+                args: instruction
+                    .args
+                    .into_iter()
+                    .map(shank_field)
+                    .collect::<Result<_, _>>()?,
+                returns: None,
+            })
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    let types = shank
+        .types
+        .into_iter()
+        .map(|type_def| {
+            let ty = match type_def.ty {
+                ShankTypeDefTy::Struct { fields } => IdlTypeDefTy::Struct {
+                    fields: Some(IdlDefinedFields::Named(
+                        fields
+                            .into_iter()
+                            .map(shank_field)
+                            .collect::<Result<_, _>>()?,
+                    )),
+                },
+                ShankTypeDefTy::Enum { variants } => IdlTypeDefTy::Enum {
+                    variants: variants
+                        .into_iter()
+                        .map(shank_enum_variant)
+                        .collect::<Result<_, _>>()?,
+                },
+            };
+            Ok(type_def_of(&type_def.name, type_def.docs, ty))
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    Ok(native_idl(
+        &shank.name,
+        shank.metadata.address.unwrap_or_default(),
+        shank.version,
+        shank.docs,
+        instructions,
+        types,
+    ))
+}
+
+fn shank_field(field: ShankField) -> Result<IdlField, Box<dyn Error>> {
+    Ok(IdlField {
+        name: field.name.to_case(Case::Snake),
+        docs: field.docs,
+        ty: shank_type(&field.ty)?,
+    })
+}
+
+fn shank_enum_variant(variant: ShankEnumVariant) -> Result<IdlEnumVariant, Box<dyn Error>> {
+    let fields = match variant.fields {
+        None => None,
+        Some(fields) if fields.iter().all(|field| field.get("name").is_some()) => {
+            Some(IdlDefinedFields::Named(
+                fields
+                    .into_iter()
+                    .map(|field| shank_field(serde_json::from_value(field)?))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        Some(fields) => Some(IdlDefinedFields::Tuple(
+            fields.iter().map(shank_type).collect::<Result<_, _>>()?,
+        )),
+    };
+    Ok(IdlEnumVariant {
+        name: variant.name.to_case(Case::UpperCamel),
+        fields,
+    })
+}
+
+/// Converts the type of the Shank IDL, i.e. of the legacy Anchor IDL format.
+fn shank_type(ty: &Value) -> Result<IdlType, Box<dyn Error>> {
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "publicKey" | "pubkey" => Ok(IdlType::Pubkey),
+            "bytes" => Ok(IdlType::Bytes),
+            "string" => Ok(IdlType::String),
+            name => number_type(name),
+        };
+    }
+
+    let unsupported = || format!("unsupported Shank type {ty}");
+    let (kind, inner) = ty
+        .as_object()
+        .and_then(|object| object.iter().next())
+        .ok_or_else(unsupported)?;
+    match kind.as_str() {
+        "vec" => Ok(IdlType::Vec(Box::new(shank_type(inner)?))),
+        "option" | "coption" => Ok(IdlType::Option(Box::new(shank_type(inner)?))),
+        "array" => match inner.as_array().map(Vec::as_slice) {
+            Some([item, len]) => Ok(IdlType::Array(
+                Box::new(shank_type(item)?),
+                IdlArrayLen::Value(len.as_u64().ok_or_else(unsupported)? as usize),
+            )),
+            _ => Err(unsupported().into()),
+        },
+        "defined" => {
+            let name = inner
+                .as_str()
+                .or_else(|| inner.get("name").and_then(Value::as_str))
+                .ok_or_else(unsupported)?;
+            Ok(defined_type(name))
+        }
+        _ => Err(unsupported().into()),
+    }
+}
+
+#[derive(Deserialize)]
+struct CodamaRoot {
+    program: CodamaProgram,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodamaProgram {
+    name: String,
+    public_key: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    instructions: Vec<CodamaInstruction>,
+    #[serde(default)]
+    defined_types: Vec<CodamaDefinedType>,
+}
+
+#[derive(Deserialize)]
+struct CodamaInstruction {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    accounts: Vec<CodamaAccount>,
+    arguments: Vec<CodamaArgument>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodamaAccount {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    is_writable: bool,
+    /// `true`, `false` or `"either"` for the optional signers.
+    is_signer: Value,
+    #[serde(default)]
+    is_optional: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodamaArgument {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    #[serde(rename = "type")]
+    ty: Value,
+    #[serde(default)]
+    default_value: Option<Value>,
+    #[serde(default)]
+    default_value_strategy: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CodamaDefinedType {
+    name: String,
+    #[serde(default)]
+    docs: Vec<String>,
+    #[serde(rename = "type")]
+    ty: Value,
+}
+
+fn codama_to_idl(root: CodamaRoot) -> Result<Idl, Box<dyn Error>> {
+    let program = root.program;
+
+    let instructions = program
+        .instructions
+        .into_iter()
+        .map(|instruction| {
+            // the arguments omitted from the instruction builders precede the other arguments, they make up the discriminator
+            let mut discriminator = vec![];
+            let mut args = vec![];
+            for argument in instruction.arguments {
+                if argument.default_value_strategy.as_deref() != Some("omitted") {
+                    args.push(IdlField {
+                        name: argument.name.to_case(Case::Snake),
+                        docs: argument.docs,
+                        ty: codama_type(&argument.ty)?,
+                    });
+                    continue;
+                }
+                if !args.is_empty() {
+                    return Err(format!(
+                        "omitted argument `{}` of `{}` following the other arguments is not supported",
+                        argument.name, instruction.name
+                    )
+                    .into());
+                }
+                discriminator.extend(codama_value_bytes(
+                    &argument.ty,
+                    argument.default_value.as_ref(),
+                )?);
+            }
+
+            Ok(IdlInstruction {
+                name: instruction.name.to_case(Case::Snake),
+                docs: instruction.docs,
+                discriminator,
+                accounts: instruction
+                    .accounts
+                    .into_iter()
+                    .map(|account| {
+                        instruction_account(
+                            account.name,
+                            account.docs,
+                            account.is_writable,
+                            account.is_signer == Value::Bool(true),
+                            account.is_optional,
+                        )
+                    })
+                    .collect(),
+                args,
+                returns: None,
+            })
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    let types = program
+        .defined_types
+        .into_iter()
+        .map(|defined_type| {
+            let ty = match kind(&defined_type.ty) {
+                "structTypeNode" => IdlTypeDefTy::Struct {
+                    fields: Some(IdlDefinedFields::Named(codama_fields(&defined_type.ty)?)),
+                },
+                "enumTypeNode" => IdlTypeDefTy::Enum {
+                    variants: items(&defined_type.ty, "variants")
+                        .iter()
+                        .map(codama_enum_variant)
+                        .collect::<Result<_, _>>()?,
+                },
+                _ => IdlTypeDefTy::Type {
+                    alias: codama_type(&defined_type.ty)?,
+                },
+            };
+            Ok(type_def_of(&defined_type.name, defined_type.docs, ty))
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    Ok(native_idl(
+        &program.name,
+        program.public_key,
+        program.version,
+        program.docs,
+        instructions,
+        types,
+    ))
+}
+
+fn codama_fields(struct_node: &Value) -> Result<Vec<IdlField>, Box<dyn Error>> {
+    items(struct_node, "fields")
+        .iter()
+        .map(|field| {
+            Ok(IdlField {
+                name: string(field, "name").to_case(Case::Snake),
+                docs: serde_json::from_value(field.get("docs").cloned().unwrap_or_default())
+                    .unwrap_or_default(),
+                ty: codama_type(&field["type"])?,
+            })
+        })
+        .collect()
+}
+
+fn codama_enum_variant(variant: &Value) -> Result<IdlEnumVariant, Box<dyn Error>> {
+    let fields = match kind(variant) {
+        "enumStructVariantTypeNode" => {
+            Some(IdlDefinedFields::Named(codama_fields(&variant["struct"])?))
+        }
+        "enumTupleVariantTypeNode" => Some(IdlDefinedFields::Tuple(
+            items(&variant["tuple"], "items")
+                .iter()
+                .map(codama_type)
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => None,
+    };
+    Ok(IdlEnumVariant {
+        name: string(variant, "name").to_case(Case::UpperCamel),
+        fields,
+    })
+}
+
+/// Converts the type node of the Codama IDL.
+fn codama_type(node: &Value) -> Result<IdlType, Box<dyn Error>> {
+    match kind(node) {
+        "numberTypeNode" => number_type(string(node, "format")),
+        "booleanTypeNode" => Ok(IdlType::Bool),
+        "publicKeyTypeNode" => Ok(IdlType::Pubkey),
+        "stringTypeNode" => Ok(IdlType::String),
+        "bytesTypeNode" => Ok(IdlType::Bytes),
+        "definedTypeLinkNode" => Ok(defined_type(string(node, "name"))),
+        "optionTypeNode" | "zeroableOptionTypeNode" | "remainderOptionTypeNode" => {
+            Ok(IdlType::Option(Box::new(codama_type(&node["item"])?)))
+        }
+        "arrayTypeNode" | "setTypeNode" => {
+            let item = Box::new(codama_type(&node["item"])?);
+            match node["count"]["value"].as_u64() {
+                Some(len) if kind(&node["count"]) == "fixedCountNode" => {
+                    Ok(IdlType::Array(item, IdlArrayLen::Value(len as usize)))
+                }
+                _ => Ok(IdlType::Vec(item)),
+            }
+        }
+        "fixedSizeTypeNode" => match (kind(&node["type"]), node["size"].as_u64()) {
+            ("bytesTypeNode", Some(size)) => Ok(IdlType::Array(
+                Box::new(IdlType::U8),
+                IdlArrayLen::Value(size as usize),
+            )),
+            _ => codama_type(&node["type"]),
+        },
+        "sizePrefixTypeNode" | "hiddenPrefixTypeNode" | "hiddenSuffixTypeNode" => {
+            codama_type(&node["type"])
+        }
+        "amountTypeNode" | "dateTimeTypeNode" | "solAmountTypeNode" => codama_type(&node["number"]),
+        kind => Err(format!("unsupported Codama type node `{kind}`").into()),
+    }
+}
+
+/// Serializes the default value of the omitted argument into the discriminator bytes.
+fn codama_value_bytes(ty: &Value, value: Option<&Value>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let value = value.ok_or("omitted argument without the default value")?;
+    match kind(value) {
+        "numberValueNode" => {
+            let number = value["number"]
+                .as_u64()
+                .ok_or("invalid discriminator number")?;
+            number_bytes(string(ty, "format"), number)
+        }
+        "bytesValueNode" => {
+            let data = string(value, "data");
+            match string(value, "encoding") {
+                "base16" => (0..data.len())
+                    .step_by(2)
+                    .map(|i| {
+                        data.get(i..i + 2)
+                            .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                            .ok_or_else(|| format!("invalid base16 discriminator `{data}`").into())
+                    })
+                    .collect(),
+                "utf8" => Ok(data.as_bytes().to_vec()),
+                encoding => Err(format!("unsupported discriminator encoding `{encoding}`").into()),
+            }
+        }
+        kind => Err(format!("unsupported discriminator value node `{kind}`").into()),
+    }
+}
+
+fn kind(node: &Value) -> &str {
+    string(node, "kind")
+}
+
+fn string<'a>(node: &'a Value, key: &str) -> &'a str {
+    node.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn items<'a>(node: &'a Value, key: &str) -> &'a [Value] {
+    node.get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn number_type(format: &str) -> Result<IdlType, Box<dyn Error>> {
+    match format {
+        "bool" => Ok(IdlType::Bool),
+        "u8" => Ok(IdlType::U8),
+        "i8" => Ok(IdlType::I8),
+        "u16" | "shortU16" => Ok(IdlType::U16),
+        "i16" => Ok(IdlType::I16),
+        "u32" => Ok(IdlType::U32),
+        "i32" => Ok(IdlType::I32),
+        "f32" => Ok(IdlType::F32),
+        "u64" => Ok(IdlType::U64),
+        "i64" => Ok(IdlType::I64),
+        "f64" => Ok(IdlType::F64),
+        "u128" => Ok(IdlType::U128),
+        "i128" => Ok(IdlType::I128),
+        format => Err(format!("unsupported type `{format}`").into()),
+    }
+}
+
+/// Little-endian bytes of the discriminator of the given integer type.
+fn number_bytes(format: &str, value: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+    let size = match format {
+        "u8" => 1,
+        "u16" => 2,
+        "u32" => 4,
+        "u64" => 8,
+        format => return Err(format!("unsupported discriminator type `{format}`").into()),
+    };
+    if size < 8 && value >> (size * 8) != 0 {
+        return Err(format!("discriminator {value} does not fit into `{format}`").into());
+    }
+    Ok(value.to_le_bytes()[..size].to_vec())
+}
+
+fn defined_type(name: &str) -> IdlType {
+    IdlType::Defined {
+        name: name.to_case(Case::UpperCamel),
+        generics: vec![],
+    }
+}
+
+fn instruction_account(
+    name: String,
+    docs: Vec<String>,
+    writable: bool,
+    signer: bool,
+    optional: bool,
+) -> IdlInstructionAccountItem {
+    IdlInstructionAccountItem::Single(IdlInstructionAccount {
+        name: name.to_case(Case::Snake),
+        docs,
+        writable,
+        signer,
+        optional,
+        address: None,
+        pda: None,
+        relations: vec![],
+    })
+}
+
+fn type_def_of(name: &str, docs: Vec<String>, ty: IdlTypeDefTy) -> IdlTypeDef {
+    IdlTypeDef {
+        name: name.to_case(Case::UpperCamel),
+        docs,
+        serialization: IdlSerialization::Borsh,
+        repr: None,
+        generics: vec![],
+        ty,
+    }
+}
+
+fn native_idl(
+    name: &str,
+    address: String,
+    version: String,
+    docs: Vec<String>,
+    instructions: Vec<IdlInstruction>,
+    types: Vec<IdlTypeDef>,
+) -> Idl {
+    Idl {
+        address,
+        metadata: IdlMetadata {
+            name: name.to_case(Case::Snake),
+            version,
+            spec: IDL_SPEC.to_string(),
+            description: None,
+            repository: None,
+            dependencies: vec![],
+            contact: None,
+            deployments: None,
+        },
+        docs,
+        instructions,
+        accounts: vec![],
+        events: vec![],
+        errors: vec![],
+        types,
+        constants: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shank_type() {
+        let ty: Value = serde_json::json!({ "option": { "array": ["publicKey", 2] } });
+        assert_eq!(
+            shank_type(&ty).unwrap(),
+            IdlType::Option(Box::new(IdlType::Array(
+                Box::new(IdlType::Pubkey),
+                IdlArrayLen::Value(2)
+            )))
+        );
+        assert_eq!(
+            shank_type(&serde_json::json!({ "defined": "create_args" })).unwrap(),
+            defined_type("CreateArgs")
+        );
+        assert!(shank_type(&serde_json::json!({ "hashMap": ["u8", "u8"] })).is_err());
+    }
+
+    #[test]
+    fn test_codama_discriminator() {
+        let u16_type = serde_json::json!({ "kind": "numberTypeNode", "format": "u16" });
+        let number = serde_json::json!({ "kind": "numberValueNode", "number": 258 });
+        assert_eq!(
+            codama_value_bytes(&u16_type, Some(&number)).unwrap(),
+            vec![2, 1]
+        );
+
+        let bytes_type = serde_json::json!({ "kind": "fixedSizeTypeNode", "size": 8 });
+        let bytes = serde_json::json!({
+            "kind": "bytesValueNode",
+            "data": "afaf6d1f0d989bed",
+            "encoding": "base16"
+        });
+        assert_eq!(
+            codama_value_bytes(&bytes_type, Some(&bytes)).unwrap(),
+            vec![0xaf, 0xaf, 0x6d, 0x1f, 0x0d, 0x98, 0x9b, 0xed]
+        );
+
+        let u8_type = serde_json::json!({ "kind": "numberTypeNode", "format": "u8" });
+        let overflow = serde_json::json!({ "kind": "numberValueNode", "number": 256 });
+        assert!(codama_value_bytes(&u8_type, Some(&overflow)).is_err());
+    }
+}
@@ -1,16 +1,23 @@
-use anchor_lang_idl_spec::{Idl, IdlInstructionAccountItem, IdlType};
+use anchor_lang_idl_spec::{
+    Idl, IdlDefinedFields, IdlField, IdlInstruction, IdlInstructionAccountItem, IdlType,
+    IdlTypeDefTy,
+};
 use convert_case::{Case, Casing};
 use quote::{format_ident, quote, ToTokens};
 use std::collections::{HashMap, HashSet};
 use syn::{parse_quote, parse_str};
 
+use super::{program_address, ProgramFramework, ProgramIdl};
+
 // Main function to generate source code from IDLs
-pub fn generate_source_code(idls: &[Idl]) -> String {
+pub fn generate_source_code(idls: &[ProgramIdl]) -> String {
     // Collections to store generated items
     let mut all_instructions: Vec<syn::Variant> = Vec::new();
     let mut all_instruction_inputs: Vec<syn::ItemStruct> = Vec::new();
     let mut all_instructions_ixops_impls: Vec<syn::ItemImpl> = Vec::new();
     let mut all_fuzz_accounts: Vec<syn::FnArg> = Vec::new();
+    let mut all_defined_types: Vec<syn::Item> = Vec::new();
+    let mut defined_type_names: HashSet<String> = HashSet::new();
 
     // Mappings for instructions and accounts
     let mut instructions_mappings: HashMap<String, u8> = HashMap::new();
@@ -20,11 +27,26 @@ pub fn generate_source_code(idls: &[Idl]) -> String {
     get_unique_accounts_n_instructions(idls, &mut instructions_mappings, &mut accounts_mappings);
 
     // Iterate over each IDL to generate various parts of the code
-    for idl in idls {
+    for ProgramIdl { idl, framework } in idls {
+        let serializable_types = get_serializable_types(idl);
+
         all_instructions.extend(get_instruction_variants(idl, &instructions_mappings));
         all_instruction_inputs.extend(get_instruction_inputs(idl, &instructions_mappings));
-        all_instructions_ixops_impls.extend(get_instruction_ixops(idl, &instructions_mappings));
+        all_instructions_ixops_impls.extend(get_instruction_ixops(
+            idl,
+            *framework,
+            &instructions_mappings,
+            &serializable_types,
+        ));
         all_fuzz_accounts.extend(get_fuzz_accounts(idl, &accounts_mappings));
+        // the custom types of the native programs are known only from their IDL
+        if *framework != ProgramFramework::Anchor {
+            all_defined_types.extend(get_defined_types(
+                idl,
+                &serializable_types,
+                &mut defined_type_names,
+            ));
+        }
     }
 
     // Define the Rust module with all generated code
@@ -40,6 +62,8 @@ pub fn generate_source_code(idls: &[Idl]) -> String {
 
         #(#all_instruction_inputs)*
 
+        #(#all_defined_types)*
+
         #(#all_instructions_ixops_impls)*
 
         /// Check supported AccountsStorages at
@@ -56,11 +80,11 @@ pub fn generate_source_code(idls: &[Idl]) -> String {
 
 // Function to get unique accounts and instructions across all IDLs
 fn get_unique_accounts_n_instructions(
-    idls: &[Idl],
+    idls: &[ProgramIdl],
     instructions_mappings: &mut HashMap<String, u8>,
     accounts_mappings: &mut HashMap<String, u8>,
 ) {
-    for idl in idls {
+    for ProgramIdl { idl, .. } in idls {
         let mut seen_accounts: HashSet<String> = HashSet::new();
 
         for instruction in idl.instructions.iter() {
@@ -149,7 +173,7 @@ fn get_instruction_inputs(
                         panic!("Composite Accounts are not supported yet!")
                     }
                     IdlInstructionAccountItem::Single(single) => {
-                        let name = format_ident!("{}", single.name);
+                        let name = account_ident(&single.name);
                         let account: syn::FnArg = parse_quote!(#name: AccountId);
                         account
                     }
@@ -204,7 +228,9 @@ fn get_instruction_inputs(
 // Generate implementation of IxOps trait for each instruction
 fn get_instruction_ixops(
     idl: &Idl,
+    framework: ProgramFramework,
     instruction_mappings: &HashMap<String, u8>,
+    serializable_types: &HashSet<String>,
 ) -> Vec<syn::ItemImpl> {
     let module_name: syn::Ident = parse_str(&idl.metadata.name).unwrap();
     let program_name = idl.metadata.name.to_case(Case::UpperCamel);
@@ -224,38 +250,43 @@ fn get_instruction_ixops(
             let instruction_ident_name_modified: syn::Ident =
                 format_ident!("{}", &instruction_name);
 
-            // Map arguments to their types
-            let parameters = instruction
-                .args
-                .iter()
-                .map(|arg| {
-                    let arg_name = format_ident!("{}", arg.name);
-                    let parameter: syn::FieldValue = match arg.ty {
-                        IdlType::Pubkey => parse_quote!(#arg_name: todo!()),
-                        IdlType::String => {
-                            let arg_value: syn::Expr = parse_quote!(self.data.#arg_name.clone());
-                            parse_quote!(#arg_name: #arg_value)
-                        }
-                        IdlType::Bytes => {
-                            let arg_value: syn::Expr = parse_quote!(self.data.#arg_name.clone());
-                            parse_quote!(#arg_name: #arg_value)
-                        }
-                        IdlType::Vec(_) => {
-                            let arg_value: syn::Expr = parse_quote!(self.data.#arg_name.clone());
-                            parse_quote!(#arg_name: #arg_value)
-                        }
-                        IdlType::Defined {
-                            name: _,
-                            generics: _,
-                        } => parse_quote!(#arg_name: todo!()),
-                        _ => {
-                            let arg_value: syn::Expr = parse_quote!(self.data.#arg_name);
-                            parse_quote!(#arg_name: #arg_value)
-                        }
-                    };
-                    parameter
-                })
-                .collect::<Vec<_>>();
+            // Anchor instructions are defined by the program, the other programs receive the raw bytes
+            let (ix_data_type, ix_data): (syn::Type, Vec<syn::Stmt>) = match framework {
+                ProgramFramework::Anchor => {
+                    let parameters = instruction.args.iter().map(|arg| {
+                        let arg_name = format_ident!("{}", arg.name);
+                        let arg_value = get_arg_value(arg);
+                        let parameter: syn::FieldValue = parse_quote!(#arg_name: #arg_value);
+                        parameter
+                    });
+                    (
+                        parse_quote!(#module_name::instruction::#instruction_ident_name),
+                        vec![parse_quote! {
+                            let data = #module_name::instruction::#instruction_ident_name {
+                                #(#parameters),*
+                            };
+                        }],
+                    )
+                }
+                ProgramFramework::Native | ProgramFramework::Pinocchio => (
+                    parse_quote!(RawData),
+                    get_raw_data(instruction, serializable_types),
+                ),
+            };
+
+            let get_accounts = match framework {
+                ProgramFramework::Anchor => get_anchor_accounts(),
+                ProgramFramework::Native | ProgramFramework::Pinocchio => {
+                    get_native_accounts(instruction)
+                }
+            };
+
+            let program_id: syn::Expr = match framework {
+                ProgramFramework::Anchor | ProgramFramework::Native => {
+                    parse_quote!(#module_name::ID)
+                }
+                ProgramFramework::Pinocchio => program_address(idl),
+            };
 
             let doc_comment = format!(
                 "IxOps implementation for `{}` with all required functions.",
@@ -264,12 +295,12 @@ fn get_instruction_ixops(
             let ix_impl: syn::ItemImpl = parse_quote! {
                 #[doc = #doc_comment]
                 impl IxOps for #instruction_ident_name_modified {
-                    type IxData = #module_name::instruction::#instruction_ident_name;
+                    type IxData = #ix_data_type;
                     type IxAccounts = FuzzAccounts;
 
                     /// Definition of the program ID that the Instruction is associated with.
                     fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
-                        #module_name::ID
+                        #program_id
                     }
 
                     /// Definition of the Instruction data.
@@ -281,36 +312,376 @@ fn get_instruction_ixops(
                         _client: &mut impl FuzzClient,
                         _fuzz_accounts: &mut FuzzAccounts,
                     ) -> Result<Self::IxData, FuzzingError> {
-                        let data = #module_name::instruction::#instruction_ident_name {
-                            #(#parameters),*
-                        };
+                        #(#ix_data)*
                         Ok(data)
                     }
 
-                    /// Definition of of the accounts required by the Instruction.
-                    /// To utilize accounts stored in `FuzzAccounts`, use
-                    /// `fuzz_accounts.account_name.get_or_create_account()`.
-                    /// If no signers are required, leave the vector empty.
-                    /// For AccountMetas use <program>::accounts::<corresponding_metas>
-                    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
-                    fn get_accounts(
-                    &self,
-                    client: &mut impl FuzzClient,
-                    fuzz_accounts: &mut FuzzAccounts,
-                    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+                    #get_accounts
+                }
+            };
 
-                        let signers = vec![todo!()];
+            instructions_ixops_impl.push(ix_impl);
+            instructions_ixops_impl
+        })
+}
 
-                        let acc_meta = todo!();
+// The accounts of Anchor instructions are resolved by the user into the accounts struct of the program
+fn get_anchor_accounts() -> syn::ImplItemFn {
+    parse_quote! {
+        /// Definition of of the accounts required by the Instruction.
+        /// To utilize accounts stored in `FuzzAccounts`, use
+        /// `fuzz_accounts.account_name.get_or_create_account()`.
+        /// If no signers are required, leave the vector empty.
+        /// For AccountMetas use <program>::accounts::<corresponding_metas>
+        /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+        fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+        ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+
+            let signers = vec![todo!()];
+
+            let acc_meta = todo!();
+
+            Ok((signers, acc_meta))
+        }
+    }
+}
 
-                        Ok((signers, acc_meta))
+// The account metas of native instructions are built from the order and the flags of the accounts within the IDL
+fn get_native_accounts(instruction: &IdlInstruction) -> syn::ImplItemFn {
+    let mut accounts: Vec<syn::Stmt> = vec![];
+    let mut signers: Vec<syn::Ident> = vec![];
+    let mut optional_signers: Vec<syn::Ident> = vec![];
+    let mut acc_metas: Vec<syn::Expr> = vec![];
+    for account in &instruction.accounts {
+        let IdlInstructionAccountItem::Single(single) = account else {
+            panic!("Composite Accounts are not supported yet!")
+        };
+        let name = account_ident(&single.name);
+        let is_signer = single.signer;
+        let pubkey: syn::Expr = match is_signer {
+            true => parse_quote!(#name.pubkey()),
+            false => parse_quote!(#name),
+        };
+        let acc_meta: syn::Expr = match single.writable {
+            true => parse_quote!(AccountMeta::new(#pubkey, #is_signer)),
+            false => parse_quote!(AccountMeta::new_readonly(#pubkey, #is_signer)),
+        };
+        // the keypairs are moved into the signers afterwards
+        let (ty, account_ref): (syn::Type, syn::Expr) = match is_signer {
+            true => (parse_quote!(Keypair), parse_quote!(&#name)),
+            false => (parse_quote!(Pubkey), parse_quote!(#name)),
+        };
+
+        // the omitted optional accounts are replaced by the program ID
+        match single.optional {
+            true => {
+                accounts.push(parse_quote!(let #name: Option<#ty> = todo!();));
+                acc_metas.push(parse_quote! {
+                    match #account_ref {
+                        Some(#name) => #acc_meta,
+                        None => AccountMeta::new_readonly(self.get_program_id(), false),
                     }
+                });
+                if is_signer {
+                    optional_signers.push(name);
                 }
-            };
+            }
+            false => {
+                accounts.push(parse_quote!(let #name: #ty = todo!();));
+                acc_metas.push(acc_meta);
+                if is_signer {
+                    signers.push(name);
+                }
+            }
+        }
+    }
 
-            instructions_ixops_impl.push(ix_impl);
-            instructions_ixops_impl
+    let signers: Vec<syn::Stmt> = match optional_signers.is_empty() {
+        true => vec![parse_quote!(let signers = vec![#(#signers),*];)],
+        false => std::iter::once(parse_quote!(let mut signers = vec![#(#signers),*];))
+            .chain(
+                optional_signers
+                    .iter()
+                    .map(|name| parse_quote!(signers.extend(#name);)),
+            )
+            .collect(),
+    };
+
+    parse_quote! {
+        /// Definition of of the accounts required by the Instruction.
+        /// To utilize accounts stored in `FuzzAccounts`, use
+        /// `fuzz_accounts.account_name.get_or_create_account()`.
+        /// The AccountMetas follow the order and flags of the IDL accounts.
+        /// Return the keypairs of the signer accounts as the signers.
+        /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+        fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+        ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+            #(#accounts)*
+
+            let acc_meta = vec![#(#acc_metas),*];
+
+            #(#signers)*
+
+            Ok((signers, acc_meta))
+        }
+    }
+}
+
+// Identifier of the account, the names which are Rust keywords are used as raw identifiers
+fn account_ident(name: &str) -> syn::Ident {
+    parse_str(name).unwrap_or_else(|_| format_ident!("r#{}", name))
+}
+
+// Map the instruction argument to its value from the fuzzer input
+fn get_arg_value(arg: &IdlField) -> syn::Expr {
+    let arg_name = format_ident!("{}", arg.name);
+    match arg.ty {
+        IdlType::Pubkey => parse_quote!(todo!()),
+        IdlType::String | IdlType::Bytes | IdlType::Vec(_) => {
+            parse_quote!(self.data.#arg_name.clone())
+        }
+        IdlType::Defined {
+            name: _,
+            generics: _,
+        } => parse_quote!(todo!()),
+        _ => parse_quote!(self.data.#arg_name),
+    }
+}
+
+// Instruction data of a program not built with Anchor, the discriminator followed by the Borsh serialized arguments
+fn get_raw_data(
+    instruction: &IdlInstruction,
+    serializable_types: &HashSet<String>,
+) -> Vec<syn::Stmt> {
+    let discriminator = &instruction.discriminator;
+    match instruction.args.as_slice() {
+        // the instruction without discriminator taking only bytes passes them to the program as they are
+        [IdlField {
+            name,
+            ty: IdlType::Bytes,
+            ..
+        }] if discriminator.is_empty() => {
+            let arg_name = format_ident!("{}", name);
+            vec![parse_quote!(let data = RawData(self.data.#arg_name.clone());)]
+        }
+        [] => vec![parse_quote!(let data = RawData::borsh(&[#(#discriminator),*], &());)],
+        args => {
+            let mut arg_types: Vec<syn::Type> = vec![];
+            let mut arg_values: Vec<syn::Expr> = vec![];
+            for arg in args {
+                if is_serializable(&arg.ty, serializable_types) {
+                    arg_types.push(idl_type_to_syn_type(&arg.ty, 0).0);
+                    arg_values.push(get_native_arg_value(arg));
+                } else if contains_defined_type(&arg.ty) {
+                    // the custom type with public keys has to be converted into the type defined in the program
+                    return vec![parse_quote!(let data = RawData(todo!());)];
+                } else {
+                    // the public keys are resolved from the accounts storages
+                    arg_types.push(program_type(&arg.ty));
+                    arg_values.push(parse_quote!(todo!()));
+                }
+            }
+            vec![
+                parse_quote!(let args: (#(#arg_types,)*) = (#(#arg_values,)*);),
+                parse_quote!(let data = RawData::borsh(&[#(#discriminator),*], &args);),
+            ]
+        }
+    }
+}
+
+// Map the argument of the native program to its value from the fuzzer input
+fn get_native_arg_value(arg: &IdlField) -> syn::Expr {
+    let arg_name = format_ident!("{}", arg.name);
+    match is_copy(&arg.ty) {
+        true => parse_quote!(self.data.#arg_name),
+        false => parse_quote!(self.data.#arg_name.clone()),
+    }
+}
+
+fn is_copy(ty: &IdlType) -> bool {
+    match ty {
+        IdlType::Option(inner) | IdlType::Array(inner, _) => is_copy(inner),
+        IdlType::Bytes
+        | IdlType::String
+        | IdlType::Vec(_)
+        | IdlType::Defined { .. }
+        | IdlType::Generic(_) => false,
+        _ => true,
+    }
+}
+
+// Returns the names of the custom types which can be serialized as they are generated, i.e. without public keys
+fn get_serializable_types(idl: &Idl) -> HashSet<String> {
+    let mut serializable_types = HashSet::new();
+    loop {
+        let serializable: Vec<String> = idl
+            .types
+            .iter()
+            .filter(|type_def| !serializable_types.contains(&type_def.name))
+            .filter(|type_def| {
+                let field_types: Vec<&IdlType> = match &type_def.ty {
+                    IdlTypeDefTy::Struct { fields } => defined_field_types(fields.as_ref()),
+                    IdlTypeDefTy::Enum { variants } => variants
+                        .iter()
+                        .flat_map(|variant| defined_field_types(variant.fields.as_ref()))
+                        .collect(),
+                    IdlTypeDefTy::Type { alias } => vec![alias],
+                };
+                field_types
+                    .into_iter()
+                    .all(|ty| is_serializable(ty, &serializable_types))
+            })
+            .map(|type_def| type_def.name.clone())
+            .collect();
+        if serializable.is_empty() {
+            return serializable_types;
+        }
+        serializable_types.extend(serializable);
+    }
+}
+
+fn defined_field_types(fields: Option<&IdlDefinedFields>) -> Vec<&IdlType> {
+    match fields {
+        None => vec![],
+        Some(IdlDefinedFields::Named(fields)) => fields.iter().map(|field| &field.ty).collect(),
+        Some(IdlDefinedFields::Tuple(types)) => types.iter().collect(),
+    }
+}
+
+// The public keys are represented by the `AccountId`s within the fuzzer input
+fn is_serializable(ty: &IdlType, serializable_types: &HashSet<String>) -> bool {
+    match ty {
+        IdlType::Pubkey | IdlType::Generic(_) => false,
+        IdlType::Option(inner) | IdlType::Vec(inner) | IdlType::Array(inner, _) => {
+            is_serializable(inner, serializable_types)
+        }
+        IdlType::Defined { name, .. } => serializable_types.contains(name),
+        _ => true,
+    }
+}
+
+fn contains_defined_type(ty: &IdlType) -> bool {
+    match ty {
+        IdlType::Option(inner) | IdlType::Vec(inner) | IdlType::Array(inner, _) => {
+            contains_defined_type(inner)
+        }
+        IdlType::Defined { .. } => true,
+        _ => false,
+    }
+}
+
+// Type of the argument within the program, i.e. with the public keys
+fn program_type(ty: &IdlType) -> syn::Type {
+    match ty {
+        IdlType::Pubkey => parse_quote!(Pubkey),
+        IdlType::Option(inner) => {
+            let inner = program_type(inner);
+            parse_quote!(Option<#inner>)
+        }
+        IdlType::Vec(inner) => {
+            let inner = program_type(inner);
+            parse_quote!(Vec<#inner>)
+        }
+        IdlType::Array(inner, anchor_lang_idl_spec::IdlArrayLen::Value(len)) => {
+            let inner = program_type(inner);
+            parse_quote!([#inner; #len])
+        }
+        ty => idl_type_to_syn_type(ty, 0).0,
+    }
+}
+
+// Generate the custom data types defined within the IDL, the types defined by several programs are generated once
+fn get_defined_types(
+    idl: &Idl,
+    serializable_types: &HashSet<String>,
+    defined_type_names: &mut HashSet<String>,
+) -> Vec<syn::Item> {
+    idl.types
+        .iter()
+        .filter(|type_def| defined_type_names.insert(type_def.name.clone()))
+        .map(|type_def| {
+            let name = format_ident!("{}", type_def.name);
+            let (doc_comment, derive): (String, syn::Attribute) =
+                match serializable_types.contains(&type_def.name) {
+                    true => (
+                        format!(
+                            "Custom data type `{}` of the program, serialized the same way as within the program.",
+                            type_def.name
+                        ),
+                        parse_quote!(#[derive(Arbitrary, Debug, Clone, AnchorSerialize)]),
+                    ),
+                    false => (
+                        format!(
+                            "Custom data type `{}` of the program with `AccountId`s in place of the public keys, convert it into the type defined in the program within `get_data`.",
+                            type_def.name
+                        ),
+                        parse_quote!(#[derive(Arbitrary, Debug, Clone)]),
+                    ),
+                };
+            match &type_def.ty {
+                IdlTypeDefTy::Struct { fields } => {
+                    let mut item: syn::ItemStruct = parse_quote! {
+                        #[doc = #doc_comment]
+                        #derive
+                        pub struct #name {}
+                    };
+                    item.fields = get_defined_fields(fields.as_ref());
+                    // unit and tuple structs end with a semicolon
+                    if !matches!(item.fields, syn::Fields::Named(_)) {
+                        item.semi_token = Some(Default::default());
+                    }
+                    syn::Item::Struct(item)
+                }
+                IdlTypeDefTy::Enum { variants } => {
+                    let variants = variants.iter().map(|variant| {
+                        let variant_name = format_ident!("{}", variant.name);
+                        let fields = get_defined_fields(variant.fields.as_ref());
+                        let variant: syn::Variant = parse_quote!(#variant_name #fields);
+                        variant
+                    });
+                    parse_quote! {
+                        #[doc = #doc_comment]
+                        #derive
+                        pub enum #name {
+                            #(#variants),*
+                        }
+                    }
+                }
+                IdlTypeDefTy::Type { alias } => {
+                    let (alias, _is_custom) = idl_type_to_syn_type(alias, 0);
+                    parse_quote! {
+                        #[doc = #doc_comment]
+                        pub type #name = #alias;
+                    }
+                }
+            }
         })
+        .collect()
+}
+
+// Generate the fields of the custom struct or enum variant
+fn get_defined_fields(fields: Option<&IdlDefinedFields>) -> syn::Fields {
+    match fields {
+        None => syn::Fields::Unit,
+        Some(IdlDefinedFields::Named(fields)) => {
+            let fields = fields.iter().map(|field| {
+                let field_name = format_ident!("{}", field.name);
+                let (field_type, _is_custom) = idl_type_to_syn_type(&field.ty, 0);
+                quote!(pub #field_name: #field_type)
+            });
+            syn::Fields::Named(parse_quote!({ #(#fields),* }))
+        }
+        Some(IdlDefinedFields::Tuple(types)) => {
+            let types = types.iter().map(|ty| idl_type_to_syn_type(ty, 0).0);
+            syn::Fields::Unnamed(parse_quote!((#(#types),*)))
+        }
+    }
 }
 
 // Generate accounts for fuzzing
@@ -338,7 +709,7 @@ fn get_fuzz_accounts(idl: &Idl, accounts_mappings: &HashMap<String, u8>) -> Vec<
                                 account_name.push_str(&format!("_{}", &program_name));
                             }
 
-                            let name: syn::Ident = format_ident!("{}", &account_name);
+                            let name = account_ident(&account_name);
                             let account = match single.pda {
                                 Some(_) => parse_quote! { #name: AccountsStorage<PdaStore> },
                                 None => parse_quote! { #name: AccountsStorage<todo!()> },
@@ -1,2 +1,88 @@
+use anchor_lang_idl_spec::{Idl, IdlField, IdlInstruction, IdlMetadata, IdlType, IDL_SPEC};
+use syn::parse_quote;
+
 pub mod fuzz_instructions_generator;
 pub mod test_fuzz_generator;
+
+/// Framework the program is written with, it determines how the generated fuzz test calls the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramFramework {
+    /// Anchor program, called through its `entry` with the `<program>::instruction::*` data.
+    Anchor,
+    /// Program not built with Anchor, called through its `process_instruction` with the [`RawData`](trident_fuzz::ix_data::RawData).
+    Native,
+    /// Pinocchio program, instructions are generated the same way as for the native programs. Its entrypoint
+    /// takes the Pinocchio `AccountInfo`, so the program is not compiled into the fuzz test and it is loaded
+    /// from its SBF binary instead.
+    Pinocchio,
+}
+
+/// IDL of a program the fuzz test is generated for.
+#[derive(Debug, Clone)]
+pub struct ProgramIdl {
+    pub idl: Idl,
+    pub framework: ProgramFramework,
+}
+
+impl ProgramIdl {
+    pub fn anchor(idl: Idl) -> Self {
+        Self {
+            idl,
+            framework: ProgramFramework::Anchor,
+        }
+    }
+
+    pub fn native(idl: Idl) -> Self {
+        Self {
+            idl,
+            framework: ProgramFramework::Native,
+        }
+    }
+
+    /// IDL of a native program without any IDL, with the single `process_instruction` instruction
+    /// passing the fuzzed bytes to the program as they are.
+    pub fn native_without_idl(program_name: &str) -> Self {
+        Self::native(Idl {
+            address: String::default(),
+            metadata: IdlMetadata {
+                name: program_name.to_string(),
+                version: "0.1.0".to_string(),
+                spec: IDL_SPEC.to_string(),
+                description: None,
+                repository: None,
+                dependencies: vec![],
+                contact: None,
+                deployments: None,
+            },
+            docs: vec![],
+            instructions: vec![IdlInstruction {
+                name: "process_instruction".to_string(),
+                docs: vec![],
+                discriminator: vec![],
+                accounts: vec![],
+                args: vec![IdlField {
+                    name: "data".to_string(),
+                    docs: vec![],
+                    ty: IdlType::Bytes,
+                }],
+                returns: None,
+            }],
+            accounts: vec![],
+            events: vec![],
+            errors: vec![],
+            types: vec![],
+            constants: vec![],
+        })
+    }
+}
+
+/// Program ID of the program which is not compiled into the fuzz test, taken from the address of its IDL.
+pub fn program_address(idl: &Idl) -> syn::Expr {
+    match idl.address.is_empty() {
+        true => parse_quote!(todo!()),
+        false => {
+            let address = &idl.address;
+            parse_quote!(solana_sdk::pubkey!(#address))
+        }
+    }
+}
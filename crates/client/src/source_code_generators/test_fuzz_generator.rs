@@ -1,8 +1,9 @@
-use anchor_lang_idl_spec::Idl;
 use quote::{format_ident, ToTokens};
 use syn::parse_quote;
 
-pub fn generate_source_code(idl_instructions: &[Idl]) -> String {
+use super::{program_address, ProgramFramework, ProgramIdl};
+
+pub fn generate_source_code(idl_instructions: &[ProgramIdl]) -> String {
     let program_imports = get_program_imports(idl_instructions);
     let program_names = get_program_names(idl_instructions);
     let (fuzzing_programs, programs_array) = get_fuzzing_programs(idl_instructions);
@@ -58,10 +59,10 @@ pub fn generate_source_code(idl_instructions: &[Idl]) -> String {
     test_fuzz_definition.into_token_stream().to_string()
 }
 
-fn get_program_names(idl_instructions: &[Idl]) -> Vec<syn::Stmt> {
+fn get_program_names(idl_instructions: &[ProgramIdl]) -> Vec<syn::Stmt> {
     idl_instructions
         .iter()
-        .map(|idl| {
+        .map(|ProgramIdl { idl, .. }| {
             let program_name = &idl.metadata.name;
             let program_name_upper = &idl.metadata.name;
             let program_name_ident = format_ident!("PROGRAM_NAME_{}", program_name_upper);
@@ -71,32 +72,40 @@ fn get_program_names(idl_instructions: &[Idl]) -> Vec<syn::Stmt> {
         .collect()
 }
 
-fn get_program_imports(idl_instructions: &[Idl]) -> Vec<syn::ItemUse> {
+fn get_program_imports(idl_instructions: &[ProgramIdl]) -> Vec<syn::ItemUse> {
     idl_instructions
         .iter()
-        .flat_map(|idl| {
+        .flat_map(|ProgramIdl { idl, framework }| {
             let program_name = &idl.metadata.name;
             let program_name_upper = &idl.metadata.name;
             let program_name_ident = format_ident!("{}", program_name);
             let program_entry_ident = format_ident!("entry_{}", program_name);
             let program_id_name_ident = format_ident!("PROGRAM_ID_{}", program_name_upper);
 
+            // native programs expose the entrypoint of the runtime directly, Pinocchio programs are not
+            // compiled into the fuzz test
+            let entry_ident = match framework {
+                ProgramFramework::Anchor => format_ident!("entry"),
+                ProgramFramework::Native => format_ident!("process_instruction"),
+                ProgramFramework::Pinocchio => return vec![],
+            };
+
             vec![
-                parse_quote!(use #program_name_ident::entry as #program_entry_ident;),
+                parse_quote!(use #program_name_ident::#entry_ident as #program_entry_ident;),
                 parse_quote!(use #program_name_ident::ID as #program_id_name_ident;),
             ]
         })
         .collect()
 }
 
-fn get_fuzzing_programs(idl_instructions: &[Idl]) -> (Vec<syn::Stmt>, syn::ExprArray) {
+fn get_fuzzing_programs(idl_instructions: &[ProgramIdl]) -> (Vec<syn::Stmt>, syn::ExprArray) {
     // Vector to collect the `fuzzing_program_name_ident` identifiers
     let mut fuzzing_program_idents = Vec::new();
 
     // Collect the statements and the identifiers for the `ExprArray`
     let fuzzing_programs: Vec<syn::Stmt> = idl_instructions
         .iter()
-        .map(|idl| {
+        .map(|ProgramIdl { idl, framework }| {
             let program_name = &idl.metadata.name;
             let program_name_upper = &idl.metadata.name;
             let fuzzing_program_name_ident = format_ident!("fuzzing_program_{}", program_name);
@@ -107,12 +116,26 @@ fn get_fuzzing_programs(idl_instructions: &[Idl]) -> (Vec<syn::Stmt>, syn::ExprA
             // Add the identifier to the vector
             fuzzing_program_idents.push(fuzzing_program_name_ident.clone());
 
+            let program_entry: syn::Expr = match framework {
+                ProgramFramework::Anchor => parse_quote!(convert_entry!(#program_entry_ident)),
+                ProgramFramework::Native => parse_quote!(#program_entry_ident),
+                ProgramFramework::Pinocchio => {
+                    let program_id = program_address(idl);
+                    return parse_quote! {
+                        let #fuzzing_program_name_ident = FuzzingProgram::sbf(
+                            #program_name_ident,
+                            &#program_id
+                        );
+                    };
+                }
+            };
+
             // Generate the statement
             parse_quote! {
                 let #fuzzing_program_name_ident = FuzzingProgram::new(
                     #program_name_ident,
                     &#program_id_name_ident,
-                    processor!(convert_entry!(#program_entry_ident))
+                    processor!(#program_entry)
                 );
             }
         })
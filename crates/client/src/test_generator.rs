@@ -1,7 +1,7 @@
 use crate::___private::test_fuzz_generator;
 use crate::commander::{Commander, Error as CommanderError};
 use crate::constants::*;
-use crate::source_code_generators::fuzz_instructions_generator;
+use crate::source_code_generators::{fuzz_instructions_generator, ProgramFramework, ProgramIdl};
 use crate::versions_config::TridentVersionsConfig;
 use crate::{construct_path, load_template, utils::*};
 use cargo_metadata::Package;
@@ -14,8 +14,6 @@ use std::{
 };
 use thiserror::Error;
 
-use anchor_lang_idl_spec::Idl;
-
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("cannot parse Cargo.toml")]
@@ -32,7 +30,7 @@ pub enum Error {
     Toml(#[from] toml::de::Error),
     #[error("{0:?}")]
     Commander(#[from] CommanderError),
-    #[error("The Anchor project does not contain any programs")]
+    #[error("The workspace does not contain any programs")]
     NoProgramsFound,
     #[error("parsing Cargo.toml dependencies failed")]
     ParsingCargoTomlDependenciesFailed,
@@ -41,7 +39,7 @@ pub enum Error {
 pub struct TestGenerator {
     pub root: PathBuf,
     pub program_packages: Vec<Package>,
-    pub anchor_idls: Vec<Idl>,
+    pub program_idls: Vec<ProgramIdl>,
     pub test_fuzz: String,
     pub fuzz_instructions: String,
     pub versions_config: TridentVersionsConfig,
@@ -55,7 +53,7 @@ impl TestGenerator {
         Self {
             root: Path::new(&root).to_path_buf(),
             program_packages: Vec::default(),
-            anchor_idls: Vec::default(),
+            program_idls: Vec::default(),
             fuzz_instructions: String::default(),
             test_fuzz: String::default(),
             versions_config,
@@ -63,7 +61,7 @@ impl TestGenerator {
     }
     #[throws]
     pub async fn initialize(&mut self) {
-        Commander::build_anchor_project().await?;
+        self.build_programs().await?;
 
         self.get_program_packages().await?;
         self.load_programs_idl()?;
@@ -71,13 +69,16 @@ impl TestGenerator {
         self.initialize_new_fuzz_test().await?;
 
         update_gitignore(&self.root, CARGO_TARGET_DIR_DEFAULT_HFUZZ)?;
-        // update_gitignore(&self.root, CARGO_TARGET_DIR_DEFAULT_AFL)?;
+        update_gitignore(&self.root, COVERAGE_DIR_DEFAULT)?;
+        update_gitignore(&self.root, REPLAY_TARGET_DIR_DEFAULT)?;
+        update_gitignore(&self.root, CARGO_TARGET_DIR_DEFAULT_AFL)?;
+        update_gitignore(&self.root, CARGO_TARGET_DIR_DEFAULT_LIBFUZZER)?;
         // initialize_package_metadata(&self.program_packages, &self.versions_config).await?;
     }
 
     #[throws]
     pub async fn add_fuzz_test(&mut self) {
-        Commander::build_anchor_project().await?;
+        self.build_programs().await?;
 
         self.get_program_packages().await?;
         self.load_programs_idl()?;
@@ -87,6 +88,14 @@ impl TestGenerator {
         // update_package_metadata(&self.program_packages, &self.versions_config).await?;
     }
 
+    #[throws]
+    async fn build_programs(&self) {
+        // the IDLs are generated by Anchor, the native programs are built together with the fuzz tests
+        if self.root.join(ANCHOR_TOML).exists() {
+            Commander::build_anchor_project().await?;
+        }
+    }
+
     #[throws]
     async fn get_program_packages(&mut self) {
         // TODO consider optionally excluding packages
@@ -95,9 +104,9 @@ impl TestGenerator {
 
     #[throws]
     async fn generate_source_codes(&mut self) {
-        let test_fuzz = test_fuzz_generator::generate_source_code(&self.anchor_idls);
+        let test_fuzz = test_fuzz_generator::generate_source_code(&self.program_idls);
         let fuzz_instructions =
-            fuzz_instructions_generator::generate_source_code(&self.anchor_idls);
+            fuzz_instructions_generator::generate_source_code(&self.program_idls);
 
         self.test_fuzz = Commander::format_program_code_nightly(&test_fuzz).await?;
         self.fuzz_instructions = Commander::format_program_code_nightly(&fuzz_instructions).await?;
@@ -108,8 +117,42 @@ impl TestGenerator {
         let target_path = construct_path!(self.root, "target/idl/");
 
         // TODO consider optionally excluding packages
-        self.anchor_idls =
-            crate::anchor_idl::load_idls(target_path, &self.program_packages).unwrap();
+        let anchor_packages: Vec<Package> = self
+            .program_packages
+            .iter()
+            .filter(|package| is_anchor_package(package))
+            .cloned()
+            .collect();
+        self.program_idls = match target_path.is_dir() {
+            true => crate::anchor_idl::load_idls(target_path, &anchor_packages).unwrap(),
+            false => vec![],
+        }
+        .into_iter()
+        .map(ProgramIdl::anchor)
+        .collect();
+
+        // the native programs use their Shank or Codama IDL if there is any, otherwise their instruction
+        // data are fuzzed as raw bytes
+        self.program_idls.extend(
+            self.program_packages
+                .iter()
+                .filter(|package| !is_anchor_package(package))
+                .map(|package| {
+                    let program_name = package_lib_name(package);
+                    let mut program_idl = match crate::native_idl::load_native_idl(
+                        &self.root,
+                        package,
+                        &program_name,
+                    ) {
+                        Some(idl) => ProgramIdl::native(idl),
+                        None => ProgramIdl::native_without_idl(&program_name),
+                    };
+                    if is_pinocchio_package(package) {
+                        program_idl.framework = ProgramFramework::Pinocchio;
+                    }
+                    program_idl
+                }),
+        );
     }
 
     #[throws]
@@ -151,6 +194,8 @@ impl TestGenerator {
             &format!("{TESTS_WORKSPACE_DIRECTORY}/{FUZZ_TEST_DIRECTORY}",),
         )
         .await?;
+
+        self.set_execution_mode(&new_fuzz_test).await?;
     }
     #[throws]
     pub async fn initialize_new_fuzz_test(&self) {
@@ -194,5 +239,21 @@ impl TestGenerator {
             &format!("{TESTS_WORKSPACE_DIRECTORY}/{FUZZ_TEST_DIRECTORY}",),
         )
         .await?;
+
+        self.set_execution_mode(&new_fuzz_test).await?;
+    }
+
+    /// The Pinocchio programs can only be loaded from their SBF binaries, so the fuzz tests of such
+    /// programs are executed with the `sbf` execution mode.
+    #[throws]
+    async fn set_execution_mode(&self, fuzz_test: &str) {
+        let trident_toml_path = construct_path!(self.root, TRIDENT_TOML);
+        let has_pinocchio_program = self
+            .program_idls
+            .iter()
+            .any(|program_idl| program_idl.framework == ProgramFramework::Pinocchio);
+        if has_pinocchio_program && trident_toml_path.exists() {
+            set_sbf_execution_mode(&trident_toml_path, fuzz_test).await?;
+        }
     }
 }
@@ -90,6 +90,9 @@ pub async fn collect_program_packages() -> Vec<cargo_metadata::Package> {
         packages
     }
 }
+/// Dependencies of the programs, a shared library depending on any of them is considered a program.
+const PROGRAM_DEPENDENCIES: [&str; 3] = ["anchor-lang", "solana-program", "pinocchio"];
+
 pub fn program_packages() -> impl Iterator<Item = cargo_metadata::Package> {
     let cargo_toml_data = cargo_metadata::MetadataCommand::new()
         .no_deps()
@@ -101,10 +104,74 @@ pub fn program_packages() -> impl Iterator<Item = cargo_metadata::Package> {
         if let Some("programs") = package.manifest_path.iter().nth_back(2) {
             return true;
         }
-        false
+        // programs outside of the Anchor layout are recognized as the shared libraries built for the SBF target
+        let is_cdylib = package.targets.iter().any(|target| {
+            target
+                .crate_types
+                .iter()
+                .any(|crate_type| crate_type == "cdylib")
+        });
+        is_cdylib
+            && package
+                .dependencies
+                .iter()
+                .any(|dependency| PROGRAM_DEPENDENCIES.contains(&dependency.name.as_str()))
     })
 }
 
+/// Returns true if the program package is built with Anchor.
+pub fn is_anchor_package(package: &Package) -> bool {
+    package
+        .dependencies
+        .iter()
+        .any(|dependency| dependency.name == "anchor-lang")
+}
+
+/// Returns true if the program package is built with Pinocchio, such programs are loaded from their
+/// SBF binaries instead of being compiled into the fuzz tests.
+pub fn is_pinocchio_package(package: &Package) -> bool {
+    !is_anchor_package(package)
+        && package
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.name == "pinocchio")
+}
+
+/// Name of the library of the program package, i.e. the crate name the fuzz test imports.
+pub fn package_lib_name(package: &Package) -> String {
+    package
+        .targets
+        .iter()
+        .find(|target| {
+            target
+                .kind
+                .iter()
+                .any(|kind| kind == "lib" || kind == "cdylib")
+        })
+        .map(|target| target.name.replace('-', "_"))
+        .unwrap_or_else(|| package.name.replace('-', "_"))
+}
+
+/// Root of the Cargo workspace within the current directory, used for the workspaces without the Anchor.toml.
+pub fn cargo_workspace_root() -> Option<PathBuf> {
+    cargo_metadata::MetadataCommand::new()
+        .no_deps()
+        .exec()
+        .ok()
+        .map(|metadata| metadata.workspace_root.into_std_path_buf())
+}
+
+/// Features of the native program package the fuzz tests depend on, the `no-entrypoint` feature
+/// prevents the conflicting `entrypoint` symbols of multiple programs.
+fn native_program_features(package: &Package) -> Value {
+    let features = ["no-entrypoint", "trident-fuzzing"]
+        .into_iter()
+        .filter(|feature| package.features.contains_key(*feature))
+        .map(|feature| Value::String(feature.to_string()))
+        .collect();
+    Value::Array(features)
+}
+
 #[throws]
 pub fn update_gitignore(root: &PathBuf, ignored_path: &str) {
     let gitignore_path = construct_path!(root, GIT_IGNORE);
@@ -368,7 +435,11 @@ pub async fn initialize_fuzz_tests_manifest(
         }),
     );
 
-    for package in packages {
+    // the Pinocchio programs are not compiled into the fuzz tests
+    for package in packages
+        .iter()
+        .filter(|package| !is_pinocchio_package(package))
+    {
         let manifest_path = package.manifest_path.parent().unwrap().as_std_path();
         let relative_path = pathdiff::diff_paths(manifest_path, cargo_dir).unwrap();
 
@@ -387,6 +458,9 @@ pub async fn initialize_fuzz_tests_manifest(
                 //     "features".to_string(),
                 //     Value::Array(vec![Value::String("trident-fuzzing".to_string())]),
                 // );
+                if !is_anchor_package(package) {
+                    package_entry.insert("features".to_string(), native_program_features(package));
+                }
                 package_entry
             }),
         );
@@ -421,7 +495,11 @@ pub async fn update_fuzz_tests_manifest(
             Value::Table(trident_client)
         });
 
-    for package in packages {
+    // the Pinocchio programs are not compiled into the fuzz tests
+    for package in packages
+        .iter()
+        .filter(|package| !is_pinocchio_package(package))
+    {
         let manifest_path = package.manifest_path.parent().unwrap().as_std_path();
         let relative_path = pathdiff::diff_paths(manifest_path, cargo_dir).unwrap();
 
@@ -433,13 +511,42 @@ pub async fn update_fuzz_tests_manifest(
                 "path".to_string(),
                 Value::String(relative_path_str.to_owned()),
             );
-            package_entry.insert(
-                "features".to_string(),
-                Value::Array(vec![Value::String("trident-fuzzing".to_string())]),
-            );
+            let features = match is_anchor_package(package) {
+                true => Value::Array(vec![Value::String("trident-fuzzing".to_string())]),
+                false => native_program_features(package),
+            };
+            package_entry.insert("features".to_string(), features);
             Value::Table(package_entry)
         });
     }
 
     fs::write(cargo_path, toml::to_string(&cargo_toml).unwrap()).await?;
 }
+
+/// Sets the `sbf` execution mode of the fuzz target within the Trident.toml. The table is appended
+/// to the file so that the comments of the manifest are kept.
+#[throws]
+pub async fn set_sbf_execution_mode(trident_toml_path: &PathBuf, fuzz_target: &str) {
+    let trident_toml_content = fs::read_to_string(trident_toml_path).await?;
+    let trident_toml: Value = toml::from_str(&trident_toml_content)?;
+
+    let target_exists = trident_toml
+        .get("fuzz")
+        .and_then(|fuzz| fuzz.get("targets"))
+        .and_then(|targets| targets.get(fuzz_target))
+        .is_some();
+    if target_exists {
+        println!("{SKIP} [{TRIDENT_TOML}], already configures [{fuzz_target}]");
+        return;
+    }
+
+    let separator = match trident_toml_content.ends_with('\n') {
+        true => "",
+        false => "\n",
+    };
+    let content = format!(
+        "{trident_toml_content}{separator}\n[fuzz.targets.{fuzz_target}]\nexecution_mode = \"sbf\"\n"
+    );
+    fs::write(trident_toml_path, content).await?;
+    println!("{FINISH} [{TRIDENT_TOML}] set the sbf execution mode of [{fuzz_target}]");
+}
@@ -0,0 +1,416 @@
+use trident_client::fuzzing::*;
+/// FuzzInstruction contains all available Instructions.
+/// Below, the instruction arguments (accounts and data) are defined.
+#[derive(Arbitrary, DisplayIx, FuzzTestExecutor)]
+pub enum FuzzInstruction {
+    Initialize(Initialize),
+    Increment(Increment),
+    SetConfig(SetConfig),
+    SetAuthority(SetAuthority),
+    Delegate(Delegate),
+    Close(Close),
+}
+#[derive(Arbitrary, Debug)]
+pub struct Initialize {
+    pub accounts: InitializeAccounts,
+    pub data: InitializeData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct InitializeAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+    pub system_program: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct InitializeData {}
+#[derive(Arbitrary, Debug)]
+pub struct Increment {
+    pub accounts: IncrementAccounts,
+    pub data: IncrementData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct IncrementAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct IncrementData {
+    pub amount: u64,
+    pub limit: Option<u32>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetConfig {
+    pub accounts: SetConfigAccounts,
+    pub data: SetConfigData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetConfigAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct SetConfigData {
+    pub config: CounterConfig,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetAuthority {
+    pub accounts: SetAuthorityAccounts,
+    pub data: SetAuthorityData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetAuthorityAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct SetAuthorityData {
+    pub new_authority: AccountId,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Delegate {
+    pub accounts: DelegateAccounts,
+    pub data: DelegateData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct DelegateAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct DelegateData {
+    pub delegation: Delegation,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Close {
+    pub accounts: CloseAccounts,
+    pub data: CloseData,
+}
+#[derive(Arbitrary, Debug)]
+pub struct CloseAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+    pub recipient: AccountId,
+    pub r#type: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[derive(Arbitrary, Debug)]
+pub struct CloseData {}
+///Custom data type `CounterConfig` of the program, serialized the same way as
+/// within the program.
+#[derive(Arbitrary, Debug, Clone, AnchorSerialize)]
+pub struct CounterConfig {
+    pub max_value: u64,
+    pub mode: Mode,
+}
+///Custom data type `Mode` of the program, serialized the same way as within
+/// the program.
+#[derive(Arbitrary, Debug, Clone, AnchorSerialize)]
+pub enum Mode {
+    Linear,
+    Capped(u64),
+}
+///Custom data type `Delegation` of the program with `AccountId`s in place of
+/// the public keys, convert it into the type defined in the program within
+/// `get_data`.
+#[derive(Arbitrary, Debug, Clone)]
+pub struct Delegation {
+    pub delegate: AccountId,
+    pub amount: u64,
+}
+///IxOps implementation for `Initialize` with all required functions.
+impl IxOps for Initialize {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let data = RawData::borsh(&[0u8], &());
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Keypair = todo!();
+        let authority: Keypair = todo!();
+        let system_program: Pubkey = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter.pubkey(), true),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(system_program, false),
+        ];
+        let signers = vec![counter, authority];
+        Ok((signers, acc_meta))
+    }
+}
+///IxOps implementation for `Increment` with all required functions.
+impl IxOps for Increment {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let args: (u64, Option<u32>) = (self.data.amount, self.data.limit);
+        let data = RawData::borsh(&[1u8], &args);
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Pubkey = todo!();
+        let authority: Keypair = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+        ];
+        let signers = vec![authority];
+        Ok((signers, acc_meta))
+    }
+}
+///IxOps implementation for `SetConfig` with all required functions.
+impl IxOps for SetConfig {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let args: (CounterConfig,) = (self.data.config.clone(),);
+        let data = RawData::borsh(&[2u8], &args);
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Pubkey = todo!();
+        let authority: Keypair = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+        ];
+        let signers = vec![authority];
+        Ok((signers, acc_meta))
+    }
+}
+///IxOps implementation for `SetAuthority` with all required functions.
+impl IxOps for SetAuthority {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let args: (Pubkey,) = (todo!(),);
+        let data = RawData::borsh(&[3u8], &args);
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Pubkey = todo!();
+        let authority: Keypair = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+        ];
+        let signers = vec![authority];
+        Ok((signers, acc_meta))
+    }
+}
+///IxOps implementation for `Delegate` with all required functions.
+impl IxOps for Delegate {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let data = RawData(todo!());
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Pubkey = todo!();
+        let authority: Keypair = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+        ];
+        let signers = vec![authority];
+        Ok((signers, acc_meta))
+    }
+}
+///IxOps implementation for `Close` with all required functions.
+impl IxOps for Close {
+    type IxData = RawData;
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        native_counter::ID
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        _client: &mut impl FuzzClient,
+        _fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Self::IxData, FuzzingError> {
+        let data = RawData::borsh(&[5u8], &());
+        Ok(data)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// The AccountMetas follow the order and flags of the IDL accounts.
+    /// Return the keypairs of the signer accounts as the signers.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let counter: Pubkey = todo!();
+        let authority: Keypair = todo!();
+        let recipient: Option<Pubkey> = todo!();
+        let r#type: Pubkey = todo!();
+        let acc_meta = vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            match recipient {
+                Some(recipient) => AccountMeta::new(recipient, false),
+                None => AccountMeta::new_readonly(self.get_program_id(), false),
+            },
+            AccountMeta::new_readonly(r#type, false),
+        ];
+        let signers = vec![authority];
+        Ok((signers, acc_meta))
+    }
+}
+/// Check supported AccountsStorages at
+/// https://ackee.xyz/trident/docs/latest/features/account-storages/
+#[derive(Default)]
+pub struct FuzzAccounts {
+    authority: AccountsStorage<todo!()>,
+    counter: AccountsStorage<todo!()>,
+    r#type: AccountsStorage<todo!()>,
+    recipient: AccountsStorage<todo!()>,
+    system_program: AccountsStorage<todo!()>,
+}
@@ -7,6 +7,7 @@ use anchor_lang_idl_spec::Idl;
 use anyhow::Error;
 use fehler::throws;
 use pretty_assertions::assert_str_eq;
+use trident_client::___private::{ProgramFramework, ProgramIdl};
 
 #[throws]
 #[tokio::test]
@@ -21,7 +22,8 @@ async fn test_fuzz_instructions() {
 
     let fuzz_instructions_code =
         trident_client::___private::fuzz_instructions_generator::generate_source_code(&vec![
-            idl_2, idl_dummy,
+            ProgramIdl::anchor(idl_2),
+            ProgramIdl::anchor(idl_dummy),
         ]);
 
     let fuzz_instructions_code =
@@ -43,7 +45,8 @@ async fn test_fuzz_test() {
     let idl_dummy = read_idl("dummy_example.json")?;
 
     let test_fuzz = trident_client::___private::test_fuzz_generator::generate_source_code(&vec![
-        idl_2, idl_dummy,
+        ProgramIdl::anchor(idl_2),
+        ProgramIdl::anchor(idl_dummy),
     ]);
 
     let test_fuzz =
@@ -52,6 +55,101 @@ async fn test_fuzz_test() {
     assert_str_eq!(test_fuzz, test_fuzz_expected);
 }
 
+#[throws]
+#[tokio::test]
+async fn test_native_program() {
+    let expected_fuzz_instructions_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_native_fuzz_instructions.rs"
+    ));
+    let test_fuzz_expected = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_native_test_fuzz.rs"
+    ));
+
+    let idls = vec![
+        ProgramIdl::anchor(read_idl("dummy_example.json")?),
+        ProgramIdl::native_without_idl("native_counter"),
+    ];
+
+    let fuzz_instructions_code =
+        trident_client::___private::fuzz_instructions_generator::generate_source_code(&idls);
+    let fuzz_instructions_code =
+        trident_client::___private::Commander::format_program_code_nightly(&fuzz_instructions_code)
+            .await?;
+    assert_str_eq!(fuzz_instructions_code, expected_fuzz_instructions_code);
+
+    let test_fuzz = trident_client::___private::test_fuzz_generator::generate_source_code(&idls);
+    let test_fuzz =
+        trident_client::___private::Commander::format_program_code_nightly(&test_fuzz).await?;
+    assert_str_eq!(test_fuzz, test_fuzz_expected);
+}
+
+#[throws]
+#[tokio::test]
+async fn test_shank_and_codama_idls() {
+    let expected_fuzz_instructions_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_shank_fuzz_instructions.rs"
+    ));
+
+    let shank_idl = trident_client::___private::parse_native_idl(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/native_idl/shank_counter.json"
+    )))
+    .unwrap();
+    let codama_idl = trident_client::___private::parse_native_idl(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/native_idl/codama_counter.json"
+    )))
+    .unwrap();
+
+    // both formats describe the same program
+    assert_eq!(shank_idl, codama_idl);
+
+    let fuzz_instructions_code =
+        trident_client::___private::fuzz_instructions_generator::generate_source_code(&[
+            ProgramIdl::native(shank_idl),
+        ]);
+    let fuzz_instructions_code =
+        trident_client::___private::Commander::format_program_code_nightly(&fuzz_instructions_code)
+            .await?;
+    assert_str_eq!(fuzz_instructions_code, expected_fuzz_instructions_code);
+}
+
+#[throws]
+#[tokio::test]
+async fn test_pinocchio_program() {
+    let test_fuzz_expected = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_pinocchio_test_fuzz.rs"
+    ));
+
+    let pinocchio_idl = trident_client::___private::parse_native_idl(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/native_idl/shank_counter.json"
+    )))
+    .unwrap();
+    let idls = vec![
+        ProgramIdl::anchor(read_idl("dummy_example.json")?),
+        ProgramIdl {
+            idl: pinocchio_idl,
+            framework: ProgramFramework::Pinocchio,
+        },
+    ];
+
+    // the Pinocchio program is not compiled into the fuzz test, its ID is taken from the IDL
+    let fuzz_instructions_code =
+        trident_client::___private::fuzz_instructions_generator::generate_source_code(&idls);
+    assert!(fuzz_instructions_code
+        .contains("solana_sdk :: pubkey ! (\"Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS\")"));
+
+    let test_fuzz = trident_client::___private::test_fuzz_generator::generate_source_code(&idls);
+    let test_fuzz =
+        trident_client::___private::Commander::format_program_code_nightly(&test_fuzz).await?;
+    assert_str_eq!(test_fuzz, test_fuzz_expected);
+}
+
 #[throws]
 fn read_idl(_idl_name: &str) -> Idl {
     let current_dir = std::env::current_dir()?;
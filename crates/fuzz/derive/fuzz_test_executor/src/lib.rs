@@ -13,27 +13,14 @@ pub fn fuzz_test_executor(input: TokenStream) -> TokenStream {
                 let variant_name = &variant.ident;
                 quote! {
                     #enum_name::#variant_name (ix) => {
-
-                        if cfg!(honggfuzz){
-                            TransactionExecutor::process_transaction_honggfuzz(
-                                &self.to_context_string(),
-                                client,
-                                ix,
-                                sent_txs,
-                                config,
-                                accounts
-                            )?;
-                        }else if cfg!(afl){
-                            TransactionExecutor::process_transaction_afl(
-                                &self.to_context_string(),
-                                client,
-                                ix,
-                                sent_txs,
-                                config,
-                                accounts
-                            )?;
-                        }
-
+                        TransactionExecutor::process_transaction(
+                            &self.to_context_string(),
+                            client,
+                            ix,
+                            sent_txs,
+                            config,
+                            accounts
+                        )?;
                     }
                 }
             });
@@ -0,0 +1,298 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use serde_json::json;
+
+use crate::{
+    fuzz_stats::FuzzingStatistics,
+    replay::{triage, CrashBucket, ReplayResult, CUSTOM_CHECK_FAILED},
+};
+
+/// Rule of the crashes outside of the Trident checks, e.g. arithmetic overflows.
+const PANIC_RULE: &str = "panic";
+
+/// Reports of the fuzzing session or the corpus replay in the formats understood by the CI systems,
+/// i.e. JUnit XML and SARIF.
+#[derive(Debug, Clone)]
+pub struct CiReport {
+    /// Name of the fuzz target.
+    pub target: String,
+    /// Statistics of the session, the invoked instructions are reported even if they did not crash.
+    pub stats: FuzzingStatistics,
+    /// Crashes grouped by their signatures.
+    pub buckets: Vec<CrashBucket>,
+}
+
+impl CiReport {
+    pub fn new(target: &str, stats: FuzzingStatistics, results: &[ReplayResult]) -> Self {
+        let crashes = results
+            .iter()
+            .filter_map(|result| {
+                let crash = result.crash.clone()?;
+                Some((result.file.clone(), result.size, crash))
+            })
+            .collect();
+        Self {
+            target: target.to_string(),
+            stats,
+            buckets: triage(crashes),
+        }
+    }
+
+    /// Serializes the report into a JUnit XML. Every instruction has a testcase for its execution and
+    /// a testcase for its invariant checks, crashes outside of the instructions have a testcase each.
+    /// Crashes are reported as failures of the corresponding testcases.
+    pub fn to_junit(&self) -> String {
+        let mut instructions: BTreeSet<&str> =
+            self.stats.instructions.keys().map(String::as_str).collect();
+        instructions.extend(
+            self.buckets
+                .iter()
+                .filter_map(|bucket| bucket.signature.instruction.as_deref()),
+        );
+
+        let mut testcases = vec![];
+        for instruction in instructions {
+            let buckets = |invariant: bool| {
+                self.buckets
+                    .iter()
+                    .filter(|bucket| {
+                        bucket.signature.instruction.as_deref() == Some(instruction)
+                            && (bucket.signature.error == CUSTOM_CHECK_FAILED) == invariant
+                    })
+                    .collect::<Vec<_>>()
+            };
+            testcases.push(("instructions", instruction.to_string(), buckets(false)));
+            testcases.push(("invariants", instruction.to_string(), buckets(true)));
+        }
+        for bucket in self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.signature.instruction.is_none())
+        {
+            testcases.push(("panics", bucket.signature.error.clone(), vec![bucket]));
+        }
+
+        let tests = testcases.len();
+        let failures = testcases
+            .iter()
+            .filter(|(_, _, buckets)| !buckets.is_empty())
+            .count();
+        let target = xml_escape(&self.target);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"trident\" tests=\"{tests}\" failures=\"{failures}\">\n"
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{target}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+        ));
+        for (class, name, buckets) in testcases {
+            let name = xml_escape(&name);
+            if buckets.is_empty() {
+                xml.push_str(&format!(
+                    "    <testcase classname=\"{target}.{class}\" name=\"{name}\"/>\n"
+                ));
+                continue;
+            }
+            xml.push_str(&format!(
+                "    <testcase classname=\"{target}.{class}\" name=\"{name}\">\n"
+            ));
+            for bucket in buckets {
+                let files: Vec<String> = bucket
+                    .files
+                    .iter()
+                    .map(|file| file.display().to_string())
+                    .collect();
+                xml.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    xml_escape(&bucket.signature.error),
+                    xml_escape(&bucket.signature.to_string()),
+                    xml_escape(&files.join("\n")),
+                ));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+
+    /// Serializes the report into a SARIF log with one result per crash bucket. Crashes are located at the
+    /// panic location if it is within the project, i.e. the path of the source file is relative.
+    pub fn to_sarif(&self) -> String {
+        let rule_id = |bucket: &CrashBucket| match bucket.signature.instruction {
+            Some(_) => bucket.signature.error.replace(' ', "-"),
+            None => PANIC_RULE.to_string(),
+        };
+        let rules: BTreeSet<String> = self.buckets.iter().map(rule_id).collect();
+
+        let results: Vec<_> = self
+            .buckets
+            .iter()
+            .map(|bucket| {
+                let mut result = json!({
+                    "ruleId": rule_id(bucket),
+                    "level": "error",
+                    "message": {
+                        "text": format!(
+                            "{} ({} crash files of the {} fuzz target, e.g. {})",
+                            bucket.signature,
+                            bucket.files.len(),
+                            self.target,
+                            bucket.representative().display()
+                        )
+                    },
+                });
+                if let Some((file, line, column)) = bucket
+                    .signature
+                    .location
+                    .as_deref()
+                    .and_then(parse_location)
+                {
+                    result["locations"] = json!([{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": file.display().to_string() },
+                            "region": { "startLine": line, "startColumn": column }
+                        }
+                    }]);
+                }
+                result
+            })
+            .collect();
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "Trident",
+                        "informationUri": "https://ackee.xyz/trident/docs/latest/",
+                        "rules": rules
+                            .iter()
+                            .map(|rule| json!({ "id": rule, "shortDescription": { "text": rule } }))
+                            .collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+            }]
+        });
+        serde_json::to_string_pretty(&sarif).unwrap()
+    }
+}
+
+/// Parses the panic location (`file:line:column`) within the project.
+fn parse_location(location: &str) -> Option<(PathBuf, u32, u32)> {
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let file = Path::new(parts.next()?);
+    // sources of the dependencies (e.g. Trident itself) have absolute paths
+    if file.is_absolute() {
+        return None;
+    }
+    Some((file.to_path_buf(), line, column))
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::CrashSignature;
+
+    fn report() -> CiReport {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_invoked("Deposit".to_string());
+        stats.increase_invoked("Withdraw".to_string());
+        let result =
+            |file: &str, instruction: Option<&str>, error: &str, location: &str| ReplayResult {
+                file: PathBuf::from(file),
+                size: 10,
+                crash: Some(CrashSignature {
+                    instruction: instruction.map(str::to_string),
+                    error: error.to_string(),
+                    message: (error == CUSTOM_CHECK_FAILED)
+                        .then(|| "Custom fuzzing error: 1".to_string()),
+                    location: Some(location.to_string()),
+                }),
+            };
+        let results = vec![
+            result(
+                "a.fuzz",
+                Some("Withdraw"),
+                CUSTOM_CHECK_FAILED,
+                "fuzz_tests/fuzz_0/fuzz_instructions.rs:5:30",
+            ),
+            result(
+                "b.fuzz",
+                None,
+                "attempt to add with overflow",
+                "programs/vault/src/lib.rs:42:9",
+            ),
+            ReplayResult {
+                file: PathBuf::from("c.fuzz"),
+                size: 10,
+                crash: None,
+            },
+        ];
+        CiReport::new("fuzz_0", stats, &results)
+    }
+
+    #[test]
+    fn test_junit() {
+        let junit = report().to_junit();
+        assert!(junit.contains("<testsuite name=\"fuzz_0\" tests=\"5\" failures=\"2\">"));
+        assert!(junit.contains("<testcase classname=\"fuzz_0.instructions\" name=\"Deposit\"/>"));
+        assert!(junit.contains("<testcase classname=\"fuzz_0.instructions\" name=\"Withdraw\"/>"));
+        assert!(junit.contains(
+            "<testcase classname=\"fuzz_0.invariants\" name=\"Withdraw\">\n      \
+             <failure type=\"custom check failed\""
+        ));
+        assert!(junit.contains(
+            "<testcase classname=\"fuzz_0.panics\" name=\"attempt to add with overflow\">"
+        ));
+        assert!(junit.contains(">b.fuzz</failure>"));
+    }
+
+    #[test]
+    fn test_sarif() {
+        let sarif: serde_json::Value = serde_json::from_str(&report().to_sarif()).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        let panic = results
+            .iter()
+            .find(|result| result["ruleId"] == PANIC_RULE)
+            .unwrap();
+        assert_eq!(
+            panic["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "programs/vault/src/lib.rs"
+        );
+        assert_eq!(
+            panic["locations"][0]["physicalLocation"]["region"]["startLine"],
+            42
+        );
+        let check = results
+            .iter()
+            .find(|result| result["ruleId"] == "custom-check-failed")
+            .unwrap();
+        assert_eq!(
+            check["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "fuzz_tests/fuzz_0/fuzz_instructions.rs"
+        );
+        assert!(check["message"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("custom check failed (Custom fuzzing error: 1) in Withdraw"));
+    }
+}
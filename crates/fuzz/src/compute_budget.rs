@@ -0,0 +1,171 @@
+use arbitrary::{Arbitrary, Unstructured};
+use serde::Deserialize;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+
+/// Maximal compute unit limit a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Default (and minimal) size of the program heap region in bytes.
+pub const MIN_HEAP_FRAME_BYTES: u32 = 32 * 1024;
+/// Maximal size of the program heap region in bytes.
+pub const MAX_HEAP_FRAME_BYTES: u32 = 256 * 1024;
+/// Requested heap frame has to be a multiple of this value.
+pub const HEAP_FRAME_BYTES_GRANULARITY: u32 = 1024;
+
+/// Compute Budget settings of a transaction. Every value which is set results in the corresponding
+/// ComputeBudget instruction being prepended to the fuzzed instruction.
+///
+/// The settings can be specified globally within the Trident Manifest or per instruction using
+/// [`IxOps::get_compute_budget`](crate::ix_ops::IxOps::get_compute_budget). Values specified per
+/// instruction take precedence over the values from the Trident Manifest.
+///
+/// `ComputeBudget` implements `Arbitrary`, so it can be part of the instruction input in order to
+/// fuzz the compute unit limit, heap frame size and the priority fee. The generated values are
+/// always within the bounds accepted by the runtime. The fuzzed compute unit limit can be arbitrarily low,
+/// so exceeding the limit requested by the instruction is not reported as a crash even if
+/// `ComputeBudgetExceeded` is one of the `crash_kinds`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ComputeBudget {
+    /// Compute unit limit of the transaction (`SetComputeUnitLimit`).
+    pub compute_unit_limit: Option<u32>,
+    /// Size of the program heap region in bytes (`RequestHeapFrame`).
+    pub heap_frame_bytes: Option<u32>,
+    /// Priority fee in micro-lamports per compute unit (`SetComputeUnitPrice`).
+    pub compute_unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    pub fn new(
+        compute_unit_limit: Option<u32>,
+        heap_frame_bytes: Option<u32>,
+        compute_unit_price: Option<u64>,
+    ) -> Self {
+        Self {
+            compute_unit_limit,
+            heap_frame_bytes,
+            compute_unit_price,
+        }
+    }
+
+    /// Returns `true` if no value is set, i.e. no ComputeBudget instruction would be created.
+    pub fn is_empty(&self) -> bool {
+        self.compute_unit_limit.is_none()
+            && self.heap_frame_bytes.is_none()
+            && self.compute_unit_price.is_none()
+    }
+
+    /// Fills values which are not set from the `other` ComputeBudget.
+    pub fn or(self, other: &ComputeBudget) -> Self {
+        Self {
+            compute_unit_limit: self.compute_unit_limit.or(other.compute_unit_limit),
+            heap_frame_bytes: self.heap_frame_bytes.or(other.heap_frame_bytes),
+            compute_unit_price: self.compute_unit_price.or(other.compute_unit_price),
+        }
+    }
+
+    /// Creates the ComputeBudget instructions which should be prepended to the transaction.
+    pub fn get_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = vec![];
+        if let Some(compute_unit_limit) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                compute_unit_limit,
+            ));
+        }
+        if let Some(heap_frame_bytes) = self.heap_frame_bytes {
+            instructions.push(ComputeBudgetInstruction::request_heap_frame(
+                heap_frame_bytes,
+            ));
+        }
+        if let Some(compute_unit_price) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                compute_unit_price,
+            ));
+        }
+        instructions
+    }
+}
+
+impl<'a> Arbitrary<'a> for ComputeBudget {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let compute_unit_limit = if u.arbitrary()? {
+            Some(u.int_in_range(0..=MAX_COMPUTE_UNIT_LIMIT)?)
+        } else {
+            None
+        };
+        let heap_frame_bytes = if u.arbitrary()? {
+            let frames = u.int_in_range(
+                MIN_HEAP_FRAME_BYTES / HEAP_FRAME_BYTES_GRANULARITY
+                    ..=MAX_HEAP_FRAME_BYTES / HEAP_FRAME_BYTES_GRANULARITY,
+            )?;
+            Some(frames * HEAP_FRAME_BYTES_GRANULARITY)
+        } else {
+            None
+        };
+        let compute_unit_price = if u.arbitrary()? {
+            // keep the priority fee reasonably low so the payer is not drained within a few iterations
+            Some(u.int_in_range(0..=1_000_000)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            compute_unit_limit,
+            heap_frame_bytes,
+            compute_unit_price,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (3, Some(3 + 4 + 4 + 8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_instructions_by_default() {
+        let compute_budget = ComputeBudget::default();
+
+        assert!(compute_budget.is_empty());
+        assert!(compute_budget.get_instructions().is_empty());
+    }
+    #[test]
+    fn test_instruction_precedence() {
+        let config_budget = ComputeBudget::new(Some(200_000), Some(64 * 1024), None);
+        let ix_budget = ComputeBudget::new(Some(5_000), None, Some(10));
+
+        let compute_budget = ix_budget.or(&config_budget);
+
+        assert_eq!(
+            compute_budget,
+            ComputeBudget::new(Some(5_000), Some(64 * 1024), Some(10))
+        );
+        assert_eq!(
+            compute_budget.get_instructions(),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(5_000),
+                ComputeBudgetInstruction::request_heap_frame(64 * 1024),
+                ComputeBudgetInstruction::set_compute_unit_price(10),
+            ]
+        );
+    }
+    #[test]
+    fn test_arbitrary_within_bounds() {
+        let data: Vec<u8> = (0..=u8::MAX).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+
+        while let Ok(compute_budget) = ComputeBudget::arbitrary(&mut u) {
+            if let Some(compute_unit_limit) = compute_budget.compute_unit_limit {
+                assert!(compute_unit_limit <= MAX_COMPUTE_UNIT_LIMIT);
+            }
+            if let Some(heap_frame_bytes) = compute_budget.heap_frame_bytes {
+                assert!((MIN_HEAP_FRAME_BYTES..=MAX_HEAP_FRAME_BYTES).contains(&heap_frame_bytes));
+                assert_eq!(heap_frame_bytes % HEAP_FRAME_BYTES_GRANULARITY, 0);
+            }
+            if u.is_empty() {
+                break;
+            }
+        }
+    }
+}
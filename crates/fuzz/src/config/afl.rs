@@ -59,14 +59,9 @@ impl Afl {
         self.fuzz_args
             .values()
             .flat_map(|arg| {
-                let val = arg.val.clone().unwrap_or_default();
-                if let Some(opt) = &arg.short_opt {
-                    vec![opt.clone(), val]
-                } else if let Some(opt) = &arg.long_opt {
-                    vec![opt.clone(), val]
-                } else {
-                    vec![]
-                }
+                // the flags without a value are passed alone, as with Honggfuzz
+                let opt = arg.short_opt.as_ref().or(arg.long_opt.as_ref());
+                opt.into_iter().chain(arg.val.as_ref()).cloned()
             })
             .collect()
     }
@@ -2,6 +2,13 @@
 pub const TRIDENT_TOML: &str = "Trident.toml";
 pub const ANCHOR_TOML: &str = "Anchor.toml";
 
+// programs
+pub const SBF_PROGRAMS_DIR_DEFAULT: &str = "target/deploy";
+pub const IDL_DIR_DEFAULT: &str = "target/idl";
+
+// reports
+pub const REPORT_DIR_DEFAULT: &str = "trident-tests/fuzz_tests/fuzzing/reports";
+
 // honggfuzz
 pub const CARGO_TARGET_DIR_DEFAULT_HFUZZ: &str =
     "trident-tests/fuzz_tests/fuzzing/honggfuzz/hfuzz_target";
@@ -20,3 +27,10 @@ pub const AFL_WORKSPACE_DEFAULT_OUT: &str =
 
 pub const DEFAULT_SEED_FILENAME: &str = "trident-seed";
 pub const DEFAULT_SEED: &str = "trident";
+
+// libfuzzer
+pub const CARGO_TARGET_DIR_DEFAULT_LIBFUZZER: &str =
+    "trident-tests/fuzz_tests/fuzzing/libfuzzer/libfuzzer_target";
+
+pub const LIBFUZZER_WORKSPACE_DEFAULT: &str =
+    "trident-tests/fuzz_tests/fuzzing/libfuzzer/libfuzzer_workspace";
@@ -0,0 +1,489 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use base64::Engine;
+use serde::Deserialize;
+use solana_sdk::{account::Account, pubkey::Pubkey, rent::Rent};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    #[error("failed to read account fixture {0}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("failed to parse account fixture {0}: {1}")]
+    Json(PathBuf, serde_json::Error),
+    #[error("invalid {field} \"{value}\" in account fixture {path}")]
+    InvalidPubkey {
+        path: PathBuf,
+        field: &'static str,
+        value: String,
+    },
+    #[error("unsupported data encoding \"{1}\" in account fixture {0}, expected one of: base64, base64+zstd, base58")]
+    UnsupportedEncoding(PathBuf, String),
+    #[error("failed to decode {encoding} data of account fixture {path}: {reason}")]
+    Decode {
+        path: PathBuf,
+        encoding: &'static str,
+        reason: String,
+    },
+    #[error("raw account fixture {0} requires the owner to be specified")]
+    MissingOwner(PathBuf),
+    #[error("account fixture pattern {0} does not match any file")]
+    NoMatch(String),
+    #[error("address override {0} is ambiguous, pattern {1} matches {2} account fixtures")]
+    AmbiguousAddress(String, String, usize),
+}
+
+/// Specification of genesis accounts within the Trident Manifest (`[[fuzz.accounts]]`).
+///
+/// The `filename` can point to a single fixture, to a directory (all `.json` and `.bin` files
+/// within the directory are loaded) or it can be a glob pattern with `*` and `?` wildcards
+/// within the file name, e.g. `accounts/mainnet/*.json`.
+///
+/// JSON fixtures follow the format of `solana account --output json`. Files with any other
+/// extension are loaded as raw account data, their address is taken from the file name
+/// (`<pubkey>.bin`) unless overridden.
+#[derive(Debug, Deserialize, Clone)]
+pub struct _FuzzAccount {
+    pub filename: String,
+    #[serde(default)]
+    /// Overrides the address of the account, the pattern has to match exactly one fixture
+    pub address: Option<String>,
+    #[serde(default)]
+    /// Overrides the owner of all matched accounts, required for raw fixtures
+    pub owner: Option<String>,
+    #[serde(default)]
+    /// Lamports of raw fixtures (default: rent-exempt minimum)
+    pub lamports: Option<u64>,
+    #[serde(default)]
+    /// Executable flag of raw fixtures (default: false)
+    pub executable: Option<bool>,
+    #[serde(default)]
+    /// Pubkeys replaced within the addresses and owners of all matched accounts,
+    /// e.g. the mainnet program ID remapped to the program ID under test
+    pub remap: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AccountFixture {
+    pub filename: String,
+    pub address: Option<String>,
+    pub owner: Option<String>,
+    pub lamports: Option<u64>,
+    pub executable: bool,
+    pub remap: HashMap<String, String>,
+}
+
+impl From<_FuzzAccount> for AccountFixture {
+    fn from(_f: _FuzzAccount) -> Self {
+        Self {
+            filename: _f.filename,
+            address: _f.address,
+            owner: _f.owner,
+            lamports: _f.lamports,
+            executable: _f.executable.unwrap_or_default(),
+            remap: _f.remap.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FuzzAccount {
+    pub pubkey: Pubkey,
+    pub account: Account,
+}
+
+#[derive(Debug, Deserialize)]
+struct FuzzAccountRaw {
+    pubkey: String,
+    account: AccountRaw,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountRaw {
+    lamports: u64,
+    data: AccountDataRaw,
+    owner: String,
+    #[serde(default)]
+    executable: bool,
+    #[serde(default, rename = "rentEpoch")]
+    rent_epoch: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AccountDataRaw {
+    /// `["<data>", "<encoding>"]`
+    Encoded(String, String),
+    /// Legacy binary format, i.e. base58 encoded data
+    Legacy(String),
+}
+
+impl AccountFixture {
+    /// Loads all accounts matched by the fixture. `root` is used to resolve relative paths.
+    pub fn load(&self, root: &Path) -> Result<Vec<FuzzAccount>, FixtureError> {
+        let files = find_files(root, &self.filename)?;
+
+        let address = match &self.address {
+            Some(_) if files.len() != 1 => {
+                return Err(FixtureError::AmbiguousAddress(
+                    self.address.clone().unwrap_or_default(),
+                    self.filename.clone(),
+                    files.len(),
+                ))
+            }
+            Some(address) => Some(parse_pubkey(&self.filename, "address", address)?),
+            None => None,
+        };
+        let owner = self
+            .owner
+            .as_ref()
+            .map(|owner| parse_pubkey(&self.filename, "owner", owner))
+            .transpose()?;
+        let remap = self
+            .remap
+            .iter()
+            .map(|(from, to)| {
+                Ok((
+                    parse_pubkey(&self.filename, "remap key", from)?,
+                    parse_pubkey(&self.filename, "remap value", to)?,
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, FixtureError>>()?;
+
+        let mut accounts = Vec::with_capacity(files.len());
+        for path in files {
+            let mut fuzz_account = if is_json(&path) {
+                read_json_fixture(&path)?
+            } else {
+                let pubkey = match (address, path.file_stem().and_then(|s| s.to_str())) {
+                    (Some(address), _) => address,
+                    (None, stem) => parse_pubkey(&path, "file name", stem.unwrap_or_default())?,
+                };
+                let owner = owner.ok_or_else(|| FixtureError::MissingOwner(path.clone()))?;
+                let data = fs::read(&path).map_err(|e| FixtureError::Io(path.clone(), e))?;
+                let account = Account {
+                    lamports: self
+                        .lamports
+                        .unwrap_or_else(|| Rent::default().minimum_balance(data.len())),
+                    data,
+                    owner,
+                    executable: self.executable,
+                    rent_epoch: 0,
+                };
+                FuzzAccount { pubkey, account }
+            };
+
+            if let Some(address) = address {
+                fuzz_account.pubkey = address;
+            }
+            if let Some(owner) = owner {
+                fuzz_account.account.owner = owner;
+            }
+            if let Some(pubkey) = remap.get(&fuzz_account.pubkey) {
+                fuzz_account.pubkey = *pubkey;
+            }
+            if let Some(owner) = remap.get(&fuzz_account.account.owner) {
+                fuzz_account.account.owner = *owner;
+            }
+            accounts.push(fuzz_account);
+        }
+        Ok(accounts)
+    }
+}
+
+fn read_json_fixture(path: &Path) -> Result<FuzzAccount, FixtureError> {
+    let file_content =
+        fs::read_to_string(path).map_err(|e| FixtureError::Io(path.to_path_buf(), e))?;
+    let account_raw: FuzzAccountRaw = serde_json::from_str(&file_content)
+        .map_err(|e| FixtureError::Json(path.to_path_buf(), e))?;
+
+    let pubkey = parse_pubkey(path, "pubkey", &account_raw.pubkey)?;
+    let owner = parse_pubkey(path, "owner", &account_raw.account.owner)?;
+    let data = match &account_raw.account.data {
+        AccountDataRaw::Encoded(data, encoding) => decode_data(path, data, encoding)?,
+        AccountDataRaw::Legacy(data) => decode_data(path, data, "base58")?,
+    };
+
+    Ok(FuzzAccount {
+        pubkey,
+        account: Account {
+            lamports: account_raw.account.lamports,
+            data,
+            owner,
+            executable: account_raw.account.executable,
+            rent_epoch: account_raw.account.rent_epoch,
+        },
+    })
+}
+
+fn decode_data(path: &Path, data: &str, encoding: &str) -> Result<Vec<u8>, FixtureError> {
+    let decode_error = |encoding: &'static str, reason: String| FixtureError::Decode {
+        path: path.to_path_buf(),
+        encoding,
+        reason,
+    };
+    let base64_decode = |encoding: &'static str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| decode_error(encoding, e.to_string()))
+    };
+
+    match encoding {
+        "base64" => base64_decode("base64"),
+        "base64+zstd" => {
+            let compressed = base64_decode("base64+zstd")?;
+            zstd::stream::decode_all(compressed.as_slice())
+                .map_err(|e| decode_error("base64+zstd", e.to_string()))
+        }
+        // `binary` is the legacy name of the base58 encoding
+        "base58" | "binary" => bs58::decode(data)
+            .into_vec()
+            .map_err(|e| decode_error("base58", e.to_string())),
+        _ => Err(FixtureError::UnsupportedEncoding(
+            path.to_path_buf(),
+            encoding.to_string(),
+        )),
+    }
+}
+
+fn parse_pubkey(
+    path: impl AsRef<Path>,
+    field: &'static str,
+    value: &str,
+) -> Result<Pubkey, FixtureError> {
+    Pubkey::from_str(value).map_err(|_| FixtureError::InvalidPubkey {
+        path: path.as_ref().to_path_buf(),
+        field,
+        value: value.to_string(),
+    })
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+/// Returns the sorted list of files matched by the fixture `filename`.
+fn find_files(root: &Path, filename: &str) -> Result<Vec<PathBuf>, FixtureError> {
+    let path = root.join(filename);
+
+    let mut files = if path.is_dir() {
+        read_dir(&path)?
+            .into_iter()
+            .filter(|file| {
+                file.extension()
+                    .is_some_and(|extension| extension == "json" || extension == "bin")
+            })
+            .collect()
+    } else if is_pattern(filename) {
+        let pattern = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let dir = path.parent().unwrap_or(root);
+        read_dir(dir)?
+            .into_iter()
+            .filter(|file| {
+                file.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| wildcard_match(&pattern, name))
+            })
+            .collect()
+    } else {
+        vec![path]
+    };
+
+    if files.is_empty() {
+        return Err(FixtureError::NoMatch(filename.to_string()));
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, FixtureError> {
+    let entries = fs::read_dir(dir).map_err(|e| FixtureError::Io(dir.to_path_buf(), e))?;
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| FixtureError::Io(dir.to_path_buf(), e))?
+            .path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn is_pattern(filename: &str) -> bool {
+    filename.contains(['*', '?'])
+}
+
+/// Matches the name against the pattern with `*` (any sequence) and `?` (any character) wildcards.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("trident-fixtures-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn json_fixture(pubkey: &Pubkey, owner: &Pubkey, data: &str, encoding: &str) -> String {
+        format!(
+            r#"{{"pubkey":"{pubkey}","account":{{"lamports":1000,"data":["{data}","{encoding}"],"owner":"{owner}","executable":false,"rentEpoch":0}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.json", "account.json"));
+        assert!(wildcard_match("acc?unt*", "account.json"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("*.json", "account.bin"));
+        assert!(!wildcard_match("a?", "a"));
+    }
+    #[test]
+    fn test_decode_data() {
+        let path = Path::new("fixture.json");
+        let data = vec![1, 2, 3, 4, 0, 0, 0, 0];
+        let base64 = base64::engine::general_purpose::STANDARD.encode(&data);
+        let compressed = zstd::stream::encode_all(data.as_slice(), 0).unwrap();
+        let base64_zstd = base64::engine::general_purpose::STANDARD.encode(compressed);
+        let base58 = bs58::encode(&data).into_string();
+
+        assert_eq!(decode_data(path, &base64, "base64").unwrap(), data);
+        assert_eq!(
+            decode_data(path, &base64_zstd, "base64+zstd").unwrap(),
+            data
+        );
+        assert_eq!(decode_data(path, &base58, "base58").unwrap(), data);
+        assert!(matches!(
+            decode_data(path, &base64, "jsonParsed"),
+            Err(FixtureError::UnsupportedEncoding(..))
+        ));
+        assert!(matches!(
+            decode_data(path, "not base64!", "base64"),
+            Err(FixtureError::Decode { .. })
+        ));
+    }
+    #[test]
+    fn test_load_directory_with_overrides() {
+        let dir = fixture_dir("directory");
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mainnet_program, program) = (Pubkey::new_unique(), Pubkey::new_unique());
+        fs::write(
+            dir.join("first.json"),
+            json_fixture(&first, &mainnet_program, "AQID", "base64"),
+        )
+        .unwrap();
+        fs::write(dir.join(format!("{second}.bin")), [7u8; 16]).unwrap();
+        fs::write(dir.join("README.md"), "not a fixture").unwrap();
+
+        let fixture = AccountFixture {
+            filename: dir.to_string_lossy().to_string(),
+            address: None,
+            owner: None,
+            lamports: None,
+            executable: false,
+            remap: HashMap::from([(mainnet_program.to_string(), program.to_string())]),
+        };
+        // raw fixture requires the owner
+        assert!(matches!(
+            fixture.load(&dir),
+            Err(FixtureError::MissingOwner(_))
+        ));
+
+        let fixture = AccountFixture {
+            owner: Some(mainnet_program.to_string()),
+            ..fixture
+        };
+        let accounts = fixture.load(&dir).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts.iter().all(|a| a.account.owner == program));
+
+        let raw = accounts.iter().find(|a| a.pubkey == second).unwrap();
+        assert_eq!(raw.account.data, vec![7u8; 16]);
+        assert_eq!(raw.account.lamports, Rent::default().minimum_balance(16));
+        let json = accounts.iter().find(|a| a.pubkey == first).unwrap();
+        assert_eq!(json.account.data, vec![1, 2, 3]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+    #[test]
+    fn test_load_errors() {
+        let dir = fixture_dir("errors");
+        let pubkey = Pubkey::new_unique();
+        fs::write(
+            dir.join("a.json"),
+            json_fixture(&pubkey, &pubkey, "", "base64"),
+        )
+        .unwrap();
+        fs::write(dir.join("b.json"), "{ malformed").unwrap();
+
+        let fixture = |filename: &str, address: Option<String>| AccountFixture {
+            filename: filename.to_string(),
+            address,
+            owner: None,
+            lamports: None,
+            executable: false,
+            remap: HashMap::new(),
+        };
+
+        assert!(matches!(
+            fixture("*.json", Some(Pubkey::new_unique().to_string())).load(&dir),
+            Err(FixtureError::AmbiguousAddress(_, _, 2))
+        ));
+        assert!(matches!(
+            fixture("b.json", None).load(&dir),
+            Err(FixtureError::Json(..))
+        ));
+        assert!(matches!(
+            fixture("*.txt", None).load(&dir),
+            Err(FixtureError::NoMatch(_))
+        ));
+
+        let address = Pubkey::new_unique();
+        let accounts = fixture("a*", Some(address.to_string())).load(&dir).unwrap();
+        assert_eq!(accounts[0].pubkey, address);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,13 +1,19 @@
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use solana_sdk::pubkey::Pubkey;
 
+use crate::compute_budget::ComputeBudget;
+use crate::crash_classifier::CrashKind;
+
+use super::constants::{REPORT_DIR_DEFAULT, SBF_PROGRAMS_DIR_DEFAULT};
 use super::discover_root;
+use super::fixtures::{AccountFixture, FixtureError, FuzzAccount, _FuzzAccount};
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct Fuzz {
@@ -15,6 +21,14 @@ pub struct Fuzz {
     pub allow_duplicate_txs: bool,
     pub programs: Vec<FuzzProgram>,
     pub accounts: Vec<FuzzAccount>,
+    pub reference_programs: Vec<FuzzProgram>,
+    pub compute_budget: ComputeBudget,
+    pub execution_mode: ExecutionMode,
+    pub engine: Option<String>,
+    pub targets: HashMap<String, FuzzTarget>,
+    pub crash_kinds: Vec<CrashKind>,
+    pub report_dir: String,
+    pub report_csv: bool,
 }
 
 #[derive(Default, Debug, Deserialize, Clone)]
@@ -26,33 +40,89 @@ pub struct _Fuzz {
     #[serde(default)]
     pub programs: Option<Vec<_FuzzProgram>>,
     #[serde(default)]
+    /// Genesis account fixtures, a single file, a directory or a glob pattern
     pub accounts: Option<Vec<_FuzzAccount>>,
+    #[serde(default)]
+    /// Previous versions of the programs under test used as the reference during differential fuzzing
+    pub reference_programs: Option<Vec<_FuzzProgram>>,
+    #[serde(default)]
+    /// Compute unit limit of each transaction (default: 0 [runtime default])
+    pub compute_unit_limit: Option<u32>,
+    #[serde(default)]
+    /// Program heap region size in bytes, multiple of 1024 (default: 0 [runtime default])
+    pub heap_frame_bytes: Option<u32>,
+    #[serde(default)]
+    /// Priority fee in micro-lamports per compute unit (default: 0 [no priority fee])
+    pub compute_unit_price: Option<u64>,
+    #[serde(default)]
+    /// Execution mode of the programs under test (default: "native")
+    pub execution_mode: Option<ExecutionMode>,
+    #[serde(default)]
+    /// Fuzzing engine used when the engine is not specified on the command line (default: "honggfuzz")
+    pub engine: Option<String>,
+    #[serde(default)]
+    /// Settings overridden for particular fuzz targets, e.g. [fuzz.targets.fuzz_0]
+    pub targets: Option<HashMap<String, _FuzzTarget>>,
+    #[serde(default)]
+    /// Kinds of transaction failures reported as crashes (default: all kinds)
+    pub crash_kinds: Option<Vec<CrashKind>>,
+    #[serde(default)]
+    /// Directory of the fuzzing reports written at the end of the session with statistics
+    /// (default: "trident-tests/fuzz_tests/fuzzing/reports")
+    pub report_dir: Option<String>,
+    #[serde(default)]
+    /// Write also the CSV export of the fuzzing report (default: false)
+    pub report_csv: Option<bool>,
 }
-impl From<_Fuzz> for Fuzz {
-    fn from(_f: _Fuzz) -> Self {
+impl TryFrom<_Fuzz> for Fuzz {
+    type Error = FixtureError;
+
+    fn try_from(_f: _Fuzz) -> Result<Self, Self::Error> {
+        // zero means that the ComputeBudget instruction is not included at all
+        let compute_budget = ComputeBudget::new(
+            _f.compute_unit_limit.filter(|value| *value > 0),
+            _f.heap_frame_bytes.filter(|value| *value > 0),
+            _f.compute_unit_price.filter(|value| *value > 0),
+        );
+
         let mut _self = Self {
             fuzzing_with_stats: _f.fuzzing_with_stats.unwrap_or_default(),
             allow_duplicate_txs: _f.allow_duplicate_txs.unwrap_or_default(),
             programs: vec![],
             accounts: vec![],
+            reference_programs: vec![],
+            compute_budget,
+            execution_mode: _f.execution_mode.unwrap_or_default(),
+            engine: _f.engine.filter(|value| !value.is_empty()),
+            targets: HashMap::new(),
+            crash_kinds: _f.crash_kinds.unwrap_or(CrashKind::DEFAULT.to_vec()),
+            report_dir: _f.report_dir.unwrap_or(REPORT_DIR_DEFAULT.to_string()),
+            report_csv: _f.report_csv.unwrap_or_default(),
         };
 
-        if let Some(accounts) = _f.accounts {
-            for account in accounts {
-                _self
-                    .accounts
-                    .push(read_and_parse_account(&account.filename));
+        if let Some(targets) = _f.targets {
+            for (target, settings) in targets {
+                _self.targets.insert(target, settings.into());
             }
         }
+
+        if let Some(accounts) = _f.accounts {
+            _self.accounts = load_accounts(accounts)?;
+        }
         if let Some(programs) = _f.programs {
             for account in programs {
+                _self.programs.push(read_and_parse_program(&account));
+            }
+        }
+        if let Some(reference_programs) = _f.reference_programs {
+            for program in reference_programs {
                 _self
-                    .programs
-                    .push(read_and_parse_program(&account.program, &account.address));
+                    .reference_programs
+                    .push(read_and_parse_program(&program));
             }
         }
 
-        _self
+        Ok(_self)
     }
 }
 
@@ -63,107 +133,150 @@ impl Fuzz {
     pub fn get_allow_duplicate_txs(&self) -> bool {
         self.allow_duplicate_txs
     }
+    pub fn get_accounts(&self) -> &[FuzzAccount] {
+        &self.accounts
+    }
+    pub fn get_compute_budget(&self) -> &ComputeBudget {
+        &self.compute_budget
+    }
+    pub fn get_crash_kinds(&self) -> &[CrashKind] {
+        &self.crash_kinds
+    }
+    pub fn get_report_dir(&self) -> PathBuf {
+        resolve_path(&self.report_dir)
+    }
+    pub fn get_report_csv(&self) -> bool {
+        self.report_csv
+    }
+    /// Returns the execution mode of the given fuzz target, falls back to the
+    /// execution mode of the whole fuzzing environment.
+    pub fn get_execution_mode(&self, target: Option<&str>) -> ExecutionMode {
+        target
+            .and_then(|target| self.targets.get(target))
+            .and_then(|target| target.execution_mode)
+            .unwrap_or(self.execution_mode)
+    }
+    /// Returns the fuzzing engine configured for the given fuzz target, falls back to the
+    /// fuzzing engine of the whole fuzzing environment.
+    pub fn get_engine(&self, target: &str) -> Option<&str> {
+        self.targets
+            .get(target)
+            .and_then(|target| target.engine.as_deref())
+            .or(self.engine.as_deref())
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct _FuzzProgram {
-    pub address: String,
-    pub program: String,
+/// Specifies how the programs under test are loaded into the fuzzing environment.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// Programs are compiled into the fuzz test and registered as native builtins.
+    #[default]
+    Native,
+    /// Programs are loaded from the compiled SBF binaries within `target/deploy`.
+    Sbf,
+    /// Every transaction is executed against both the native build and the reference environment
+    /// and any divergence in results or account states is reported as a crash. The reference
+    /// environment contains the reference programs if specified, otherwise the SBF build.
+    Differential,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct _FuzzAccount {
-    pub address: String,
-    pub filename: String,
+#[derive(Default, Debug, Deserialize, Clone)]
+pub struct _FuzzTarget {
+    #[serde(default)]
+    pub execution_mode: Option<ExecutionMode>,
+    #[serde(default)]
+    pub engine: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct FuzzProgram {
-    pub address: Pubkey,
-    pub data: Vec<u8>,
+#[derive(Default, Debug, Deserialize, Clone)]
+pub struct FuzzTarget {
+    pub execution_mode: Option<ExecutionMode>,
+    pub engine: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct FuzzAccount {
-    pub pubkey: Pubkey,
-    pub account: Account,
+impl From<_FuzzTarget> for FuzzTarget {
+    fn from(_f: _FuzzTarget) -> Self {
+        Self {
+            execution_mode: _f.execution_mode,
+            engine: _f.engine.filter(|value| !value.is_empty()),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Account {
-    pub lamports: u64,
-    pub data: String,
-    pub owner: Pubkey,
-    pub executable: bool,
-    pub rent_epoch: u64,
+#[derive(Debug, Deserialize, Clone)]
+pub struct _FuzzProgram {
+    pub address: String,
+    pub program: String,
+    #[serde(default)]
+    /// Deploy the program through the upgradeable BPF loader together with its ProgramData account (default: false)
+    pub upgradeable: Option<bool>,
+    #[serde(default)]
+    /// Upgrade authority of the upgradeable program (default: none [immutable program])
+    pub upgrade_authority: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
-pub struct FuzzAccountRaw {
-    pub pubkey: String,
-    pub account: AccountRaw,
+pub struct FuzzProgram {
+    pub address: Pubkey,
+    pub data: Vec<u8>,
+    pub upgradeable: bool,
+    pub upgrade_authority: Option<Pubkey>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AccountRaw {
-    pub lamports: u64,
-    pub data: Vec<String>,
-    pub owner: String,
-    pub executable: bool,
-    #[serde(rename = "rentEpoch")]
-    pub rent_epoch: u64,
+/// Loads the genesis accounts from all account fixtures. Fixtures are decoded only once when
+/// the configuration is loaded, not for every fuzzing iteration.
+fn load_accounts(accounts: Vec<_FuzzAccount>) -> Result<Vec<FuzzAccount>, FixtureError> {
+    let root = discover_root().unwrap_or_default();
+    let mut loaded = vec![];
+    for account in accounts {
+        loaded.extend(AccountFixture::from(account).load(&root)?);
+    }
+    Ok(loaded)
 }
 
-fn read_and_parse_program(filename: &str, program_address: &str) -> FuzzProgram {
-    let path = resolve_path(filename);
+fn read_and_parse_program(program: &_FuzzProgram) -> FuzzProgram {
+    let path = resolve_path(&program.program);
 
     let program_data =
-        fs::read(path).unwrap_or_else(|_| panic!("Failed to read file: {}", filename));
+        fs::read(path).unwrap_or_else(|_| panic!("Failed to read file: {}", program.program));
 
-    let pubkey = Pubkey::from_str(program_address)
-        .unwrap_or_else(|_| panic!("Cannot parse the program address: {}", program_address));
+    let pubkey = Pubkey::from_str(&program.address)
+        .unwrap_or_else(|_| panic!("Cannot parse the program address: {}", program.address));
+
+    let upgrade_authority = program.upgrade_authority.as_ref().map(|authority| {
+        Pubkey::from_str(authority)
+            .unwrap_or_else(|_| panic!("Cannot parse the upgrade authority: {}", authority))
+    });
 
     FuzzProgram {
         address: pubkey,
         data: program_data,
+        upgradeable: program.upgradeable.unwrap_or_default() || upgrade_authority.is_some(),
+        upgrade_authority,
     }
 }
 
-fn read_and_parse_account(filename: &str) -> FuzzAccount {
-    let path = resolve_path(filename);
-
-    let file_content =
-        fs::read_to_string(path).unwrap_or_else(|_| panic!("Failed to read file: {}", filename));
-
-    let account_raw: FuzzAccountRaw = serde_json::from_str(&file_content)
-        .unwrap_or_else(|_| panic!("Failed to parse JSON from file: {}", filename));
-
-    let pubkey = Pubkey::from_str(&account_raw.pubkey)
-        .unwrap_or_else(|_| panic!("Cannot convert address for: {}", account_raw.pubkey));
-
-    let owner_address = Pubkey::from_str(&account_raw.account.owner).unwrap_or_else(|_| {
-        panic!(
-            "Cannot convert address for owner: {}",
-            account_raw.account.owner
-        )
-    });
-
-    let data_base_64 = account_raw.account.data.first().unwrap_or_else(|| {
-        panic!(
-            "Cannot read base64 data for account: {}",
-            account_raw.pubkey
-        )
-    });
+/// Returns the name of the currently executed fuzz target, i.e. the name of the fuzz test binary.
+pub fn current_fuzz_target() -> Option<String> {
+    let exe = std::env::current_exe().ok()?;
+    let name = exe.file_stem()?.to_str()?;
+    Some(strip_metadata_hash(name).to_string())
+}
 
-    let account = Account {
-        lamports: account_raw.account.lamports,
-        data: data_base_64.to_string(),
-        owner: owner_address,
-        executable: account_raw.account.executable,
-        rent_epoch: account_raw.account.rent_epoch,
-    };
+/// Strips the metadata hash suffix of test binaries built by cargo, i.e. fuzz_0-1a2b3c4d5e6f7a8b.
+fn strip_metadata_hash(name: &str) -> &str {
+    match name.rsplit_once('-') {
+        Some((target, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            target
+        }
+        _ => name,
+    }
+}
 
-    FuzzAccount { pubkey, account }
+/// Returns the path to the compiled SBF binary of the program.
+pub fn sbf_program_path(program_name: &str) -> PathBuf {
+    resolve_path(&format!("{SBF_PROGRAMS_DIR_DEFAULT}/{program_name}.so"))
 }
 
 fn resolve_path(filename: &str) -> PathBuf {
@@ -176,3 +289,115 @@ fn resolve_path(filename: &str) -> PathBuf {
             .unwrap_or_else(|_| panic!("Failed to resolve relative path: {}", path.display()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_budget_zero_is_not_set() {
+        let fuzz: Fuzz = toml::from_str::<_Fuzz>(
+            r#"
+            compute_unit_limit = 0
+            heap_frame_bytes = 65536
+            "#,
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+        assert_eq!(
+            fuzz.get_compute_budget(),
+            &ComputeBudget::new(None, Some(65536), None)
+        );
+    }
+    #[test]
+    fn test_crash_kinds() {
+        let fuzz: Fuzz = toml::from_str::<_Fuzz>("").unwrap().try_into().unwrap();
+        assert_eq!(fuzz.get_crash_kinds(), CrashKind::DEFAULT);
+
+        let fuzz: Fuzz = toml::from_str::<_Fuzz>(
+            r#"
+            crash_kinds = ["arithmetic_overflow", "access_violation"]
+            "#,
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(
+            fuzz.get_crash_kinds(),
+            [CrashKind::ArithmeticOverflow, CrashKind::AccessViolation]
+        );
+    }
+    #[test]
+    fn test_execution_mode_target_override() {
+        let fuzz: Fuzz = toml::from_str::<_Fuzz>(
+            r#"
+            execution_mode = "sbf"
+
+            [targets.fuzz_1]
+            execution_mode = "differential"
+            "#,
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+        assert_eq!(fuzz.get_execution_mode(None), ExecutionMode::Sbf);
+        assert_eq!(fuzz.get_execution_mode(Some("fuzz_0")), ExecutionMode::Sbf);
+        assert_eq!(
+            fuzz.get_execution_mode(Some("fuzz_1")),
+            ExecutionMode::Differential
+        );
+        assert_eq!(
+            Fuzz::default().get_execution_mode(Some("fuzz_1")),
+            ExecutionMode::Native
+        );
+    }
+    #[test]
+    fn test_strip_metadata_hash() {
+        assert_eq!(strip_metadata_hash("fuzz_0-1a2b3c4d5e6f7a8b"), "fuzz_0");
+        assert_eq!(strip_metadata_hash("escrow-1-1a2b3c4d5e6f7a8b"), "escrow-1");
+        assert_eq!(strip_metadata_hash("escrow-1"), "escrow-1");
+        assert_eq!(strip_metadata_hash("fuzz-cafe"), "fuzz-cafe");
+        assert_eq!(strip_metadata_hash("fuzz_0"), "fuzz_0");
+    }
+    #[test]
+    fn test_engine_target_override() {
+        let fuzz: Fuzz = toml::from_str::<_Fuzz>(
+            r#"
+            engine = "libfuzzer"
+
+            [targets.fuzz_1]
+            engine = "afl"
+            "#,
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+        assert_eq!(fuzz.get_engine("fuzz_0"), Some("libfuzzer"));
+        assert_eq!(fuzz.get_engine("fuzz_1"), Some("afl"));
+        assert_eq!(Fuzz::default().get_engine("fuzz_1"), None);
+    }
+    #[test]
+    fn test_malformed_account_fixture() {
+        let path = std::env::temp_dir().join(format!(
+            "trident-malformed-fixture-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, "{ malformed").unwrap();
+
+        let fuzz = toml::from_str::<_Fuzz>(&format!(
+            "accounts = [{{ filename = {:?} }}]",
+            path.to_string_lossy()
+        ))
+        .unwrap();
+        assert!(matches!(
+            Fuzz::try_from(fuzz),
+            Err(FixtureError::Json(file, _)) if file == path
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
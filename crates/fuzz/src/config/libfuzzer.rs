@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::constants::*;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LibFuzzer {
+    pub cargo_target_dir: String,
+    pub libfuzzer_workspace: String,
+    pub dict: Option<String>,
+    pub use_value_profile: bool,
+    pub fork: u32,
+    pub runs: u64,
+    pub max_total_time: u64,
+    pub max_len: u64,
+    pub timeout: u64,
+}
+
+impl LibFuzzer {
+    pub fn get_cargo_target_dir(&self) -> &str {
+        &self.cargo_target_dir
+    }
+    pub fn get_workspace(&self) -> &str {
+        &self.libfuzzer_workspace
+    }
+    /// Directory of the inputs kept by libFuzzer for the given target.
+    pub fn get_corpus_dir(&self, target: &str) -> PathBuf {
+        Path::new(&self.libfuzzer_workspace)
+            .join(target)
+            .join("corpus")
+    }
+    /// Directory of the crashes, timeouts and out-of-memory inputs found by libFuzzer for the given target.
+    pub fn get_artifacts_dir(&self, target: &str) -> PathBuf {
+        Path::new(&self.libfuzzer_workspace)
+            .join(target)
+            .join("artifacts")
+    }
+    /// Options passed to libFuzzer, except the corpus and the artifact directories.
+    pub fn get_collect_fuzz_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(dict) = &self.dict {
+            args.push(format!("-dict={dict}"));
+        }
+        if self.use_value_profile {
+            args.push("-use_value_profile=1".to_string());
+        }
+        if self.fork > 0 {
+            // the crashes are collected in the artifact directory and the fuzzing continues
+            args.push(format!("-fork={}", self.fork));
+            args.push("-ignore_crashes=1".to_string());
+            args.push("-ignore_timeouts=1".to_string());
+            args.push("-ignore_ooms=1".to_string());
+        }
+        if self.runs > 0 {
+            args.push(format!("-runs={}", self.runs));
+        }
+        if self.max_total_time > 0 {
+            args.push(format!("-max_total_time={}", self.max_total_time));
+        }
+        if self.max_len > 0 {
+            args.push(format!("-max_len={}", self.max_len));
+        }
+        if self.timeout > 0 {
+            args.push(format!("-timeout={}", self.timeout));
+        }
+        args
+    }
+}
+
+#[derive(Default, Debug, Deserialize, Clone)]
+pub struct _LibFuzzer {
+    #[serde(default)]
+    pub cargo_target_dir: Option<String>,
+    #[serde(default)]
+    pub libfuzzer_workspace: Option<String>,
+    #[serde(default)]
+    pub dict: Option<String>,
+    #[serde(default)]
+    pub use_value_profile: Option<bool>,
+    #[serde(default)]
+    pub fork: Option<u32>,
+    #[serde(default)]
+    pub runs: Option<u64>,
+    #[serde(default)]
+    pub max_total_time: Option<u64>,
+    #[serde(default)]
+    pub max_len: Option<u64>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+impl From<_LibFuzzer> for LibFuzzer {
+    fn from(_f: _LibFuzzer) -> Self {
+        Self {
+            cargo_target_dir: _f
+                .cargo_target_dir
+                .filter(|value| !value.is_empty())
+                .unwrap_or(CARGO_TARGET_DIR_DEFAULT_LIBFUZZER.to_owned()),
+            libfuzzer_workspace: _f
+                .libfuzzer_workspace
+                .filter(|value| !value.is_empty())
+                .unwrap_or(LIBFUZZER_WORKSPACE_DEFAULT.to_owned()),
+            dict: _f.dict.filter(|value| !value.is_empty()),
+            use_value_profile: _f.use_value_profile.unwrap_or_default(),
+            fork: _f.fork.unwrap_or_default(),
+            runs: _f.runs.unwrap_or_default(),
+            max_total_time: _f.max_total_time.unwrap_or_default(),
+            max_len: _f.max_len.unwrap_or_default(),
+            timeout: _f.timeout.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_fuzz_args() {
+        let libfuzzer: LibFuzzer = _LibFuzzer::default().into();
+
+        assert_eq!(
+            libfuzzer.cargo_target_dir,
+            CARGO_TARGET_DIR_DEFAULT_LIBFUZZER
+        );
+        assert_eq!(
+            libfuzzer.get_corpus_dir("fuzz_0"),
+            Path::new(LIBFUZZER_WORKSPACE_DEFAULT).join("fuzz_0/corpus")
+        );
+        assert!(libfuzzer.get_collect_fuzz_args().is_empty());
+    }
+
+    #[test]
+    fn test_fuzz_args() {
+        let libfuzzer: LibFuzzer = toml::from_str::<_LibFuzzer>(
+            r#"
+            dict = "trident-tests/fuzz_tests/fuzz_0/fuzz_0.dict"
+            use_value_profile = true
+            fork = 4
+            max_total_time = 3600
+            "#,
+        )
+        .unwrap()
+        .into();
+
+        assert_eq!(
+            libfuzzer.get_collect_fuzz_args(),
+            vec![
+                "-dict=trident-tests/fuzz_tests/fuzz_0/fuzz_0.dict",
+                "-use_value_profile=1",
+                "-fork=4",
+                "-ignore_crashes=1",
+                "-ignore_timeouts=1",
+                "-ignore_ooms=1",
+                "-max_total_time=3600",
+            ]
+        );
+    }
+}
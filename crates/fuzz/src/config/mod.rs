@@ -1,13 +1,19 @@
 pub mod afl;
 pub mod constants;
+pub mod fixtures;
 pub mod fuzz;
 pub mod honggfuzz;
+pub mod libfuzzer;
 
 use afl::*;
 use constants::*;
+use fixtures::FixtureError;
 use fuzz::*;
 use honggfuzz::*;
+use libfuzzer::*;
 
+use crate::compute_budget::ComputeBudget;
+use crate::crash_classifier::CrashKind;
 use anyhow::Context;
 use fehler::throw;
 use serde::Deserialize;
@@ -24,12 +30,15 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("{0:?}")]
     Toml(#[from] toml::de::Error),
+    #[error("{0}")]
+    Fixture(#[from] FixtureError),
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub honggfuzz: HonggFuzz,
     pub afl: Afl,
+    pub libfuzzer: LibFuzzer,
     pub fuzz: Fuzz,
 }
 
@@ -40,16 +49,21 @@ struct _Config {
     #[serde(default)]
     pub afl: Option<_Afl>,
     #[serde(default)]
+    pub libfuzzer: Option<_LibFuzzer>,
+    #[serde(default)]
     pub fuzz: Option<_Fuzz>,
 }
 
-impl From<_Config> for Config {
-    fn from(_c: _Config) -> Self {
-        Self {
+impl TryFrom<_Config> for Config {
+    type Error = Error;
+
+    fn try_from(_c: _Config) -> Result<Self, Self::Error> {
+        Ok(Self {
             honggfuzz: _c.honggfuzz.unwrap_or_default().into(),
             afl: _c.afl.unwrap_or_default().into(),
-            fuzz: _c.fuzz.unwrap_or_default().into(),
-        }
+            libfuzzer: _c.libfuzzer.unwrap_or_default().into(),
+            fuzz: _c.fuzz.unwrap_or_default().try_into()?,
+        })
     }
 }
 
@@ -60,12 +74,18 @@ impl Default for Config {
 }
 
 impl Config {
+    /// Loads the Trident config, panics if it cannot be loaded. Use [`Config::load`] to handle the errors.
     pub fn new() -> Self {
-        let root = discover_root().expect("failed to find the root folder");
+        Self::load().unwrap_or_else(|e| panic!("Failed to load the Trident config file: {e}"))
+    }
+
+    /// Loads the Trident config including the account fixtures.
+    pub fn load() -> Result<Self, Error> {
+        let root = discover_root()?;
         let s = fs::read_to_string(root.join(TRIDENT_TOML).as_path())
-            .expect("failed to read the Trident config file");
-        let _config: _Config = toml::from_str(&s).expect("failed to parse the Trident config file");
-        _config.into()
+            .context("failed to read the Trident config file")?;
+        let _config: _Config = toml::from_str(&s)?;
+        _config.try_into()
     }
 
     pub fn get_honggfuzz_args(&self, cli_input: String) -> String {
@@ -117,20 +137,66 @@ impl Config {
             .clone()
             .expect("AFL Workspace in value not available")
     }
+    pub fn get_afl_workspace_out(&self) -> String {
+        let afl_arg = self
+            .afl
+            .get_workspace_out()
+            .expect("AFL Workspace out value argument available");
+
+        afl_arg
+            .val
+            .clone()
+            .expect("AFL Workspace out value not available")
+    }
     pub fn get_initial_seed(&self) -> &[AflSeed] {
         &self.afl.seeds
     }
 
+    pub fn get_libfuzzer_cargo_target_dir(&self) -> &str {
+        self.libfuzzer.get_cargo_target_dir()
+    }
+    pub fn get_libfuzzer_workspace(&self) -> &str {
+        self.libfuzzer.get_workspace()
+    }
+    pub fn get_libfuzzer_corpus_dir(&self, target: &str) -> PathBuf {
+        self.libfuzzer.get_corpus_dir(target)
+    }
+    pub fn get_libfuzzer_artifacts_dir(&self, target: &str) -> PathBuf {
+        self.libfuzzer.get_artifacts_dir(target)
+    }
+    pub fn get_libfuzzer_fuzz_args(&self) -> Vec<String> {
+        self.libfuzzer.get_collect_fuzz_args()
+    }
+
     pub fn get_fuzzing_with_stats(&self) -> bool {
         self.fuzz.get_fuzzing_with_stats()
     }
     pub fn get_allow_duplicate_txs(&self) -> bool {
         self.fuzz.get_allow_duplicate_txs()
     }
+    pub fn get_compute_budget(&self) -> &ComputeBudget {
+        self.fuzz.get_compute_budget()
+    }
+    pub fn get_crash_kinds(&self) -> &[CrashKind] {
+        self.fuzz.get_crash_kinds()
+    }
+    pub fn get_report_dir(&self) -> PathBuf {
+        self.fuzz.get_report_dir()
+    }
+    pub fn get_report_csv(&self) -> bool {
+        self.fuzz.get_report_csv()
+    }
+    pub fn get_execution_mode(&self) -> ExecutionMode {
+        self.fuzz
+            .get_execution_mode(current_fuzz_target().as_deref())
+    }
+    pub fn get_engine(&self, target: &str) -> Option<&str> {
+        self.fuzz.get_engine(target)
+    }
 }
 
-/// Tries to find the root directory with the `Anchor.toml` file.
-/// Throws an error when there is no directory with the `Anchor.toml` file
+/// Tries to find the root directory with the `Trident.toml` or the `Anchor.toml` file.
+/// Throws an error when there is no directory with any of these files
 pub fn discover_root() -> Result<PathBuf, Error> {
     let current_dir = env::current_dir()?;
     let mut dir = Some(current_dir.as_path());
@@ -144,7 +210,7 @@ pub fn discover_root() -> Result<PathBuf, Error> {
                 })?
                 .path();
             if let Some(filename) = path.file_name() {
-                if filename.to_str() == Some(ANCHOR_TOML) {
+                if let Some(TRIDENT_TOML | ANCHOR_TOML) = filename.to_str() {
                     return Ok(PathBuf::from(cwd));
                 }
             }
@@ -0,0 +1,180 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+use crate::compute_budget::ComputeBudget;
+
+/// Kinds of transaction failures which indicate a bug within the program rather than
+/// a deliberate rejection of the transaction (e.g. Anchor constraint violation).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    /// The program panicked on an arithmetic overflow, e.g. `attempt to add with overflow`.
+    ArithmeticOverflow,
+    /// The program accessed memory outside of the allowed regions.
+    AccessViolation,
+    /// The program exceeded the compute budget of the transaction.
+    ComputeBudgetExceeded,
+    /// The program aborted for any other reason, e.g. it panicked.
+    ProgramFailedToComplete,
+}
+
+impl CrashKind {
+    /// Failures reported as crashes if not specified otherwise within the Trident Manifest.
+    pub const DEFAULT: [CrashKind; 4] = [
+        CrashKind::ArithmeticOverflow,
+        CrashKind::AccessViolation,
+        CrashKind::ComputeBudgetExceeded,
+        CrashKind::ProgramFailedToComplete,
+    ];
+}
+
+impl Display for CrashKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CrashKind::ArithmeticOverflow => "arithmetic overflow",
+            CrashKind::AccessViolation => "access violation",
+            CrashKind::ComputeBudgetExceeded => "compute budget exceeded",
+            CrashKind::ProgramFailedToComplete => "program failed to complete",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Classifies the failed transaction based on its error and the program logs. Returns `None`
+/// for failures which are not crashes, e.g. custom program errors.
+pub fn classify(error: &TransactionError, logs: &[String]) -> Option<CrashKind> {
+    let TransactionError::InstructionError(_, instruction_error) = error else {
+        return None;
+    };
+    match instruction_error {
+        InstructionError::ComputationalBudgetExceeded => Some(CrashKind::ComputeBudgetExceeded),
+        InstructionError::ProgramFailedToComplete => {
+            let logs_contain = |patterns: &[&str]| {
+                logs.iter()
+                    .any(|log| patterns.iter().any(|pattern| log.contains(pattern)))
+            };
+            if logs_contain(&["with overflow", "arithmetic operation overflow"]) {
+                Some(CrashKind::ArithmeticOverflow)
+            } else if logs_contain(&["Access violation"]) {
+                Some(CrashKind::AccessViolation)
+            } else if logs_contain(&["exceeded CUs meter", "exceeded maximum compute"]) {
+                Some(CrashKind::ComputeBudgetExceeded)
+            } else {
+                Some(CrashKind::ProgramFailedToComplete)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if the failure of the given kind is reported as a crash, i.e. it is one of the `crash_kinds`
+/// and the instruction does not expect it. Exceeding the compute unit limit requested by the instruction itself
+/// (e.g. the fuzzed limit of the [`ComputeBudget`]) is never a crash, the limit may be lower than the program needs.
+pub fn is_crash(
+    kind: CrashKind,
+    crash_kinds: &[CrashKind],
+    expected: &[CrashKind],
+    instruction_budget: Option<&ComputeBudget>,
+) -> bool {
+    let limit_requested =
+        instruction_budget.is_some_and(|budget| budget.compute_unit_limit.is_some());
+    crash_kinds.contains(&kind)
+        && !expected.contains(&kind)
+        && !(kind == CrashKind::ComputeBudgetExceeded && limit_requested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(instruction_error: InstructionError) -> TransactionError {
+        TransactionError::InstructionError(0, instruction_error)
+    }
+
+    #[test]
+    fn test_classify() {
+        let overflow_logs = vec![
+            "Program FtevoQoDMv6ZB3N9Lix5Tbjs8EVuNL8vDSqG9kzaZPit invoke [1]".to_string(),
+            "Program log: panicked at programs/hello_world/src/lib.rs:21:9:\nattempt to add with overflow".to_string(),
+        ];
+        let access_violation_logs = vec![
+            "Program FtevoQoDMv6ZB3N9Lix5Tbjs8EVuNL8vDSqG9kzaZPit failed: Access violation in stack frame 5 at address 0x200005ff8 of size 8".to_string(),
+        ];
+        let exceeded_logs = vec![
+            "Program FtevoQoDMv6ZB3N9Lix5Tbjs8EVuNL8vDSqG9kzaZPit failed: exceeded CUs meter at BPF instruction #1234".to_string(),
+        ];
+
+        assert_eq!(
+            classify(
+                &failed(InstructionError::ProgramFailedToComplete),
+                &overflow_logs
+            ),
+            Some(CrashKind::ArithmeticOverflow)
+        );
+        assert_eq!(
+            classify(
+                &failed(InstructionError::ProgramFailedToComplete),
+                &access_violation_logs
+            ),
+            Some(CrashKind::AccessViolation)
+        );
+        assert_eq!(
+            classify(
+                &failed(InstructionError::ProgramFailedToComplete),
+                &exceeded_logs
+            ),
+            Some(CrashKind::ComputeBudgetExceeded)
+        );
+        assert_eq!(
+            classify(&failed(InstructionError::ComputationalBudgetExceeded), &[]),
+            Some(CrashKind::ComputeBudgetExceeded)
+        );
+        assert_eq!(
+            classify(&failed(InstructionError::ProgramFailedToComplete), &[]),
+            Some(CrashKind::ProgramFailedToComplete)
+        );
+        assert_eq!(classify(&failed(InstructionError::Custom(2006)), &[]), None);
+        assert_eq!(classify(&TransactionError::AccountNotFound, &[]), None);
+    }
+    #[test]
+    fn test_is_crash() {
+        let kinds = &CrashKind::DEFAULT;
+        let fuzzed_limit = ComputeBudget::new(Some(0), None, None);
+        let fuzzed_price = ComputeBudget::new(None, None, Some(10));
+
+        assert!(is_crash(CrashKind::ComputeBudgetExceeded, kinds, &[], None));
+        assert!(is_crash(
+            CrashKind::ComputeBudgetExceeded,
+            kinds,
+            &[],
+            Some(&fuzzed_price)
+        ));
+        // the compute unit limit requested by the instruction may be lower than the program needs
+        assert!(!is_crash(
+            CrashKind::ComputeBudgetExceeded,
+            kinds,
+            &[],
+            Some(&fuzzed_limit)
+        ));
+        assert!(is_crash(
+            CrashKind::ArithmeticOverflow,
+            kinds,
+            &[],
+            Some(&fuzzed_limit)
+        ));
+        assert!(!is_crash(
+            CrashKind::ArithmeticOverflow,
+            kinds,
+            &[CrashKind::ArithmeticOverflow],
+            None
+        ));
+        assert!(!is_crash(
+            CrashKind::AccessViolation,
+            &[CrashKind::ArithmeticOverflow],
+            &[],
+            None
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+use solana_banks_client::BanksClientError;
+use solana_sdk::account::{AccountSharedData, ReadableAccount};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+
+use crate::error_decoder::ErrorDecoder;
+
+/// Difference between the primary and the reference execution of a transaction
+/// observed during differential fuzzing.
+#[derive(Debug)]
+pub enum Divergence {
+    /// The transaction succeeded in one of the executions and failed in the other one,
+    /// or both executions failed with a different error.
+    TransactionResult {
+        primary: Result<(), TransactionError>,
+        reference: Result<(), TransactionError>,
+    },
+    /// The state of the account differs after the transaction.
+    Account {
+        address: Pubkey,
+        primary: Option<AccountSharedData>,
+        reference: Option<AccountSharedData>,
+    },
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Divergence::TransactionResult { primary, reference } => {
+                writeln!(f, "Transaction result differs:")?;
+                writeln!(f, "  primary:   {}", ResultSummary(primary))?;
+                writeln!(f, "  reference: {}", ResultSummary(reference))
+            }
+            Divergence::Account {
+                address,
+                primary,
+                reference,
+            } => {
+                writeln!(f, "Account {} differs:", address)?;
+                writeln!(f, "  primary:   {}", AccountSummary(primary.as_ref()))?;
+                writeln!(f, "  reference: {}", AccountSummary(reference.as_ref()))
+            }
+        }
+    }
+}
+
+struct ResultSummary<'a>(&'a Result<(), TransactionError>);
+
+impl Display for ResultSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Ok(()) => write!(f, "Ok"),
+            Err(e) => write!(f, "{:?} ({})", e, ErrorDecoder::global().decode(e, None)),
+        }
+    }
+}
+
+struct AccountSummary<'a>(Option<&'a AccountSharedData>);
+
+impl Display for AccountSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(account) => write!(
+                f,
+                "lamports: {}, owner: {}, executable: {}, data ({} bytes): {:?}",
+                account.lamports(),
+                account.owner(),
+                account.executable(),
+                account.data().len(),
+                account.data()
+            ),
+            None => write!(f, "account does not exist"),
+        }
+    }
+}
+
+/// Converts the result of the transaction processing into a comparable form. Errors which are
+/// not caused by the transaction itself (e.g. IO errors of the client) are returned as they are.
+pub fn transaction_result(
+    result: &Result<(), BanksClientError>,
+) -> Result<Result<(), TransactionError>, &BanksClientError> {
+    match result {
+        Ok(()) => Ok(Ok(())),
+        Err(BanksClientError::TransactionError(e))
+        | Err(BanksClientError::SimulationError { err: e, .. }) => Ok(Err(e.clone())),
+        Err(e) => Err(e),
+    }
+}
+
+/// Compares the transaction results of the primary and the reference execution.
+pub fn compare_results(
+    primary: &Result<(), TransactionError>,
+    reference: &Result<(), TransactionError>,
+) -> Option<Divergence> {
+    if primary != reference {
+        Some(Divergence::TransactionResult {
+            primary: primary.clone(),
+            reference: reference.clone(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Compares the states of the account after the primary and the reference execution.
+pub fn compare_accounts(
+    address: &Pubkey,
+    primary: Option<AccountSharedData>,
+    reference: Option<AccountSharedData>,
+) -> Option<Divergence> {
+    let equal = match (&primary, &reference) {
+        (Some(primary), Some(reference)) => {
+            primary.lamports() == reference.lamports()
+                && primary.owner() == reference.owner()
+                && primary.executable() == reference.executable()
+                && primary.data() == reference.data()
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if equal {
+        None
+    } else {
+        Some(Divergence::Account {
+            address: *address,
+            primary,
+            reference,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::InstructionError;
+
+    #[test]
+    fn test_compare_results() {
+        let failed = Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(6000),
+        ));
+        let failed_differently = Err(TransactionError::InstructionError(
+            0,
+            InstructionError::ProgramFailedToComplete,
+        ));
+
+        assert!(compare_results(&Ok(()), &Ok(())).is_none());
+        assert!(compare_results(&failed, &failed.clone()).is_none());
+        assert!(compare_results(&Ok(()), &failed).is_some());
+        assert!(compare_results(&failed, &failed_differently).is_some());
+    }
+    #[test]
+    fn test_compare_accounts() {
+        let address = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(100, 8, &owner);
+        let same_account = account.clone();
+
+        assert!(compare_accounts(&address, None, None).is_none());
+        assert!(compare_accounts(&address, Some(account.clone()), None).is_some());
+        assert!(
+            compare_accounts(&address, Some(account.clone()), Some(same_account.clone())).is_none()
+        );
+
+        account.set_data_from_slice(&[1; 8]);
+        assert!(compare_accounts(&address, Some(account), Some(same_account)).is_some());
+    }
+}
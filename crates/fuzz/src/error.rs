@@ -2,9 +2,13 @@
 
 use solana_banks_client::BanksClientError;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
 use std::fmt::{Debug, Display};
 use thiserror::Error;
 
+use crate::differential::Divergence;
+use crate::error_decoder::{DecodedError, ErrorDecoder, InstructionProgram};
+
 #[derive(Debug, Error)]
 pub enum FuzzClientError {
     #[error("Custom fuzzing error: {0}")]
@@ -14,6 +18,10 @@ pub enum FuzzClientError {
     // Box for Error variant too Long warnings
     #[error("Banks Client Error: {0}")]
     BanksError(Box<BanksClientError>),
+    #[error("Not able to load program {0}: {1}")]
+    ProgramLoadError(String, std::io::Error),
+    #[error("Differential fuzzing detected divergence:\n{}", .0.iter().map(|d| d.to_string()).collect::<String>())]
+    Divergence(Vec<Divergence>),
 }
 
 #[derive(Debug, Error)]
@@ -45,6 +53,35 @@ impl From<BanksClientError> for FuzzClientError {
 }
 
 impl FuzzClientError {
+    /// Returns the error of the transaction if the transaction was processed and failed.
+    pub fn transaction_error(&self) -> Option<TransactionError> {
+        match self {
+            FuzzClientError::BanksError(e) => match e.as_ref() {
+                BanksClientError::TransactionError(e)
+                | BanksClientError::SimulationError { err: e, .. } => Some(e.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// Decodes the error of the failed transaction into the error name of the program.
+    pub fn decode(&self, program: Option<&InstructionProgram>) -> Option<DecodedError> {
+        self.transaction_error()
+            .map(|e| ErrorDecoder::global().decode(&e, program))
+    }
+    /// Returns the short description of the error used to group failed transactions in the statistics.
+    pub fn summary(&self, program: Option<&InstructionProgram>) -> String {
+        match self.decode(program) {
+            Some(decoded) => decoded.summary(),
+            None => match self {
+                FuzzClientError::Custom(code) => format!("Custom({code})"),
+                FuzzClientError::ClientInitError(_) => "ClientInitError".to_string(),
+                FuzzClientError::BanksError(_) => "BanksError".to_string(),
+                FuzzClientError::ProgramLoadError(..) => "ProgramLoadError".to_string(),
+                FuzzClientError::Divergence(_) => "Divergence".to_string(),
+            },
+        }
+    }
     pub fn with_origin(self, origin: Origin) -> FuzzClientErrorWithOrigin {
         let mut error_with_origin = FuzzClientErrorWithOrigin::from(self);
         error_with_origin.origin = Some(origin);
@@ -99,6 +136,8 @@ pub struct FuzzClientErrorWithOrigin {
     pub client_error: FuzzClientError,
     pub origin: Option<Origin>,
     pub context: Option<Context>,
+    /// Program of the fuzzed instruction, used to decode its custom errors
+    pub program: Option<InstructionProgram>,
 }
 
 #[derive(Debug)]
@@ -114,6 +153,7 @@ impl From<FuzzClientError> for FuzzClientErrorWithOrigin {
             client_error,
             origin: None,
             context: None,
+            program: None,
         }
     }
 }
@@ -130,6 +170,10 @@ impl From<FuzzingError> for FuzzingErrorWithOrigin {
 impl Display for FuzzClientErrorWithOrigin {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Display::fmt(&self.client_error, f)?;
+        if let Some(decoded) = self.client_error.decode(self.program.as_ref()) {
+            writeln!(f)?;
+            write!(f, "Decoded error: {}", decoded)?;
+        }
         if let Some(o) = &self.origin {
             Display::fmt(o, f)?;
         }
@@ -157,6 +201,10 @@ impl FuzzClientErrorWithOrigin {
         self.origin = Some(origin);
         self
     }
+    pub fn with_program(mut self, index: u8, program_id: Pubkey) -> Self {
+        self.program = Some(InstructionProgram { index, program_id });
+        self
+    }
     pub fn with_context(mut self, context: Context) -> Self {
         self.context = Some(context);
         self
@@ -0,0 +1,513 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path, str::FromStr, sync::OnceLock};
+
+use anchor_lang::error::ErrorCode;
+use serde::Deserialize;
+use solana_sdk::{instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError};
+
+use crate::config::{constants::IDL_DIR_DEFAULT, discover_root};
+
+/// Anchor built-in errors, i.e. instruction, constraint, account and require errors.
+const ANCHOR_ERRORS: &[ErrorCode] = &[
+    ErrorCode::InstructionMissing,
+    ErrorCode::InstructionFallbackNotFound,
+    ErrorCode::InstructionDidNotDeserialize,
+    ErrorCode::InstructionDidNotSerialize,
+    ErrorCode::IdlInstructionStub,
+    ErrorCode::IdlInstructionInvalidProgram,
+    ErrorCode::IdlAccountNotEmpty,
+    ErrorCode::EventInstructionStub,
+    ErrorCode::ConstraintMut,
+    ErrorCode::ConstraintHasOne,
+    ErrorCode::ConstraintSigner,
+    ErrorCode::ConstraintRaw,
+    ErrorCode::ConstraintOwner,
+    ErrorCode::ConstraintRentExempt,
+    ErrorCode::ConstraintSeeds,
+    ErrorCode::ConstraintExecutable,
+    ErrorCode::ConstraintState,
+    ErrorCode::ConstraintAssociated,
+    ErrorCode::ConstraintAssociatedInit,
+    ErrorCode::ConstraintClose,
+    ErrorCode::ConstraintAddress,
+    ErrorCode::ConstraintZero,
+    ErrorCode::ConstraintTokenMint,
+    ErrorCode::ConstraintTokenOwner,
+    ErrorCode::ConstraintMintMintAuthority,
+    ErrorCode::ConstraintMintFreezeAuthority,
+    ErrorCode::ConstraintMintDecimals,
+    ErrorCode::ConstraintSpace,
+    ErrorCode::ConstraintAccountIsNone,
+    ErrorCode::ConstraintTokenTokenProgram,
+    ErrorCode::ConstraintMintTokenProgram,
+    ErrorCode::ConstraintAssociatedTokenTokenProgram,
+    ErrorCode::ConstraintMintGroupPointerExtension,
+    ErrorCode::ConstraintMintGroupPointerExtensionAuthority,
+    ErrorCode::ConstraintMintGroupPointerExtensionGroupAddress,
+    ErrorCode::ConstraintMintGroupMemberPointerExtension,
+    ErrorCode::ConstraintMintGroupMemberPointerExtensionAuthority,
+    ErrorCode::ConstraintMintGroupMemberPointerExtensionMemberAddress,
+    ErrorCode::ConstraintMintMetadataPointerExtension,
+    ErrorCode::ConstraintMintMetadataPointerExtensionAuthority,
+    ErrorCode::ConstraintMintMetadataPointerExtensionMetadataAddress,
+    ErrorCode::ConstraintMintCloseAuthorityExtension,
+    ErrorCode::ConstraintMintCloseAuthorityExtensionAuthority,
+    ErrorCode::ConstraintMintPermanentDelegateExtension,
+    ErrorCode::ConstraintMintPermanentDelegateExtensionDelegate,
+    ErrorCode::ConstraintMintTransferHookExtension,
+    ErrorCode::ConstraintMintTransferHookExtensionAuthority,
+    ErrorCode::ConstraintMintTransferHookExtensionProgramId,
+    ErrorCode::RequireViolated,
+    ErrorCode::RequireEqViolated,
+    ErrorCode::RequireKeysEqViolated,
+    ErrorCode::RequireNeqViolated,
+    ErrorCode::RequireKeysNeqViolated,
+    ErrorCode::RequireGtViolated,
+    ErrorCode::RequireGteViolated,
+    ErrorCode::AccountDiscriminatorAlreadySet,
+    ErrorCode::AccountDiscriminatorNotFound,
+    ErrorCode::AccountDiscriminatorMismatch,
+    ErrorCode::AccountDidNotDeserialize,
+    ErrorCode::AccountDidNotSerialize,
+    ErrorCode::AccountNotEnoughKeys,
+    ErrorCode::AccountNotMutable,
+    ErrorCode::AccountOwnedByWrongProgram,
+    ErrorCode::InvalidProgramId,
+    ErrorCode::InvalidProgramExecutable,
+    ErrorCode::AccountNotSigner,
+    ErrorCode::AccountNotSystemOwned,
+    ErrorCode::AccountNotInitialized,
+    ErrorCode::AccountNotProgramData,
+    ErrorCode::AccountNotAssociatedTokenAccount,
+    ErrorCode::AccountSysvarMismatch,
+    ErrorCode::AccountReallocExceedsLimit,
+    ErrorCode::AccountDuplicateReallocs,
+    ErrorCode::DeclaredProgramIdMismatch,
+    ErrorCode::TryingToInitPayerAsProgramAccount,
+    ErrorCode::InvalidNumericConversion,
+    ErrorCode::Deprecated,
+];
+
+/// Name and message of a program error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub name: String,
+    pub msg: Option<String>,
+}
+
+/// Transaction error decoded into the `TransactionError` variant and the program error name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedError {
+    /// `TransactionError` variant, e.g. `InstructionError`
+    pub variant: String,
+    /// `InstructionError` variant, e.g. `Custom` or `ProgramFailedToComplete`
+    pub instruction_error: Option<String>,
+    /// Custom program error code
+    pub code: Option<u32>,
+    /// Program error decoded from the IDL or from the Anchor built-in errors
+    pub info: Option<ErrorInfo>,
+}
+
+impl DecodedError {
+    /// Returns the short form of the error used to group failed transactions, e.g.
+    /// `InstructionError: Custom(6003) InsufficientFunds`.
+    pub fn summary(&self) -> String {
+        let mut summary = self.variant.clone();
+        if let Some(instruction_error) = &self.instruction_error {
+            summary.push_str(": ");
+            summary.push_str(instruction_error);
+        }
+        if let Some(code) = self.code {
+            summary.push_str(&format!("({code})"));
+        }
+        if let Some(info) = &self.info {
+            summary.push(' ');
+            summary.push_str(&info.name);
+        }
+        summary
+    }
+}
+
+impl Display for DecodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary())?;
+        if let Some(msg) = self.info.as_ref().and_then(|info| info.msg.as_ref()) {
+            write!(f, " - {msg}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlRaw {
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    metadata: Option<IdlMetadataRaw>,
+    #[serde(default)]
+    errors: Vec<IdlErrorRaw>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlMetadataRaw {
+    // IDLs generated before Anchor 0.30 store the address within the metadata
+    #[serde(default)]
+    address: Option<String>,
+    // IDLs generated by Shank for native programs contain `"origin": "shank"`
+    #[serde(default)]
+    origin: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlErrorRaw {
+    code: u32,
+    name: String,
+    #[serde(default)]
+    msg: Option<String>,
+}
+
+/// Errors of a program loaded from its IDL.
+#[derive(Debug)]
+struct ProgramErrors {
+    /// Only Anchor programs can fail with the Anchor built-in errors
+    anchor: bool,
+    errors: HashMap<u32, ErrorInfo>,
+}
+
+/// Decodes transaction errors using the `errors` section of the program IDLs
+/// and the Anchor built-in errors.
+#[derive(Debug)]
+pub struct ErrorDecoder {
+    programs: HashMap<Pubkey, ProgramErrors>,
+    anchor_errors: HashMap<u32, ErrorInfo>,
+}
+
+impl Default for ErrorDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorDecoder {
+    pub fn new() -> Self {
+        let anchor_errors = ANCHOR_ERRORS
+            .iter()
+            .map(|error| {
+                (
+                    u32::from(*error),
+                    ErrorInfo {
+                        name: error.name(),
+                        msg: Some(error.to_string()),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            programs: HashMap::new(),
+            anchor_errors,
+        }
+    }
+
+    /// Returns the decoder with the IDLs of the workspace programs, i.e. `target/idl/*.json`.
+    /// IDLs are loaded only once.
+    pub fn global() -> &'static ErrorDecoder {
+        static DECODER: OnceLock<ErrorDecoder> = OnceLock::new();
+        DECODER.get_or_init(|| {
+            let mut decoder = Self::new();
+            if let Ok(root) = discover_root() {
+                decoder.add_idl_dir(&root.join(IDL_DIR_DEFAULT));
+            }
+            decoder
+        })
+    }
+
+    /// Adds all IDLs within the directory. Files which are not valid IDLs are skipped.
+    pub fn add_idl_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                if let Ok(idl) = fs::read_to_string(&path) {
+                    let _ = self.add_idl(&idl);
+                }
+            }
+        }
+    }
+
+    /// Adds the errors of the program from its IDL.
+    pub fn add_idl(&mut self, idl: &str) -> Result<(), serde_json::Error> {
+        let idl: IdlRaw = serde_json::from_str(idl)?;
+        let (metadata_address, origin) = idl
+            .metadata
+            .map(|metadata| (metadata.address, metadata.origin))
+            .unwrap_or_default();
+        let address = idl
+            .address
+            .or(metadata_address)
+            .and_then(|address| Pubkey::from_str(&address).ok());
+        let anchor = origin.map_or(true, |origin| origin == "anchor");
+
+        if let Some(address) = address {
+            let errors = idl
+                .errors
+                .into_iter()
+                .map(|error| {
+                    (
+                        error.code,
+                        ErrorInfo {
+                            name: error.name,
+                            msg: error.msg,
+                        },
+                    )
+                })
+                .collect();
+            self.programs
+                .insert(address, ProgramErrors { anchor, errors });
+        }
+        Ok(())
+    }
+
+    /// Returns the name and message of the custom program error. Errors of the known program take
+    /// precedence over the Anchor built-in errors, which are used only for Anchor programs. If the
+    /// program is not known, the Anchor built-in errors and then the errors of all programs are searched.
+    pub fn decode_code(&self, code: u32, program_id: Option<&Pubkey>) -> Option<&ErrorInfo> {
+        match program_id.and_then(|program_id| self.programs.get(program_id)) {
+            Some(program) => program.errors.get(&code).or_else(|| {
+                program
+                    .anchor
+                    .then(|| self.anchor_errors.get(&code))
+                    .flatten()
+            }),
+            None => self.anchor_errors.get(&code).or_else(|| {
+                let mut programs: Vec<_> = self.programs.iter().collect();
+                programs.sort_by_key(|(address, _)| **address);
+                programs
+                    .into_iter()
+                    .find_map(|(_, program)| program.errors.get(&code))
+            }),
+        }
+    }
+
+    /// Decodes the error of the transaction. If the program of the fuzzed instruction is given, only the custom
+    /// errors of that instruction are decoded, the custom errors of the other instructions (e.g. the prepended
+    /// ComputeBudget instructions) are kept as raw codes.
+    pub fn decode(
+        &self,
+        error: &TransactionError,
+        program: Option<&InstructionProgram>,
+    ) -> DecodedError {
+        match error {
+            TransactionError::InstructionError(index, instruction_error) => {
+                let (code, info) = match instruction_error {
+                    InstructionError::Custom(code) => {
+                        let info = match program {
+                            Some(program) if program.index == *index => {
+                                self.decode_code(*code, Some(&program.program_id))
+                            }
+                            Some(_) => None,
+                            None => self.decode_code(*code, None),
+                        };
+                        (Some(*code), info.cloned())
+                    }
+                    _ => (None, None),
+                };
+                DecodedError {
+                    variant: variant_name(error),
+                    instruction_error: Some(variant_name(instruction_error)),
+                    code,
+                    info,
+                }
+            }
+            _ => DecodedError {
+                variant: variant_name(error),
+                instruction_error: None,
+                code: None,
+                info: None,
+            },
+        }
+    }
+}
+
+/// Program of the instruction at the given index of the transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionProgram {
+    pub index: u8,
+    pub program_id: Pubkey,
+}
+
+/// Returns the name of the enum variant without its fields.
+fn variant_name(value: &impl std::fmt::Debug) -> String {
+    format!("{:?}", value)
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_idl_and_anchor_errors() {
+        let program_id = Pubkey::new_unique();
+        let mut decoder = ErrorDecoder::new();
+        decoder
+            .add_idl(&format!(
+                r#"{{
+                    "address": "{program_id}",
+                    "metadata": {{ "name": "hello_world", "version": "0.1.0", "spec": "0.1.0" }},
+                    "instructions": [],
+                    "errors": [{{ "code": 6003, "name": "InsufficientFunds", "msg": "Not enough funds" }}]
+                }}"#
+            ))
+            .unwrap();
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(1, InstructionError::Custom(6003)),
+            Some(&InstructionProgram {
+                index: 1,
+                program_id,
+            }),
+        );
+        assert_eq!(
+            decoded.summary(),
+            "InstructionError: Custom(6003) InsufficientFunds"
+        );
+        assert_eq!(
+            decoded.to_string(),
+            "InstructionError: Custom(6003) InsufficientFunds - Not enough funds"
+        );
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(0, InstructionError::Custom(2006)),
+            None,
+        );
+        assert_eq!(
+            decoded.summary(),
+            "InstructionError: Custom(2006) ConstraintSeeds"
+        );
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(0, InstructionError::Custom(7000)),
+            Some(&InstructionProgram {
+                index: 0,
+                program_id,
+            }),
+        );
+        assert_eq!(decoded.summary(), "InstructionError: Custom(7000)");
+    }
+    #[test]
+    fn test_decode_native_program_errors() {
+        let program_id = Pubkey::new_unique();
+        let mut decoder = ErrorDecoder::new();
+        decoder
+            .add_idl(&format!(
+                r#"{{
+                    "version": "0.1.0",
+                    "name": "native_counter",
+                    "instructions": [],
+                    "errors": [{{ "code": 2006, "name": "CounterOverflow", "msg": "Counter overflow" }}],
+                    "metadata": {{ "origin": "shank", "address": "{program_id}" }}
+                }}"#
+            ))
+            .unwrap();
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(0, InstructionError::Custom(2006)),
+            Some(&InstructionProgram {
+                index: 0,
+                program_id,
+            }),
+        );
+        assert_eq!(
+            decoded.summary(),
+            "InstructionError: Custom(2006) CounterOverflow"
+        );
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(0, InstructionError::Custom(2001)),
+            Some(&InstructionProgram {
+                index: 0,
+                program_id,
+            }),
+        );
+        assert_eq!(decoded.summary(), "InstructionError: Custom(2001)");
+    }
+    #[test]
+    fn test_decode_prepended_instruction_errors() {
+        let program_id = Pubkey::new_unique();
+        let mut decoder = ErrorDecoder::new();
+        decoder
+            .add_idl(&format!(
+                r#"{{
+                    "address": "{program_id}",
+                    "metadata": {{ "name": "hello_world", "version": "0.1.0", "spec": "0.1.0" }},
+                    "instructions": [],
+                    "errors": [{{ "code": 1, "name": "InsufficientFunds", "msg": "Not enough funds" }}]
+                }}"#
+            ))
+            .unwrap();
+        // the ComputeBudget instructions precede the fuzzed instruction
+        let program = InstructionProgram {
+            index: 2,
+            program_id,
+        };
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(0, InstructionError::Custom(1)),
+            Some(&program),
+        );
+        assert_eq!(decoded.summary(), "InstructionError: Custom(1)");
+        assert_eq!(decoded.info, None);
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(1, InstructionError::Custom(2006)),
+            Some(&program),
+        );
+        assert_eq!(decoded.summary(), "InstructionError: Custom(2006)");
+
+        let decoded = decoder.decode(
+            &TransactionError::InstructionError(2, InstructionError::Custom(1)),
+            Some(&program),
+        );
+        assert_eq!(
+            decoded.summary(),
+            "InstructionError: Custom(1) InsufficientFunds"
+        );
+    }
+    #[test]
+    fn test_decode_variants() {
+        let decoder = ErrorDecoder::new();
+
+        assert_eq!(
+            decoder
+                .decode(
+                    &TransactionError::InstructionError(
+                        0,
+                        InstructionError::ProgramFailedToComplete
+                    ),
+                    None
+                )
+                .summary(),
+            "InstructionError: ProgramFailedToComplete"
+        );
+        assert_eq!(
+            decoder
+                .decode(
+                    &TransactionError::InsufficientFundsForRent { account_index: 2 },
+                    None
+                )
+                .summary(),
+            "InsufficientFundsForRent"
+        );
+        assert_eq!(
+            decoder
+                .decode(&TransactionError::AccountNotFound, None)
+                .summary(),
+            "AccountNotFound"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use solana_sdk::account::AccountSharedData;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::sysvar::Sysvar;
-use solana_sdk::transaction::VersionedTransaction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
 
 use crate::error::*;
 
@@ -41,4 +41,20 @@ pub trait FuzzClient {
         &mut self,
         transaction: impl Into<VersionedTransaction>,
     ) -> Result<(), FuzzClientError>;
+
+    /// Send a transaction signed by the `signers` and return until the transaction has been finalized or rejected.
+    /// Clients executing the transaction within multiple environments (e.g. differential fuzzing) re-sign
+    /// the transaction with the `signers` and the recent blockhash of each environment.
+    fn process_transaction_with_signers(
+        &mut self,
+        transaction: Transaction,
+        _signers: &[&Keypair],
+    ) -> Result<(), FuzzClientError> {
+        self.process_transaction(transaction)
+    }
+
+    /// Get the program logs of the last processed transaction
+    fn get_last_transaction_logs(&self) -> &[String] {
+        &[]
+    }
 }
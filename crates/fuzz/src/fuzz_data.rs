@@ -15,6 +15,7 @@ use crate::config::Config;
 use crate::error::*;
 use crate::fuzz_client::FuzzClient;
 use crate::fuzz_test_executor::FuzzTestExecutor;
+use crate::stats_channel;
 
 pub struct FuzzData<T, U> {
     pub pre_ixs: Vec<T>,
@@ -78,6 +79,11 @@ where
             eprintln!("------ End of Instructions sequence ------ ");
         }
 
+        if stats_channel::is_enabled() {
+            let sequence_length = self.iter().count();
+            stats_channel::record(|stats| stats.increase_iterations(sequence_length));
+        }
+
         let mut sent_txs: HashMap<Hash, ()> = HashMap::new();
 
         for fuzz_ix in &mut self.iter() {
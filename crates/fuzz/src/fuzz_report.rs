@@ -0,0 +1,175 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fuzz_stats::{FuzzingStatistics, IterationStats};
+
+/// Crash file found after the fuzzing session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CrashRecord {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Machine-readable summary of a fuzzing session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzingReport {
+    /// Name of the fuzz target.
+    pub target: String,
+    /// Fuzzer used to run the session, e.g. `honggfuzz`.
+    pub fuzzer: String,
+    /// Start of the fuzzing session as a UNIX timestamp in seconds.
+    pub started_at: u64,
+    /// Duration of the fuzzing session in seconds.
+    pub duration_secs: f64,
+    /// Number of executed fuzzing iterations.
+    pub iterations: u64,
+    /// Number of iterations per length of the executed instructions sequence.
+    pub sequence_lengths: BTreeMap<usize, u64>,
+    /// Statistics of the particular instructions.
+    pub instructions: BTreeMap<String, IterationStats>,
+    /// Successful executions of the instruction (column) right after the instruction (row) within
+    /// an iteration, over all invoked instructions, so the transitions never reached are included.
+    pub transitions: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Successful executions of the short instruction sequences within an iteration.
+    pub sequences: BTreeMap<String, u64>,
+    /// Crash files within the crash directory.
+    pub crashes: Vec<CrashRecord>,
+}
+
+impl FuzzingReport {
+    pub fn new(
+        target: &str,
+        fuzzer: &str,
+        started_at: SystemTime,
+        duration: Duration,
+        stats: &FuzzingStatistics,
+        crash_files: &[PathBuf],
+    ) -> Self {
+        let crashes = crash_files
+            .iter()
+            .map(|path| CrashRecord {
+                path: path.clone(),
+                size: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            target: target.to_string(),
+            fuzzer: fuzzer.to_string(),
+            started_at: started_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            duration_secs: duration.as_secs_f64(),
+            iterations: stats.iterations,
+            sequence_lengths: stats.sequence_lengths.clone(),
+            instructions: stats
+                .instructions
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.clone()))
+                .collect(),
+            transitions: stats.transition_matrix(),
+            sequences: stats.sequences.clone(),
+            crashes,
+        }
+    }
+
+    /// Serializes the report into a pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Serializes the instruction statistics into a CSV table, one instruction per row.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("instruction,invoked,successful,failed,failed_check,failure_reasons\n");
+        for (instruction, stats) in &self.instructions {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                csv_field(instruction),
+                stats.invoked,
+                stats.successful,
+                stats.failed,
+                stats.failed_check,
+                csv_field(&stats.failure_reasons().replace('\n', "; ")),
+            ));
+        }
+        csv
+    }
+
+    /// Writes the JSON report (and optionally the CSV export) into the directory. Reports are named
+    /// after the fuzz target and the start of the session, so consecutive runs can be compared.
+    /// Returns the path to the JSON report.
+    pub fn write(&self, dir: &Path, with_csv: bool) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let name = format!("{}-{}", self.target, self.started_at);
+
+        let json_path = dir.join(format!("{name}.json"));
+        fs::write(&json_path, self.to_json())?;
+        if with_csv {
+            fs::write(dir.join(format!("{name}.csv")), self.to_csv())?;
+        }
+        Ok(json_path)
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_serialization() {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_iterations(2);
+        stats.merge(
+            serde_json::from_str(
+                r#"{"instructions":{"Deposit":{"invoked":2,"successful":1,"failed":1,"failed_check":0,"errors":{"InstructionError: Custom(6003) InsufficientFunds":1}}}}"#,
+            )
+            .unwrap(),
+        );
+
+        let report = FuzzingReport::new(
+            "fuzz_0",
+            "honggfuzz",
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            Duration::from_secs(90),
+            &stats,
+            &[PathBuf::from("does-not-exist.fuzz")],
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["target"], "fuzz_0");
+        assert_eq!(json["duration_secs"], 90.0);
+        assert_eq!(json["iterations"], 1);
+        assert_eq!(json["sequence_lengths"]["2"], 1);
+        assert_eq!(json["instructions"]["Deposit"]["failed"], 1);
+        assert_eq!(json["transitions"]["Deposit"]["Deposit"], 0);
+        assert_eq!(json["crashes"][0]["path"], "does-not-exist.fuzz");
+
+        assert_eq!(
+            report.to_csv(),
+            "instruction,invoked,successful,failed,failed_check,failure_reasons\n\
+             Deposit,2,1,1,0,1x InstructionError: Custom(6003) InsufficientFunds\n"
+        );
+    }
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
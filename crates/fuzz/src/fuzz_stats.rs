@@ -1,22 +1,79 @@
 #![allow(dead_code)]
 
-use prettytable::{row, Table};
-use std::collections::HashMap;
+use prettytable::{row, Cell, Row, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Represents fuzzing statistics, specifically tracking the number of times
 /// an instruction was invoked and successfully executed.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IterationStats {
     pub invoked: u64,
     pub successful: u64,
     pub failed: u64,
     pub failed_check: u64,
+    /// Failed transactions broken down by the decoded transaction error.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub errors: HashMap<String, u64>,
+}
+
+impl IterationStats {
+    /// Returns the errors of failed transactions ordered by their count, one error per line.
+    pub fn failure_reasons(&self) -> String {
+        let mut errors: Vec<_> = self.errors.iter().collect();
+        errors.sort_by(|(a_error, a_count), (b_error, b_count)| {
+            b_count.cmp(a_count).then(a_error.cmp(b_error))
+        });
+        errors
+            .into_iter()
+            .map(|(error, count)| format!("{count}x {error}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Length of the tracked sequences of successfully executed instructions.
+pub const TRACKED_SEQUENCE_LENGTH: usize = 3;
+
+/// Crash detected by Trident, i.e. a failed invariant check, a divergence or a program abort.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedCrash {
+    /// Name of the instruction which caused the crash.
+    pub instruction: String,
+    /// Time of the crash as a UNIX timestamp in milliseconds.
+    pub timestamp_ms: u64,
 }
 
 /// Manages and aggregates statistics for fuzzing instructions.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FuzzingStatistics {
+    #[serde(default)]
     pub instructions: HashMap<String, IterationStats>,
+    /// Number of executed fuzzing iterations.
+    #[serde(default)]
+    pub iterations: u64,
+    /// Number of iterations per length of the executed instructions sequence.
+    #[serde(default)]
+    pub sequence_lengths: BTreeMap<usize, u64>,
+    /// Number of crashes detected by Trident.
+    #[serde(default)]
+    pub crashes: u64,
+    /// The most recent crash detected by Trident.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_crash: Option<DetectedCrash>,
+    /// Number of successful executions of the instruction (inner key) right after the successful
+    /// execution of the instruction (outer key) within a single iteration.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transitions: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Number of successful executions of `TRACKED_SEQUENCE_LENGTH` instructions back-to-back within
+    /// a single iteration, e.g. `Register -> Invest -> Withdraw`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sequences: BTreeMap<String, u64>,
+    /// Instructions successfully executed back-to-back within the current iteration, at most
+    /// `TRACKED_SEQUENCE_LENGTH` of them.
+    #[serde(skip)]
+    successful_in_iteration: Vec<String>,
 }
 
 impl FuzzingStatistics {
@@ -25,14 +82,23 @@ impl FuzzingStatistics {
         let empty_instructions = HashMap::<String, IterationStats>::default();
         Self {
             instructions: empty_instructions,
+            iterations: 0,
+            sequence_lengths: BTreeMap::new(),
+            crashes: 0,
+            last_crash: None,
+            transitions: BTreeMap::new(),
+            sequences: BTreeMap::new(),
+            successful_in_iteration: vec![],
         }
     }
-    /// Outputs the statistics as a serialized JSON string.
-    pub fn output_serialized(&self) {
-        let serialized = serde_json::to_string(&self.instructions).unwrap();
-        println!("{}", serialized);
+    /// Increments the iteration count and the count of iterations with the given sequence length.
+    /// # Arguments
+    /// * `sequence_length` - The number of instructions within the executed sequence.
+    pub fn increase_iterations(&mut self, sequence_length: usize) {
+        self.successful_in_iteration.clear();
+        self.iterations += 1;
+        *self.sequence_lengths.entry(sequence_length).or_default() += 1;
     }
-
     /// Increments the invocation count for a given instruction.
     /// # Arguments
     /// * `instruction` - The instruction to increment the count for.
@@ -45,6 +111,7 @@ impl FuzzingStatistics {
                 successful: 0,
                 failed: 0,
                 failed_check: 0,
+                errors: HashMap::new(),
             });
     }
 
@@ -52,6 +119,7 @@ impl FuzzingStatistics {
     /// # Arguments
     /// * `instruction` - The instruction to increment the successful count for.
     pub fn increase_successful(&mut self, instruction: String) {
+        self.increase_transitions(&instruction);
         self.instructions
             .entry(instruction)
             .and_modify(|iterations_stats| iterations_stats.successful += 1)
@@ -63,10 +131,13 @@ impl FuzzingStatistics {
                     successful: 1,
                     failed: 0,
                     failed_check: 0,
+                    errors: HashMap::new(),
                 },
             );
     }
     pub fn increase_failed(&mut self, instruction: String) {
+        // the failed instruction breaks the sequence of successful instructions
+        self.successful_in_iteration.clear();
         self.instructions
             .entry(instruction)
             .and_modify(|iterations_stats| iterations_stats.failed += 1)
@@ -78,9 +149,97 @@ impl FuzzingStatistics {
                     successful: 0,
                     failed: 1,
                     failed_check: 0,
+                    errors: HashMap::new(),
                 },
             );
     }
+    /// Increments the failed invocation count for a given instruction together with the count
+    /// of the particular error.
+    /// # Arguments
+    /// * `instruction` - The instruction to increment the failed count for.
+    /// * `error` - The decoded error of the failed transaction.
+    pub fn increase_failed_with_error(&mut self, instruction: String, error: String) {
+        self.increase_failed(instruction.clone());
+        if let Some(iterations_stats) = self.instructions.get_mut(&instruction) {
+            *iterations_stats.errors.entry(error).or_default() += 1;
+        }
+    }
+    fn increase_transitions(&mut self, instruction: &str) {
+        if let Some(previous) = self.successful_in_iteration.last() {
+            *self
+                .transitions
+                .entry(previous.clone())
+                .or_default()
+                .entry(instruction.to_string())
+                .or_default() += 1;
+        }
+        self.successful_in_iteration.push(instruction.to_string());
+        if self.successful_in_iteration.len() > TRACKED_SEQUENCE_LENGTH {
+            self.successful_in_iteration.remove(0);
+        }
+        if self.successful_in_iteration.len() == TRACKED_SEQUENCE_LENGTH {
+            *self
+                .sequences
+                .entry(self.successful_in_iteration.join(" -> "))
+                .or_default() += 1;
+        }
+    }
+    /// Returns the transition matrix over all invoked instructions, transitions which were never
+    /// executed are included with zero count.
+    pub fn transition_matrix(&self) -> BTreeMap<String, BTreeMap<String, u64>> {
+        let instructions: BTreeSet<_> = self.instructions.keys().collect();
+        instructions
+            .iter()
+            .map(|from| {
+                let row = instructions
+                    .iter()
+                    .map(|to| {
+                        let count = self
+                            .transitions
+                            .get(*from)
+                            .and_then(|row| row.get(*to))
+                            .copied()
+                            .unwrap_or_default();
+                        (to.to_string(), count)
+                    })
+                    .collect();
+                (from.to_string(), row)
+            })
+            .collect()
+    }
+    /// Increments the crash count and remembers the instruction as the latest crash.
+    /// # Arguments
+    /// * `instruction` - The instruction which caused the crash.
+    pub fn increase_crashes(&mut self, instruction: String) {
+        self.crashes += 1;
+        self.last_crash = Some(DetectedCrash {
+            instruction,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+        });
+    }
+    /// Returns the most frequent errors of failed transactions across all instructions.
+    /// # Arguments
+    /// * `limit` - The maximal number of returned errors.
+    pub fn top_errors(&self, limit: usize) -> Vec<(String, u64)> {
+        let mut errors = BTreeMap::<&String, u64>::new();
+        for stats in self.instructions.values() {
+            for (error, count) in &stats.errors {
+                *errors.entry(error).or_default() += count;
+            }
+        }
+        let mut errors: Vec<_> = errors
+            .into_iter()
+            .map(|(error, count)| (error.clone(), count))
+            .collect();
+        errors.sort_by(|(a_error, a_count), (b_error, b_count)| {
+            b_count.cmp(a_count).then(a_error.cmp(b_error))
+        });
+        errors.truncate(limit);
+        errors
+    }
     pub fn increase_failed_check(&mut self, instruction: String) {
         self.instructions
             .entry(instruction)
@@ -93,34 +252,56 @@ impl FuzzingStatistics {
                     successful: 1,
                     failed: 0,
                     failed_check: 1,
+                    errors: HashMap::new(),
                 },
             );
     }
 
-    /// Inserts or updates instructions with statistics provided in a serialized string.
+    /// Adds the statistics collected by another fuzzing thread or process.
     /// # Arguments
-    /// * `serialized_iteration` - The serialized statistics to insert or update.
-    pub fn insert_serialized(&mut self, serialized_iteration: &str) {
-        let result = serde_json::from_str::<HashMap<String, IterationStats>>(serialized_iteration);
-
-        if let Ok(deserialized_instruction) = result {
-            for (key, value) in deserialized_instruction {
-                self.instructions
-                    .entry(key)
-                    .and_modify(|instruction_stats| {
-                        instruction_stats.invoked += value.invoked;
-                        instruction_stats.successful += value.successful;
-                        instruction_stats.failed += value.failed;
-                        instruction_stats.failed_check += value.failed_check;
-                    })
-                    .or_insert_with(|| IterationStats {
-                        invoked: value.invoked,
-                        successful: value.successful,
-                        failed: value.failed,
-                        failed_check: value.failed_check,
-                    });
+    /// * `other` - The statistics to add.
+    pub fn merge(&mut self, other: FuzzingStatistics) {
+        self.iterations += other.iterations;
+        for (sequence_length, count) in other.sequence_lengths {
+            *self.sequence_lengths.entry(sequence_length).or_default() += count;
+        }
+        self.crashes += other.crashes;
+        if let Some(crash) = other.last_crash {
+            if self
+                .last_crash
+                .as_ref()
+                .map_or(true, |last| last.timestamp_ms <= crash.timestamp_ms)
+            {
+                self.last_crash = Some(crash);
             }
         }
+        for (from, row) in other.transitions {
+            let self_row = self.transitions.entry(from).or_default();
+            for (to, count) in row {
+                *self_row.entry(to).or_default() += count;
+            }
+        }
+        for (sequence, count) in other.sequences {
+            *self.sequences.entry(sequence).or_default() += count;
+        }
+        self.merge_instructions(other.instructions);
+    }
+
+    fn merge_instructions(&mut self, instructions: HashMap<String, IterationStats>) {
+        for (key, value) in instructions {
+            self.instructions
+                .entry(key)
+                .and_modify(|instruction_stats| {
+                    instruction_stats.invoked += value.invoked;
+                    instruction_stats.successful += value.successful;
+                    instruction_stats.failed += value.failed;
+                    instruction_stats.failed_check += value.failed_check;
+                    for (error, count) in &value.errors {
+                        *instruction_stats.errors.entry(error.clone()).or_default() += count;
+                    }
+                })
+                .or_insert(value);
+        }
     }
     /// Displays the collected statistics in a formatted table.
     pub fn show_table(&self) {
@@ -130,7 +311,8 @@ impl FuzzingStatistics {
             "Invoked Total",
             "Ix Success",
             "Check Failed",
-            "Ix Failed"
+            "Ix Failed",
+            "Failure Reasons"
         ]);
         for (instruction, stats) in &self.instructions {
             table.add_row(row![
@@ -139,9 +321,134 @@ impl FuzzingStatistics {
                 stats.successful,
                 stats.failed_check,
                 stats.failed,
+                stats.failure_reasons(),
             ]);
         }
         table.printstd();
+
+        if !self.transitions.is_empty() {
+            println!(
+                "Successful transitions between instructions within an iteration (row -> column):"
+            );
+            let matrix = self.transition_matrix();
+            let mut table = Table::new();
+            let mut header = Row::new(vec![Cell::new("")]);
+            for to in matrix.keys() {
+                header.add_cell(Cell::new(to));
+            }
+            table.add_row(header);
+            for (from, row) in &matrix {
+                let mut table_row = Row::new(vec![Cell::new(from)]);
+                for count in row.values() {
+                    table_row.add_cell(Cell::new(&count.to_string()));
+                }
+                table.add_row(table_row);
+            }
+            table.printstd();
+        }
         println!("Note that unhandled panics are currently logged only as crashes and are not displayed in the table above.")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_reasons_are_aggregated() {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_invoked("Deposit".to_string());
+        stats.increase_failed_with_error(
+            "Deposit".to_string(),
+            "InstructionError: Custom(6003) InsufficientFunds".to_string(),
+        );
+
+        let mut aggregated = FuzzingStatistics::new();
+        let serialized = serde_json::to_string(&stats).unwrap();
+        aggregated.merge(serde_json::from_str(&serialized).unwrap());
+        aggregated.merge(serde_json::from_str(&serialized).unwrap());
+        aggregated.merge(serde_json::from_str(r#"{"instructions":{"Deposit":{"invoked":1,"successful":0,"failed":1,"failed_check":0,"errors":{"AccountNotFound":1}}}}"#).unwrap());
+        // statistics without the errors breakdown are accepted as well
+        aggregated.merge(
+            serde_json::from_str(
+                r#"{"instructions":{"Deposit":{"invoked":1,"successful":1,"failed":0,"failed_check":0}}}"#,
+            )
+            .unwrap(),
+        );
+
+        let mut thread_stats = FuzzingStatistics::new();
+        thread_stats.increase_iterations(3);
+        thread_stats.increase_iterations(1);
+        aggregated.merge(thread_stats.clone());
+        thread_stats.sequence_lengths.remove(&1);
+        thread_stats.iterations = 1;
+        aggregated.merge(thread_stats);
+        assert_eq!(aggregated.iterations, 3);
+        assert_eq!(
+            aggregated.sequence_lengths,
+            BTreeMap::from([(1, 1), (3, 2)])
+        );
+
+        let deposit = &aggregated.instructions["Deposit"];
+        assert_eq!(deposit.invoked, 4);
+        assert_eq!(deposit.failed, 3);
+        assert_eq!(
+            deposit.failure_reasons(),
+            "2x InstructionError: Custom(6003) InsufficientFunds\n1x AccountNotFound"
+        );
+    }
+    #[test]
+    fn test_crashes_and_top_errors() {
+        let mut first = FuzzingStatistics::new();
+        first.increase_failed_with_error("Deposit".to_string(), "AccountNotFound".to_string());
+        first.increase_failed_with_error("Withdraw".to_string(), "AccountNotFound".to_string());
+        first.increase_failed_with_error("Withdraw".to_string(), "InvalidAccountData".to_string());
+        first.increase_crashes("Deposit".to_string());
+
+        let mut second = FuzzingStatistics::new();
+        second.increase_crashes("Withdraw".to_string());
+        second.last_crash.as_mut().unwrap().timestamp_ms = u64::MAX;
+
+        let mut aggregated = FuzzingStatistics::new();
+        aggregated.merge(second);
+        aggregated.merge(first);
+        assert_eq!(aggregated.crashes, 2);
+        assert_eq!(
+            aggregated.last_crash.as_ref().unwrap().instruction,
+            "Withdraw"
+        );
+        assert_eq!(
+            aggregated.top_errors(1),
+            vec![("AccountNotFound".to_string(), 2)]
+        );
+    }
+    #[test]
+    fn test_transitions() {
+        let mut stats = FuzzingStatistics::new();
+        stats.increase_iterations(4);
+        stats.increase_successful("Register".to_string());
+        stats.increase_successful("Invest".to_string());
+        stats.increase_successful("Invest".to_string());
+        stats.increase_failed("Withdraw".to_string());
+        stats.increase_successful("Withdraw".to_string());
+        // transitions are not tracked across iterations
+        stats.increase_iterations(1);
+        stats.increase_successful("Register".to_string());
+
+        let mut aggregated = FuzzingStatistics::new();
+        aggregated.merge(serde_json::from_str(&serde_json::to_string(&stats).unwrap()).unwrap());
+        aggregated.merge(stats);
+
+        assert_eq!(aggregated.transitions["Register"]["Invest"], 2);
+        assert_eq!(aggregated.transitions["Invest"]["Invest"], 2);
+        assert!(!aggregated.transitions.contains_key("Withdraw"));
+        assert_eq!(
+            aggregated.sequences,
+            BTreeMap::from([("Register -> Invest -> Invest".to_string(), 2)])
+        );
+
+        let matrix = aggregated.transition_matrix();
+        assert_eq!(matrix["Register"]["Withdraw"], 0);
+        assert_eq!(matrix["Withdraw"].len(), 3);
+    }
+}
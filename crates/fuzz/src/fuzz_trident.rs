@@ -1,24 +1,9 @@
 #[macro_export]
 macro_rules! fuzz_trident {
     ($ix:ident: $ix_dty:ident , |$buf:ident: $dty:ident| $body:block) => {
-        if cfg!(honggfuzz) {
-            loop {
-                fuzz_honggfuzz(|$buf| {
-                    let mut $buf: FuzzData<$ix_dty, _> = {
-                        use arbitrary::Unstructured;
-
-                        let mut buf = Unstructured::new($buf);
-                        if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf) {
-                            fuzz_data
-                        } else {
-                            return;
-                        }
-                    };
-                    $body
-                });
-            }
-        } else if cfg!(afl) {
-            fuzz_afl(true, |$buf| {
+        if FUZZING_ENGINE.is_some() {
+            // the engine is selected by the `--cfg` the fuzz test is built with
+            fuzz_engine(|$buf| {
                 let mut $buf: FuzzData<$ix_dty, _> = {
                     use arbitrary::Unstructured;
 
@@ -32,6 +17,15 @@ macro_rules! fuzz_trident {
                 $body
             });
         } else {
+            $crate::replay::run_without_fuzzer(
+                |data: &[u8]| -> Option<FuzzData<$ix_dty, _>> {
+                    use arbitrary::Unstructured;
+
+                    let mut buf = Unstructured::new(data);
+                    build_ix_fuzz_data($dty {}, &mut buf).ok()
+                },
+                |mut $buf: FuzzData<$ix_dty, _>| $body,
+            );
         }
     };
 }
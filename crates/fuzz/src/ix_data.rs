@@ -0,0 +1,58 @@
+use anchor_lang::{AnchorSerialize, InstructionData};
+
+/// Serialization of the fuzzed instruction data into the bytes passed to the program.
+///
+/// The trait is implemented for every Anchor instruction (`<program>::instruction::*`), so the
+/// instruction data of Anchor programs is serialized together with its discriminator. Programs not built
+/// with Anchor (native or e.g. Pinocchio programs) use [`RawData`] instead.
+pub trait ToIxData {
+    fn to_ix_data(&self) -> Vec<u8>;
+}
+
+impl<T: InstructionData> ToIxData for T {
+    fn to_ix_data(&self) -> Vec<u8> {
+        self.data()
+    }
+}
+
+/// Instruction data passed to the program as is, e.g. of the native programs which decode
+/// the instruction data on their own.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawData(pub Vec<u8>);
+
+impl RawData {
+    /// Instruction data made of the discriminator followed by the Borsh serialized arguments,
+    /// the layout used by most of the native programs.
+    pub fn borsh(discriminator: &[u8], args: &impl AnchorSerialize) -> Self {
+        let mut data = discriminator.to_vec();
+        args.serialize(&mut data)
+            .expect("Borsh serialization of the instruction arguments");
+        Self(data)
+    }
+}
+
+impl From<Vec<u8>> for RawData {
+    fn from(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+}
+
+impl ToIxData for RawData {
+    fn to_ix_data(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_data() {
+        assert_eq!(RawData(vec![3, 1, 2]).to_ix_data(), vec![3, 1, 2]);
+        assert_eq!(
+            RawData::borsh(&[7], &(42u64, true)).to_ix_data(),
+            vec![7, 42, 0, 0, 0, 0, 0, 0, 0, 1]
+        );
+    }
+}
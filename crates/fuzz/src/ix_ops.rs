@@ -1,17 +1,19 @@
 #![allow(dead_code)]
 
+use crate::compute_budget::ComputeBudget;
+use crate::crash_classifier::CrashKind;
 use crate::error::*;
 use crate::fuzz_client::FuzzClient;
+use crate::ix_data::ToIxData;
 use crate::snapshot::SnapshotAccount;
-use anchor_lang::InstructionData;
 use solana_sdk::instruction::AccountMeta;
 use solana_sdk::signature::Keypair;
 
 /// A trait providing methods to prepare data and accounts for the fuzzed instructions and allowing
 /// users to implement custom invariants checks and transactions error handling.
 pub trait IxOps {
-    /// The data to be passed as instruction data parameter
-    type IxData: InstructionData;
+    /// The data to be passed as instruction data parameter, an Anchor instruction or [`RawData`](crate::ix_data::RawData)
+    type IxData: ToIxData;
     /// The accounts to be passed as instruction accounts
     type IxAccounts;
 
@@ -36,6 +38,24 @@ pub trait IxOps {
         fuzz_accounts: &mut Self::IxAccounts,
     ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError>;
 
+    /// Provides Compute Budget settings for the fuzzed instruction. The corresponding ComputeBudget instructions
+    /// are prepended to the transaction. Values which are not set are taken from the Trident Manifest.
+    ///
+    /// By default, only the settings from the Trident Manifest are used. To fuzz the compute unit limit, heap frame
+    /// size or priority fee, add a [`ComputeBudget`] field to the instruction input and return it from this method.
+    /// Exceeding the compute unit limit returned by this method is not reported as a crash.
+    fn get_compute_budget(&self) -> Option<ComputeBudget> {
+        None
+    }
+
+    /// Provides the allowlist of failure kinds which are expected for the fuzzed instruction. Failures of
+    /// these kinds are not reported as crashes and are passed to the `tx_error_handler` instead.
+    ///
+    /// By default, all failure kinds specified by `crash_kinds` within the Trident Manifest are crashes.
+    fn get_expected_errors(&self) -> Vec<CrashKind> {
+        vec![]
+    }
+
     /// A method to implement custom invariants checks for a given instruction. This method is called after each
     /// successfully executed instruction and by default does nothing. You can override this behavior by providing
     /// your own implementation. You can access the snapshots of account states before and after the transaction for comparison.
@@ -1,14 +1,24 @@
 pub mod accounts_storage;
+pub mod ci_report;
+pub mod compute_budget;
+pub mod crash_classifier;
+pub mod differential;
 pub mod error;
+pub mod error_decoder;
 pub mod fuzz_data;
+pub mod fuzz_report;
 pub mod fuzz_stats;
+pub mod minimize;
 pub mod program_test_client_blocking;
+pub mod replay;
 pub mod snapshot;
+pub mod stats_channel;
 pub type AccountId = u8;
 pub mod config;
 pub mod fuzz_client;
 pub mod fuzz_test_executor;
 pub mod fuzz_trident;
 pub mod instructions_sequence;
+pub mod ix_data;
 pub mod ix_ops;
 pub mod transaction_executor;
@@ -0,0 +1,81 @@
+/// Shrinks the crash input while it still reproduces the same failure. Byte ranges are removed first,
+/// removal of the range preceded by the continuation byte of a sequence drops whole instructions, then the
+/// remaining bytes are simplified to zeros, which results in the default values of the data fields.
+///
+/// The `reproduces` closure returns the number of instructions of the decoded candidate if it reproduces
+/// the failure. Candidates with more instructions than the current best input are rejected.
+pub fn minimize(data: &[u8], mut reproduces: impl FnMut(&[u8]) -> Option<usize>) -> Vec<u8> {
+    let mut best = data.to_vec();
+    let Some(mut best_instructions) = reproduces(&best) else {
+        return best;
+    };
+    let mut accept = |candidate: Vec<u8>, best: &mut Vec<u8>, best_instructions: &mut usize| {
+        let instructions = reproduces(&candidate);
+        match instructions {
+            Some(instructions) if instructions <= *best_instructions => {
+                *best = candidate;
+                *best_instructions = instructions;
+                true
+            }
+            _ => false,
+        }
+    };
+
+    // remove the byte ranges
+    let mut chunk = best.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < best.len() {
+            let end = (start + chunk).min(best.len());
+            let candidate = [&best[..start], &best[end..]].concat();
+            if !accept(candidate, &mut best, &mut best_instructions) {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    // simplify the remaining bytes
+    let mut chunk = best.len().max(1);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < best.len() {
+            let end = (start + chunk).min(best.len());
+            if best[start..end].iter().any(|byte| *byte != 0) {
+                let mut candidate = best.clone();
+                candidate[start..end].fill(0);
+                accept(candidate, &mut best, &mut best_instructions);
+            }
+            start += chunk;
+        }
+        chunk /= 2;
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize() {
+        // the failure reproduces if the input contains the byte 7 followed (not necessarily directly) by the byte 9,
+        // every byte is one instruction
+        let reproduces = |data: &[u8]| {
+            let seven = data.iter().position(|byte| *byte == 7)?;
+            data[seven..]
+                .iter()
+                .any(|byte| *byte == 9)
+                .then_some(data.len())
+        };
+        let data = [1, 2, 7, 3, 4, 5, 9, 6, 8, 10];
+        assert_eq!(minimize(&data, reproduces), vec![7, 9]);
+
+        // zeros are preferred, the failure reproduces if the second byte is nonzero
+        let reproduces = |data: &[u8]| (data.len() == 3 && data[1] != 0).then_some(1);
+        assert_eq!(minimize(&[5, 6, 7], reproduces), vec![0, 6, 0]);
+
+        // inputs which do not reproduce the failure are returned unchanged
+        assert_eq!(minimize(&[1, 2], |_| None), vec![1, 2]);
+    }
+}
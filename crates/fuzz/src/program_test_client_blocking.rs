@@ -1,18 +1,31 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use solana_banks_client::{BanksClient, BanksClientError};
 use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
 use solana_program_test::ProgramTest;
 use solana_program_test::ProgramTestContext;
-use solana_sdk::account::Account;
+use solana_sdk::account::{Account, WritableAccount};
 use solana_sdk::account_info::AccountInfo;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
 use solana_sdk::clock::Clock;
+use solana_sdk::commitment_config::CommitmentLevel;
 use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::signer::Signer;
 use solana_sdk::sysvar::Sysvar;
 use solana_sdk::{
-    account::AccountSharedData, hash::Hash, pubkey::Pubkey, rent::Rent, signature::Keypair,
-    transaction::VersionedTransaction,
+    account::AccountSharedData,
+    hash::Hash,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    transaction::{Transaction, VersionedTransaction},
 };
 use tokio::runtime::Builder;
 
+use crate::config::fuzz::{sbf_program_path, ExecutionMode, FuzzProgram};
 use crate::config::Config;
+use crate::differential;
 use crate::error::*;
 use crate::fuzz_client::FuzzClient;
 
@@ -22,9 +35,15 @@ pub type ProgramEntry = for<'info> fn(
     instruction_data: &[u8],
 ) -> ProgramResult;
 
+/// Returns `true` for divergences which are intended and should not be reported.
+pub type DivergenceFilter = dyn Fn(&differential::Divergence) -> bool;
+
 pub struct ProgramTestClientBlocking {
     ctx: ProgramTestContext,
     rt: tokio::runtime::Runtime,
+    reference_ctx: Option<ProgramTestContext>,
+    divergence_filter: Option<Box<DivergenceFilter>>,
+    last_logs: Vec<String>,
 }
 
 pub struct FuzzingProgram {
@@ -44,45 +63,367 @@ impl FuzzingProgram {
             entry: entry_fn,
         }
     }
+
+    /// Program without the native entry (e.g. a Pinocchio program), it is loaded from its compiled
+    /// SBF binary within `target/deploy` in every execution mode.
+    pub fn sbf(program_name: &str, program_id: &Pubkey) -> FuzzingProgram {
+        Self::new(program_name, program_id, None)
+    }
 }
 
 impl ProgramTestClientBlocking {
     pub fn new(program_: &[FuzzingProgram], config: &Config) -> Result<Self, FuzzClientError> {
-        let mut program_test = ProgramTest::default();
+        match config.get_execution_mode() {
+            ExecutionMode::Native => {
+                let program_test = Self::native_program_test(program_, config)?;
+                Self::start(program_test, None)
+            }
+            ExecutionMode::Sbf => {
+                let program_test = Self::sbf_program_test(program_, config)?;
+                Self::start(program_test, None)
+            }
+            ExecutionMode::Differential => Self::new_differential(program_, &[], config),
+        }
+    }
+
+    /// Creates the client for differential fuzzing. Every transaction is executed within the primary
+    /// environment containing the programs under test and within the reference environment.
+    ///
+    /// The reference environment contains the `reference_programs` (e.g. the previous release of the program
+    /// added as a renamed dependency) and the reference programs from the Trident Manifest, which replace the
+    /// programs under test with the same program ID. If no reference program is specified, the reference
+    /// environment contains the SBF build of the programs under test.
+    pub fn new_differential(
+        program_: &[FuzzingProgram],
+        reference_programs: &[FuzzingProgram],
+        config: &Config,
+    ) -> Result<Self, FuzzClientError> {
+        let program_test = match config.get_execution_mode() {
+            ExecutionMode::Sbf => Self::sbf_program_test(program_, config)?,
+            ExecutionMode::Native | ExecutionMode::Differential => {
+                Self::native_program_test(program_, config)?
+            }
+        };
+
+        let reference_program_test =
+            if reference_programs.is_empty() && config.fuzz.reference_programs.is_empty() {
+                Self::sbf_program_test(program_, config)?
+            } else {
+                Self::reference_program_test(program_, reference_programs, config)?
+            };
+
+        Self::start(program_test, Some(reference_program_test))
+    }
+
+    /// Sets the filter of intended divergences. Divergences for which the filter returns `true`
+    /// are expected (e.g. intended changes of the upgraded program) and are not reported.
+    pub fn with_divergence_filter(
+        mut self,
+        filter: impl Fn(&differential::Divergence) -> bool + 'static,
+    ) -> Self {
+        self.divergence_filter = Some(Box::new(filter));
+        self
+    }
+
+    fn start(
+        program_test: ProgramTest,
+        reference_program_test: Option<ProgramTest>,
+    ) -> Result<Self, FuzzClientError> {
+        let rt: tokio::runtime::Runtime = Builder::new_current_thread().enable_all().build()?;
+
+        let ctx = rt.block_on(program_test.start_with_context());
+        let reference_ctx = reference_program_test
+            .map(|reference_program_test| rt.block_on(reference_program_test.start_with_context()));
+
+        let mut client = Self {
+            ctx,
+            rt,
+            reference_ctx,
+            divergence_filter: None,
+            last_logs: vec![],
+        };
+        client.fund_payer_in_reference();
+        Ok(client)
+    }
+
+    /// Creates the fuzzing environment with the programs registered as native builtins, programs
+    /// without the native entry are loaded from their compiled SBF binaries.
+    fn native_program_test(
+        program_: &[FuzzingProgram],
+        config: &Config,
+    ) -> Result<ProgramTest, FuzzClientError> {
+        let mut program_test = Self::program_test_with_genesis(config)?;
         for x in program_ {
+            match x.entry {
+                Some(entry) => {
+                    program_test.add_builtin_program(&x.program_name, x.program_id, entry)
+                }
+                None => {
+                    let data = read_sbf_program(&x.program_name)?;
+                    add_sbf_program(&mut program_test, x.program_id, data);
+                }
+            }
+        }
+        Ok(program_test)
+    }
+
+    /// Creates the fuzzing environment with the programs loaded from their compiled SBF binaries.
+    fn sbf_program_test(
+        program_: &[FuzzingProgram],
+        config: &Config,
+    ) -> Result<ProgramTest, FuzzClientError> {
+        let mut program_test = Self::program_test_with_genesis(config)?;
+        for x in program_ {
+            let data = read_sbf_program(&x.program_name)?;
+            add_sbf_program(&mut program_test, x.program_id, data);
+        }
+        Ok(program_test)
+    }
+
+    /// Creates the reference fuzzing environment for differential fuzzing. Programs under test without
+    /// the reference version are loaded the same way as within the primary environment.
+    fn reference_program_test(
+        program_: &[FuzzingProgram],
+        reference_programs: &[FuzzingProgram],
+        config: &Config,
+    ) -> Result<ProgramTest, FuzzClientError> {
+        let has_reference = |program_id: &Pubkey| {
+            reference_programs
+                .iter()
+                .any(|reference| reference.program_id == *program_id)
+                || config
+                    .fuzz
+                    .reference_programs
+                    .iter()
+                    .any(|reference| reference.address == *program_id)
+        };
+        let programs_without_reference = program_
+            .iter()
+            .filter(|x| !has_reference(&x.program_id))
+            .map(|x| FuzzingProgram::new(&x.program_name, &x.program_id, x.entry))
+            .collect::<Vec<_>>();
+
+        let mut program_test = match config.get_execution_mode() {
+            ExecutionMode::Sbf => Self::sbf_program_test(&programs_without_reference, config)?,
+            ExecutionMode::Native | ExecutionMode::Differential => {
+                Self::native_program_test(&programs_without_reference, config)?
+            }
+        };
+
+        for x in reference_programs {
             if let Some(entry) = x.entry {
                 program_test.add_builtin_program(&x.program_name, x.program_id, entry);
             }
         }
-        for account in config.fuzz.accounts.iter() {
-            program_test.add_account_with_base64_data(
-                account.pubkey,
-                account.account.lamports,
-                account.account.owner,
-                &account.account.data,
-            )
+        for program in config.fuzz.reference_programs.iter() {
+            add_program(&mut program_test, program);
+        }
+        Ok(program_test)
+    }
+
+    /// Creates the fuzzing environment with the genesis accounts and programs from the Trident Manifest.
+    fn program_test_with_genesis(config: &Config) -> Result<ProgramTest, FuzzClientError> {
+        let mut program_test = ProgramTest::default();
+        for account in config.fuzz.get_accounts() {
+            program_test.add_account(account.pubkey, account.account.clone());
         }
 
         for program in config.fuzz.programs.iter() {
-            program_test.add_account(
-                program.address,
-                Account {
-                    lamports: Rent::default().minimum_balance(program.data.len()).max(1),
-                    data: program.data.clone(),
-                    owner: solana_sdk::bpf_loader::id(),
-                    executable: true,
-                    rent_epoch: 0,
-                },
-            );
+            add_program(&mut program_test, program);
         }
+        Ok(program_test)
+    }
 
-        let rt: tokio::runtime::Runtime = Builder::new_current_thread().enable_all().build()?;
+    /// Transactions are always paid by the payer of the primary environment, so the payer has to exist
+    /// with the same balance also within the reference environment.
+    fn fund_payer_in_reference(&mut self) {
+        if self.reference_ctx.is_some() {
+            let payer = self.ctx.payer.pubkey();
+            let payer_account = self.get_account(&payer);
+            if let Some(reference_ctx) = &mut self.reference_ctx {
+                reference_ctx.set_account(&payer, &payer_account);
+            }
+        }
+    }
 
-        let ctx = rt.block_on(program_test.start_with_context());
-        Ok(Self { ctx, rt })
+    /// Executes the transaction within the reference environment and compares the outcome and the states
+    /// of all writable accounts with the primary environment.
+    fn process_transaction_differential(
+        &mut self,
+        transaction: Transaction,
+        signers: &[&Keypair],
+    ) -> Result<(), FuzzClientError> {
+        let Some(reference_ctx) = &mut self.reference_ctx else {
+            return self.process_transaction(transaction);
+        };
+
+        let mut reference_transaction = Transaction::new_unsigned(transaction.message.clone());
+        reference_transaction.sign(signers, reference_ctx.last_blockhash);
+
+        let writable_accounts: Vec<Pubkey> = transaction
+            .message
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| transaction.message.is_writable(*index))
+            .map(|(_, address)| *address)
+            .collect();
+
+        let (primary_result, logs) =
+            process_transaction_with_logs(&self.rt, &mut self.ctx.banks_client, transaction);
+        self.last_logs = logs;
+        let (reference_result, _) = process_transaction_with_logs(
+            &self.rt,
+            &mut reference_ctx.banks_client,
+            reference_transaction,
+        );
+
+        let primary = differential::transaction_result(&primary_result);
+        let reference = differential::transaction_result(&reference_result);
+
+        if let (Ok(primary), Ok(reference)) = (primary, reference) {
+            let mut divergences = vec![];
+            divergences.extend(differential::compare_results(&primary, &reference));
+
+            for address in writable_accounts.iter() {
+                let primary_account = self.rt.block_on(
+                    self.ctx
+                        .banks_client
+                        .get_account_with_commitment(*address, CommitmentLevel::Confirmed),
+                )?;
+                let reference_account = self.rt.block_on(
+                    reference_ctx
+                        .banks_client
+                        .get_account_with_commitment(*address, CommitmentLevel::Confirmed),
+                )?;
+                divergences.extend(differential::compare_accounts(
+                    address,
+                    primary_account.map(Into::into),
+                    reference_account.map(Into::into),
+                ));
+            }
+
+            if let Some(filter) = &self.divergence_filter {
+                divergences.retain(|divergence| !filter(divergence));
+            }
+
+            if !divergences.is_empty() {
+                return Err(FuzzClientError::Divergence(divergences));
+            }
+        }
+        Ok(primary_result?)
+    }
+}
+
+/// Processes the transaction and returns its result together with the program logs.
+fn process_transaction_with_logs(
+    rt: &tokio::runtime::Runtime,
+    banks_client: &mut BanksClient,
+    transaction: impl Into<VersionedTransaction>,
+) -> (Result<(), BanksClientError>, Vec<String>) {
+    match rt.block_on(banks_client.process_transaction_with_metadata(transaction)) {
+        Ok(result_with_metadata) => (
+            result_with_metadata.result.map_err(BanksClientError::from),
+            result_with_metadata
+                .metadata
+                .map(|metadata| metadata.log_messages)
+                .unwrap_or_default(),
+        ),
+        Err(e) => (Err(e), vec![]),
+    }
+}
+
+/// Reads the compiled SBF binary of the program. The binary is read from the disk only once
+/// and cached for the rest of the fuzzing session, as the client is created for every iteration.
+fn read_sbf_program(program_name: &str) -> Result<Vec<u8>, FuzzClientError> {
+    static SBF_PROGRAMS: OnceLock<Mutex<HashMap<String, Vec<u8>>>> = OnceLock::new();
+
+    let mut programs = SBF_PROGRAMS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(data) = programs.get(program_name) {
+        return Ok(data.clone());
+    }
+
+    let path = sbf_program_path(program_name);
+    let data = std::fs::read(&path)
+        .map_err(|e| FuzzClientError::ProgramLoadError(path.display().to_string(), e))?;
+    programs.insert(program_name.to_string(), data.clone());
+    Ok(data)
+}
+
+/// Adds the program from the Trident Manifest, either through the BPF loader or the upgradeable BPF loader.
+fn add_program(program_test: &mut ProgramTest, program: &FuzzProgram) {
+    if program.upgradeable {
+        for (address, account) in
+            upgradeable_program_accounts(program.address, program.upgrade_authority, &program.data)
+        {
+            program_test.add_account(address, account);
+        }
+    } else {
+        add_sbf_program(program_test, program.address, program.data.clone());
     }
 }
 
+/// Creates the program account and the ProgramData account of a program deployed through
+/// the upgradeable BPF loader.
+fn upgradeable_program_accounts(
+    address: Pubkey,
+    upgrade_authority: Option<Pubkey>,
+    data: &[u8],
+) -> [(Pubkey, Account); 2] {
+    let rent = Rent::default();
+    let (programdata_address, _) =
+        Pubkey::find_program_address(&[address.as_ref()], &bpf_loader_upgradeable::id());
+
+    let program_state = UpgradeableLoaderState::Program {
+        programdata_address,
+    };
+    let program_account = AccountSharedData::new_data(
+        rent.minimum_balance(UpgradeableLoaderState::size_of_program()),
+        &program_state,
+        &bpf_loader_upgradeable::id(),
+    )
+    .expect("Failed to serialize the program account");
+
+    let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+    let programdata_state = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: upgrade_authority,
+    };
+    let mut programdata_account = AccountSharedData::new_data_with_space(
+        rent.minimum_balance(metadata_len + data.len()),
+        &programdata_state,
+        metadata_len + data.len(),
+        &bpf_loader_upgradeable::id(),
+    )
+    .expect("Failed to serialize the ProgramData account");
+    programdata_account.data_as_mut_slice()[metadata_len..].copy_from_slice(data);
+
+    let mut program_account: Account = program_account.into();
+    program_account.executable = true;
+
+    [
+        (address, program_account),
+        (programdata_address, programdata_account.into()),
+    ]
+}
+
+/// Adds the program as an executable account owned by the BPF loader.
+fn add_sbf_program(program_test: &mut ProgramTest, address: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()).max(1),
+            data,
+            owner: solana_sdk::bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+}
+
 /// Converts Anchor 0.29.0 and higher entrypoint into the runtime's entrypoint style
 ///
 /// Starting Anchor 0.29.0 the accounts are passed by reference https://github.com/coral-xyz/anchor/pull/2656
@@ -111,16 +452,17 @@ impl FuzzClient for ProgramTestClientBlocking {
     fn get_account(&mut self, key: &Pubkey) -> AccountSharedData {
         let account = self
             .rt
-            .block_on(self.ctx.banks_client.get_account_with_commitment(
-                *key,
-                solana_sdk::commitment_config::CommitmentLevel::Confirmed,
-            ))
+            .block_on(
+                self.ctx
+                    .banks_client
+                    .get_account_with_commitment(*key, CommitmentLevel::Confirmed),
+            )
             .unwrap_or_default();
         match account {
             Some(account) => account.into(),
             None => {
                 let account = AccountSharedData::new(0, 0, &solana_sdk::system_program::ID);
-                self.ctx.set_account(key, &account);
+                self.set_account_custom(key, &account);
                 account
             }
         }
@@ -132,13 +474,29 @@ impl FuzzClient for ProgramTestClientBlocking {
         &mut self,
         transaction: impl Into<VersionedTransaction>,
     ) -> Result<(), FuzzClientError> {
-        Ok(self
-            .rt
-            .block_on(self.ctx.banks_client.process_transaction(transaction))?)
+        let (result, logs) =
+            process_transaction_with_logs(&self.rt, &mut self.ctx.banks_client, transaction);
+        self.last_logs = logs;
+        Ok(result?)
+    }
+
+    fn get_last_transaction_logs(&self) -> &[String] {
+        &self.last_logs
+    }
+
+    fn process_transaction_with_signers(
+        &mut self,
+        transaction: Transaction,
+        signers: &[&Keypair],
+    ) -> Result<(), FuzzClientError> {
+        self.process_transaction_differential(transaction, signers)
     }
 
     fn set_account_custom(&mut self, address: &Pubkey, account: &AccountSharedData) {
         self.ctx.set_account(address, account);
+        if let Some(reference_ctx) = &mut self.reference_ctx {
+            reference_ctx.set_account(address, account);
+        }
     }
 
     fn forward_in_time(&mut self, seconds: i64) -> Result<(), FuzzClientError> {
@@ -155,13 +513,22 @@ impl FuzzClient for ProgramTestClientBlocking {
 
         // Update the sysvar in the program test context with the new Clock state.
         self.ctx.set_sysvar(&clock);
+        if let Some(reference_ctx) = &self.reference_ctx {
+            reference_ctx.set_sysvar(&clock);
+        }
         Ok(())
     }
     fn warp_to_slot(&mut self, warp_slot: u64) {
         let _ = self.ctx.warp_to_slot(warp_slot);
+        if let Some(reference_ctx) = &mut self.reference_ctx {
+            let _ = reference_ctx.warp_to_slot(warp_slot);
+        }
     }
     fn warp_to_epoch(&mut self, warp_epoch: u64) {
         let _ = self.ctx.warp_to_epoch(warp_epoch);
+        if let Some(reference_ctx) = &mut self.reference_ctx {
+            let _ = reference_ctx.warp_to_epoch(warp_epoch);
+        }
     }
     fn get_sysvar<T: Sysvar>(&mut self) -> T {
         self.rt
@@ -169,3 +536,43 @@ impl FuzzClient for ProgramTestClientBlocking {
             .unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgradeable_program_accounts() {
+        let address = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let elf = vec![0x7f, b'E', b'L', b'F', 1, 2, 3];
+
+        let [(program_address, program), (programdata_address, programdata)] =
+            upgradeable_program_accounts(address, Some(authority), &elf);
+
+        assert_eq!(program_address, address);
+        assert!(program.executable);
+        assert_eq!(program.owner, bpf_loader_upgradeable::id());
+        assert_eq!(
+            program
+                .deserialize_data::<UpgradeableLoaderState>()
+                .unwrap(),
+            UpgradeableLoaderState::Program {
+                programdata_address
+            }
+        );
+
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        assert!(!programdata.executable);
+        assert_eq!(
+            programdata
+                .deserialize_data::<UpgradeableLoaderState>()
+                .unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(authority)
+            }
+        );
+        assert_eq!(&programdata.data[metadata_len..], elf.as_slice());
+    }
+}
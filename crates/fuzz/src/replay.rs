@@ -0,0 +1,663 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Once,
+};
+
+use prettytable::{row, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::{fuzz_data::FuzzData, minimize::minimize, stats_channel};
+
+/// Environment variable with the corpus and crash files (or directories of them) replayed by the fuzz
+/// test built without a fuzzer, separated the same way as the `PATH` variable.
+pub const REPLAY_INPUTS_ENV: &str = "TRIDENT_REPLAY_INPUTS";
+
+/// Environment variable with the path the minimized input is written into. If set, the single input from
+/// `TRIDENT_REPLAY_INPUTS` is minimized instead of replayed.
+pub const MINIMIZE_OUTPUT_ENV: &str = "TRIDENT_MINIMIZE_OUTPUT";
+
+/// Environment variable which, if set, makes the fuzz test only decode the inputs from
+/// `TRIDENT_REPLAY_INPUTS` and print their instruction sequences without executing them.
+/// The sequences are printed as JSON if the value is `json`, as plain text otherwise.
+pub const DECODE_ENV: &str = "TRIDENT_DECODE";
+
+/// Environment variable with the path the results of the replayed inputs are written into (as JSON).
+pub const REPLAY_RESULTS_ENV: &str = "TRIDENT_REPLAY_RESULTS";
+
+/// Error of the crash caused by a failed custom invariant check of the instruction.
+pub const CUSTOM_CHECK_FAILED: &str = "custom check failed";
+
+/// Error of the crash caused by a divergence found during differential fuzzing.
+pub const DIVERGENCE: &str = "divergence";
+
+/// Environment variable which, if set, makes the fuzz test replay the crash inputs from
+/// `TRIDENT_REPLAY_INPUTS` and group them into buckets of the same failure.
+pub const TRIAGE_ENV: &str = "TRIDENT_TRIAGE";
+
+static PANIC_LOCATION_HOOK: Once = Once::new();
+
+thread_local! {
+    static LAST_CRASH: RefCell<Option<CrashSignature>> = const { RefCell::new(None) };
+    static LAST_PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Identifies the failure of the crash, i.e. the failed instruction, the kind of the failure
+/// and the source location of the panic.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrashSignature {
+    /// Name of the failed instruction, unknown for panics outside of the Trident checks.
+    pub instruction: Option<String>,
+    pub error: String,
+    /// Details of the error, e.g. the `FuzzingError` returned by the failed invariant check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Source location (`file:line:column`) of the panic.
+    pub location: Option<String>,
+}
+
+impl Display for CrashSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+        if let Some(instruction) = &self.instruction {
+            write!(f, " in {instruction}")?;
+        }
+        match &self.location {
+            Some(location) => write!(f, " at {location}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Result of the replayed input, written into the `TRIDENT_REPLAY_RESULTS` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayResult {
+    pub file: PathBuf,
+    /// Size of the input in bytes.
+    pub size: usize,
+    /// Signature of the crash, `None` if the input passed.
+    pub crash: Option<CrashSignature>,
+}
+
+/// Crash inputs failing with the same signature, sorted from the smallest input, which is
+/// the representative of the bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashBucket {
+    pub signature: CrashSignature,
+    pub files: Vec<PathBuf>,
+}
+
+impl CrashBucket {
+    pub fn representative(&self) -> &Path {
+        &self.files[0]
+    }
+}
+
+/// Result of a single replayed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayOutcome {
+    /// The input was executed without a panic.
+    Passed,
+    /// The execution of the input panicked, i.e. the input reproduces a crash.
+    Crashed(CrashSignature),
+}
+
+/// Remembers the crash detected by Trident right before the panic, so the replay can tell
+/// which instruction failed and how.
+pub fn record_crash(instruction: &str, error: impl Display) {
+    record(instruction, error, None);
+}
+
+/// Remembers the failed invariant check right before the panic. The message of the `FuzzingError`
+/// is a part of the signature, so different invariant violations of the same instruction are
+/// distinguished.
+pub fn record_check_failure(instruction: &str, error: impl Display) {
+    let message = error.to_string().trim_end().to_string();
+    record(instruction, CUSTOM_CHECK_FAILED, Some(message));
+}
+
+fn record(instruction: &str, error: impl Display, message: Option<String>) {
+    LAST_CRASH.with(|last_crash| {
+        *last_crash.borrow_mut() = Some(CrashSignature {
+            instruction: Some(instruction.to_string()),
+            error: error.to_string(),
+            message,
+            location: None,
+        })
+    });
+}
+
+/// Returns the files of the inputs, directories are expanded into the files they contain (not recursively).
+/// Hidden files are skipped, the files are sorted by their path.
+pub fn input_files(inputs: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for input in inputs {
+        if input.is_dir() {
+            let mut dir_files = fs::read_dir(input)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && !is_hidden(path))
+                .collect::<Vec<_>>();
+            dir_files.sort();
+            files.extend(dir_files);
+        } else if input.is_file() {
+            files.push(input.clone());
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("input not found: {}", input.display()),
+            ));
+        }
+    }
+    Ok(files)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| name.starts_with('.'))
+}
+
+/// Executes the fuzz iteration, panics of the iteration are caught so the following inputs
+/// can be replayed as well.
+pub fn catch_crash(run: impl FnOnce()) -> ReplayOutcome {
+    // the location of the panic is only available in the panic hook, the current hook is still called
+    PANIC_LOCATION_HOOK.call_once(|| {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|location| location.to_string());
+            LAST_PANIC_LOCATION.with(|last_location| *last_location.borrow_mut() = location);
+            hook(info)
+        }));
+    });
+    LAST_CRASH.with(|last_crash| last_crash.borrow_mut().take());
+    LAST_PANIC_LOCATION.with(|last_location| last_location.borrow_mut().take());
+    match catch_unwind(AssertUnwindSafe(run)) {
+        Ok(_) => ReplayOutcome::Passed,
+        Err(payload) => {
+            let mut signature = LAST_CRASH
+                .with(|last_crash| last_crash.borrow_mut().take())
+                .unwrap_or_else(|| {
+                    let message = payload
+                        .downcast_ref::<String>()
+                        .cloned()
+                        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                        .unwrap_or_default();
+                    CrashSignature {
+                        instruction: None,
+                        error: message.lines().next().unwrap_or_default().to_string(),
+                        message: None,
+                        location: None,
+                    }
+                });
+            signature.location =
+                LAST_PANIC_LOCATION.with(|last_location| last_location.borrow_mut().take());
+            ReplayOutcome::Crashed(signature)
+        }
+    }
+}
+
+/// Groups the crash inputs (with their sizes) into buckets by their signatures. The buckets are sorted
+/// by the number of the inputs, the most frequent first.
+pub fn triage(crashes: Vec<(PathBuf, usize, CrashSignature)>) -> Vec<CrashBucket> {
+    let mut buckets: BTreeMap<CrashSignature, Vec<(usize, PathBuf)>> = BTreeMap::new();
+    for (file, size, signature) in crashes {
+        buckets.entry(signature).or_default().push((size, file));
+    }
+    let mut buckets: Vec<CrashBucket> = buckets
+        .into_iter()
+        .map(|(signature, mut files)| {
+            files.sort();
+            CrashBucket {
+                signature,
+                files: files.into_iter().map(|(_, file)| file).collect(),
+            }
+        })
+        .collect();
+    buckets.sort_by(|a, b| b.files.len().cmp(&a.files.len()));
+    buckets
+}
+
+/// Instruction sequence decoded from the input file, the instructions are formatted by their `Display`
+/// implementation (derived by `DisplayIx`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedInput {
+    pub file: PathBuf,
+    /// Set if the input cannot be decoded, i.e. the fuzzer would skip it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub pre_ixs: Vec<String>,
+    pub ixs: Vec<String>,
+    pub post_ixs: Vec<String>,
+}
+
+impl DecodedInput {
+    pub fn new<T: Display, U>(file: &Path, fuzz_data: Option<&FuzzData<T, U>>) -> Self {
+        let format = |ixs: &[T]| ixs.iter().map(|ix| ix.to_string()).collect();
+        match fuzz_data {
+            Some(fuzz_data) => Self {
+                file: file.to_path_buf(),
+                error: None,
+                pre_ixs: format(&fuzz_data.pre_ixs),
+                ixs: format(&fuzz_data.ixs),
+                post_ixs: format(&fuzz_data.post_ixs),
+            },
+            None => Self {
+                file: file.to_path_buf(),
+                error: Some("the input cannot be decoded".to_string()),
+                pre_ixs: vec![],
+                ixs: vec![],
+                post_ixs: vec![],
+            },
+        }
+    }
+}
+
+/// Formats the instructions of the fuzz data, one instruction per line.
+pub fn format_sequence<T: Display, U>(fuzz_data: &FuzzData<T, U>) -> String {
+    let mut output = String::new();
+    for (name, ixs) in [
+        ("pre_ixs", &fuzz_data.pre_ixs),
+        ("ixs", &fuzz_data.ixs),
+        ("post_ixs", &fuzz_data.post_ixs),
+    ] {
+        output.push_str(&format!("{name}:\n"));
+        for (index, ix) in ixs.iter().enumerate() {
+            // the instruction data are pretty-printed on multiple lines
+            let ix = ix.to_string().replace('\n', "\n    ");
+            output.push_str(&format!("  {index}: {ix}\n"));
+        }
+    }
+    output
+}
+
+/// This is how the fuzz test behaves if it is built without a fuzzer (e.g. by the `trident fuzz coverage`
+/// command). The inputs from the `TRIDENT_REPLAY_INPUTS` environment variable are replayed, minimized
+/// if `TRIDENT_MINIMIZE_OUTPUT` is set, or only decoded if `TRIDENT_DECODE` is set. The replay exits
+/// with the code 1 if any of the inputs crashed.
+pub fn run_without_fuzzer<T: Display, U>(
+    decode: impl Fn(&[u8]) -> Option<FuzzData<T, U>>,
+    mut run: impl FnMut(FuzzData<T, U>),
+) {
+    let Some(inputs) = std::env::var_os(REPLAY_INPUTS_ENV) else {
+        eprintln!(
+            "\x1b[1;93mWarning\x1b[0m: The fuzz test was built without a fuzzer and no inputs were provided \
+            through the {REPLAY_INPUTS_ENV} environment variable."
+        );
+        return;
+    };
+    let inputs: Vec<PathBuf> = std::env::split_paths(&inputs).collect();
+    let files = input_files(&inputs).unwrap_or_else(|e| panic!("Failed to read the inputs: {e}"));
+    let read = |file: &Path| {
+        fs::read(file).unwrap_or_else(|e| panic!("Failed to read {}: {e}", file.display()))
+    };
+
+    if let Some(output) = std::env::var_os(MINIMIZE_OUTPUT_ENV) {
+        let [file] = files.as_slice() else {
+            panic!("Exactly one input can be minimized");
+        };
+        minimize_input(&read(file), Path::new(&output), decode, run);
+        return;
+    }
+
+    if std::env::var_os(TRIAGE_ENV).is_some() {
+        triage_inputs(&files, read, decode, run);
+        return;
+    }
+
+    if let Some(format) = std::env::var_os(DECODE_ENV) {
+        decode_inputs(&files, format == "json", read, decode);
+        return;
+    }
+
+    let mut results = vec![];
+    for file in &files {
+        let data = read(file);
+        let Some(fuzz_data) = decode(&data) else {
+            eprintln!(
+                "\x1b[1;93mSkipped\x1b[0m: {} cannot be decoded",
+                file.display()
+            );
+            continue;
+        };
+        let crash = match catch_crash(|| run(fuzz_data)) {
+            ReplayOutcome::Crashed(signature) => {
+                eprintln!("\x1b[31mCrashed\x1b[0m: {} ({signature})", file.display());
+                Some(signature)
+            }
+            ReplayOutcome::Passed => None,
+        };
+        results.push(ReplayResult {
+            file: file.clone(),
+            size: data.len(),
+            crash,
+        });
+    }
+    // the statistics of the replay are written on the thread exit, which does not happen on the process exit
+    stats_channel::flush();
+    if let Some(results_path) = std::env::var_os(REPLAY_RESULTS_ENV) {
+        fs::write(&results_path, serde_json::to_string(&results).unwrap())
+            .unwrap_or_else(|e| panic!("Failed to write the replay results: {e}"));
+    }
+
+    let crashed: Vec<_> = results
+        .iter()
+        .filter(|result| result.crash.is_some())
+        .collect();
+    eprintln!("Replayed {} inputs, {} crashed", files.len(), crashed.len());
+    if !crashed.is_empty() {
+        for result in crashed {
+            eprintln!(
+                "  {} ({})",
+                result.file.display(),
+                result.crash.as_ref().unwrap()
+            );
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Prints the instruction sequences of the inputs as plain text or JSON, no instructions are executed.
+/// The process exits with the code 1 if none of the inputs can be decoded.
+fn decode_inputs<T: Display, U>(
+    files: &[PathBuf],
+    json: bool,
+    read: impl Fn(&Path) -> Vec<u8>,
+    decode: impl Fn(&[u8]) -> Option<FuzzData<T, U>>,
+) {
+    let mut decoded = vec![];
+    for file in files {
+        let fuzz_data = decode(&read(file));
+        if !json {
+            if files.len() > 1 {
+                println!("# {}", file.display());
+            }
+            match &fuzz_data {
+                Some(fuzz_data) => print!("{}", format_sequence(fuzz_data)),
+                None => eprintln!(
+                    "\x1b[1;93mSkipped\x1b[0m: {} cannot be decoded",
+                    file.display()
+                ),
+            }
+        }
+        decoded.push(DecodedInput::new(file, fuzz_data.as_ref()));
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&decoded).unwrap());
+    }
+    if decoded.iter().all(|input| input.error.is_some()) {
+        std::process::exit(1);
+    }
+}
+
+/// Replays the crash inputs and prints the buckets of the same failures with their representatives.
+fn triage_inputs<T: Display, U>(
+    files: &[PathBuf],
+    read: impl Fn(&Path) -> Vec<u8>,
+    decode: impl Fn(&[u8]) -> Option<FuzzData<T, U>>,
+    mut run: impl FnMut(FuzzData<T, U>),
+) {
+    // the inputs are expected to panic, do not flood the output with the panic messages
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut crashes = vec![];
+    let mut passed = vec![];
+    let mut undecodable = vec![];
+    for file in files {
+        let data = read(file);
+        let Some(fuzz_data) = decode(&data) else {
+            undecodable.push(file);
+            continue;
+        };
+        match catch_crash(|| run(fuzz_data)) {
+            ReplayOutcome::Crashed(signature) => {
+                crashes.push((file.clone(), data.len(), signature))
+            }
+            ReplayOutcome::Passed => passed.push(file),
+        }
+    }
+
+    let crashed = crashes.len();
+    let buckets = triage(crashes);
+    let mut table = Table::new();
+    table.add_row(row![
+        "Bucket",
+        "Count",
+        "Instruction",
+        "Error",
+        "Location",
+        "Representative"
+    ]);
+    for (index, bucket) in buckets.iter().enumerate() {
+        table.add_row(row![
+            index,
+            bucket.files.len(),
+            bucket.signature.instruction.as_deref().unwrap_or("-"),
+            bucket.signature.error,
+            bucket.signature.location.as_deref().unwrap_or("-"),
+            bucket.representative().display(),
+        ]);
+    }
+    table.printstd();
+    println!(
+        "{crashed} of {} crash files grouped into {} unique crashes",
+        files.len(),
+        buckets.len()
+    );
+    for file in passed {
+        println!("No longer crashes: {}", file.display());
+    }
+    for file in undecodable {
+        println!("Cannot be decoded: {}", file.display());
+    }
+}
+
+/// Minimizes the crash input and writes the minimized input together with its instruction sequence
+/// (`<output>.txt`). The input has to reproduce the same failure, i.e. the same instruction has to
+/// fail with the same error at the same location.
+fn minimize_input<T: Display, U>(
+    data: &[u8],
+    output: &Path,
+    decode: impl Fn(&[u8]) -> Option<FuzzData<T, U>>,
+    mut run: impl FnMut(FuzzData<T, U>),
+) {
+    // the candidates are expected to panic, do not flood the output with the panic messages
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut execute = |data: &[u8]| {
+        let fuzz_data = decode(data)?;
+        let instructions = fuzz_data.iter().count();
+        match catch_crash(|| run(fuzz_data)) {
+            ReplayOutcome::Crashed(signature) => Some((signature, instructions)),
+            ReplayOutcome::Passed => None,
+        }
+    };
+
+    let Some((signature, instructions)) = execute(data) else {
+        println!("The input does not reproduce any crash, nothing to minimize");
+        std::process::exit(1);
+    };
+    println!(
+        "Minimizing {instructions} instructions ({} bytes) failing with {signature}",
+        data.len()
+    );
+
+    let mut candidates = 0;
+    let minimized = minimize(data, |candidate| {
+        candidates += 1;
+        execute(candidate)
+            .filter(|(candidate_signature, _)| *candidate_signature == signature)
+            .map(|(_, instructions)| instructions)
+    });
+
+    let fuzz_data = decode(&minimized).expect("The minimized input has to be decodable");
+    let sequence = format_sequence(&fuzz_data);
+    let sequence_path = PathBuf::from(format!("{}.txt", output.display()));
+    fs::write(output, &minimized)
+        .and_then(|_| fs::write(&sequence_path, &sequence))
+        .unwrap_or_else(|e| panic!("Failed to write the minimized input: {e}"));
+
+    println!(
+        "Minimized to {} instructions ({} bytes) after {candidates} executions",
+        fuzz_data.iter().count(),
+        minimized.len()
+    );
+    println!("{sequence}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_files_and_catch_crash() {
+        let dir = std::env::temp_dir().join(format!("trident-replay-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.fuzz"), [1]).unwrap();
+        fs::write(dir.join("a.fuzz"), [0]).unwrap();
+        fs::write(dir.join(".lock"), []).unwrap();
+        let single =
+            std::env::temp_dir().join(format!("trident-replay-{}.fuzz", std::process::id()));
+        fs::write(&single, [2]).unwrap();
+
+        let files = input_files(&[dir.clone(), single.clone()]).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.join("a.fuzz"), dir.join("b.fuzz"), single.clone()]
+        );
+        assert!(input_files(&[dir.join("missing")]).is_err());
+
+        assert_eq!(catch_crash(|| {}), ReplayOutcome::Passed);
+        let ReplayOutcome::Crashed(signature) =
+            catch_crash(|| panic!("attempt to add with overflow\nnote: backtrace"))
+        else {
+            panic!("the panic has to be caught");
+        };
+        assert_eq!(signature.instruction, None);
+        assert_eq!(signature.error, "attempt to add with overflow");
+        assert!(signature.location.unwrap().starts_with(file!()));
+
+        let ReplayOutcome::Crashed(signature) = catch_crash(|| {
+            record_crash("Deposit", "invariant check failed");
+            panic!("custom check failed")
+        }) else {
+            panic!("the panic has to be caught");
+        };
+        assert_eq!(signature.instruction.as_deref(), Some("Deposit"));
+        assert_eq!(signature.error, "invariant check failed");
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_file(&single).unwrap();
+    }
+
+    #[test]
+    fn test_triage() {
+        let signature = |instruction: &str, location: &str| CrashSignature {
+            instruction: Some(instruction.to_string()),
+            error: "custom check failed".to_string(),
+            message: None,
+            location: Some(location.to_string()),
+        };
+        let crashes = vec![
+            (
+                PathBuf::from("a.fuzz"),
+                30,
+                signature("Deposit", "lib.rs:1:1"),
+            ),
+            (
+                PathBuf::from("b.fuzz"),
+                10,
+                signature("Withdraw", "lib.rs:1:1"),
+            ),
+            (
+                PathBuf::from("c.fuzz"),
+                20,
+                signature("Deposit", "lib.rs:1:1"),
+            ),
+            (
+                PathBuf::from("d.fuzz"),
+                5,
+                signature("Deposit", "lib.rs:2:1"),
+            ),
+            (
+                PathBuf::from("e.fuzz"),
+                25,
+                signature("Deposit", "lib.rs:1:1"),
+            ),
+        ];
+        let buckets = triage(crashes);
+
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].signature, signature("Deposit", "lib.rs:1:1"));
+        assert_eq!(
+            buckets[0].files,
+            vec![
+                PathBuf::from("c.fuzz"),
+                PathBuf::from("e.fuzz"),
+                PathBuf::from("a.fuzz")
+            ]
+        );
+        assert_eq!(buckets[0].representative(), Path::new("c.fuzz"));
+        assert_eq!(
+            buckets[0].signature.to_string(),
+            "custom check failed in Deposit at lib.rs:1:1"
+        );
+        assert!(buckets[1..].iter().all(|bucket| bucket.files.len() == 1));
+
+        // different invariant violations of the same instruction are different crashes
+        let check_failure = |message: &str| {
+            let ReplayOutcome::Crashed(signature) = catch_crash(|| {
+                record_check_failure("Deposit", format!("Custom fuzzing error: {message}\n"));
+                panic!("custom check failed")
+            }) else {
+                panic!("the panic has to be caught");
+            };
+            (PathBuf::from(format!("{message}.fuzz")), 1, signature)
+        };
+        let buckets = triage(vec![check_failure("1"), check_failure("2")]);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(
+            buckets[0].signature.message.as_deref(),
+            Some("Custom fuzzing error: 1")
+        );
+        assert!(buckets[0]
+            .signature
+            .to_string()
+            .starts_with("custom check failed (Custom fuzzing error: 1) in Deposit at "));
+    }
+
+    #[test]
+    fn test_decoded_input() {
+        let fuzz_data: FuzzData<String, ()> = FuzzData {
+            pre_ixs: vec!["Initialize".to_string()],
+            ixs: vec!["Deposit {\n amount: 5\n}".to_string()],
+            post_ixs: vec![],
+            accounts: RefCell::new(()),
+        };
+        let decoded = DecodedInput::new(Path::new("a.fuzz"), Some(&fuzz_data));
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::json!({
+                "file": "a.fuzz",
+                "pre_ixs": ["Initialize"],
+                "ixs": ["Deposit {\n amount: 5\n}"],
+                "post_ixs": []
+            })
+        );
+        assert_eq!(
+            format_sequence(&fuzz_data),
+            "pre_ixs:\n  0: Initialize\nixs:\n  0: Deposit {\n     amount: 5\n    }\npost_ixs:\n"
+        );
+
+        let undecodable = DecodedInput::new::<String, ()>(Path::new("b.fuzz"), None);
+        assert!(undecodable.error.is_some());
+        assert!(undecodable.ixs.is_empty());
+    }
+}
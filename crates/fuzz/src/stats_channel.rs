@@ -0,0 +1,313 @@
+use std::{
+    cell::RefCell,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Once, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::fuzz_stats::FuzzingStatistics;
+
+/// Environment variable with the directory the fuzzing statistics are written into. The directory
+/// is created by the Trident CLI, statistics are not collected if the variable is not set.
+pub const STATS_DIR_ENV: &str = "TRIDENT_STATS_DIR";
+
+/// Minimal delay between two snapshots of the statistics written by a single thread.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+const SNAPSHOT_EXTENSION: &str = "json";
+
+static STATS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+static THREAD_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    static THREAD_STATS: RefCell<Option<ThreadStats>> = const { RefCell::new(None) };
+}
+
+/// Statistics aggregated in-process by a single fuzzing thread. The thread periodically writes the
+/// whole snapshot into its own file, so readers never observe partial updates and the output
+/// of the fuzzer (e.g. `keep_output` of honggfuzz) does not matter.
+struct ThreadStats {
+    stats: FuzzingStatistics,
+    path: PathBuf,
+    last_flush: Option<Instant>,
+}
+
+impl ThreadStats {
+    fn new(dir: &Path) -> Self {
+        let file_name = format!(
+            "{}-{}.{SNAPSHOT_EXTENSION}",
+            std::process::id(),
+            THREAD_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut thread_stats = Self {
+            stats: FuzzingStatistics::new(),
+            path: dir.join(file_name),
+            last_flush: None,
+        };
+        thread_stats.absorb_finished(dir);
+        thread_stats
+    }
+
+    /// Fuzzers restart the fuzzed process (e.g. AFL++ after every batch of persistent iterations), so
+    /// the snapshots of processes which are no longer running are merged into the statistics of this
+    /// thread and removed. The number of snapshot files is therefore bounded by the number of running
+    /// fuzzing threads.
+    fn absorb_finished(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut absorbed = vec![];
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            // the snapshot with the same name was left by a finished process with the same PID
+            let finished = path == self.path
+                || snapshot_pid(&path)
+                    .is_some_and(|pid| pid != std::process::id() && !is_running(pid));
+            if !finished {
+                continue;
+            }
+            // the rename succeeds only for a single thread, so every snapshot is absorbed only once
+            let claimed_path = path.with_extension(format!("absorbed-{}", std::process::id()));
+            if fs::rename(&path, &claimed_path).is_err() {
+                continue;
+            }
+            let snapshot = fs::read(&claimed_path)
+                .ok()
+                .and_then(|data| serde_json::from_slice::<FuzzingStatistics>(&data).ok());
+            if let Some(snapshot) = snapshot {
+                self.stats.merge(snapshot);
+            }
+            absorbed.push(claimed_path);
+        }
+        if !absorbed.is_empty() {
+            self.flush();
+            for path in absorbed {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    fn flush_if_due(&mut self) {
+        if self
+            .last_flush
+            .map_or(true, |last_flush| last_flush.elapsed() >= FLUSH_INTERVAL)
+        {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        // write into a temporary file first, rename is atomic so readers see either the previous or the new snapshot
+        let tmp_path = self.path.with_extension("tmp");
+        let result = fs::write(&tmp_path, serde_json::to_vec(&self.stats).unwrap())
+            .and_then(|_| fs::rename(&tmp_path, &self.path));
+        if let Err(e) = result {
+            eprintln!(
+                "\x1b[1;93mWarning\x1b[0m: Failed to write fuzzing statistics to {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+        self.last_flush = Some(Instant::now());
+    }
+}
+
+impl Drop for ThreadStats {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Returns the PID of the process which wrote the snapshot, i.e. `<pid>-<thread>.json`.
+fn snapshot_pid(path: &Path) -> Option<u32> {
+    if path
+        .extension()
+        .map_or(true, |ext| ext != SNAPSHOT_EXTENSION)
+    {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    stem.split_once('-')?.0.parse().ok()
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // signal 0 only checks whether the process exists
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+fn stats_dir() -> Option<&'static PathBuf> {
+    STATS_DIR
+        .get_or_init(|| std::env::var_os(STATS_DIR_ENV).map(PathBuf::from))
+        .as_ref()
+}
+
+/// Returns `true` if the fuzzing statistics are collected, i.e. the Trident CLI provided the stats directory.
+pub fn is_enabled() -> bool {
+    stats_dir().is_some()
+}
+
+/// Updates the statistics of the current thread. The statistics are written into the stats
+/// directory at most once per `FLUSH_INTERVAL` and before the thread panics on a crash.
+/// Does nothing if the statistics are not collected.
+pub fn record(update: impl FnOnce(&mut FuzzingStatistics)) {
+    let Some(dir) = stats_dir() else {
+        return;
+    };
+    PANIC_HOOK.call_once(install_panic_hook);
+
+    THREAD_STATS.with(|thread_stats| {
+        let mut thread_stats = thread_stats.borrow_mut();
+        let thread_stats = thread_stats.get_or_insert_with(|| ThreadStats::new(dir));
+        update(&mut thread_stats.stats);
+        thread_stats.flush_if_due();
+    });
+}
+
+/// Writes the statistics of the current thread into the stats directory.
+pub fn flush() {
+    let _ = THREAD_STATS.try_with(|thread_stats| {
+        if let Ok(mut thread_stats) = thread_stats.try_borrow_mut() {
+            if let Some(thread_stats) = thread_stats.as_mut() {
+                thread_stats.flush();
+            }
+        }
+    });
+}
+
+/// Crashes abort the fuzzed process, so the latest statistics have to be written before that.
+fn install_panic_hook() {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        flush();
+        previous_hook(info);
+    }));
+}
+
+/// Creates an empty stats directory, statistics from previous sessions are removed.
+pub fn prepare(dir: &Path) -> io::Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)
+}
+
+/// Merges the latest snapshots of all fuzzing threads within the stats directory.
+pub fn collect(dir: &Path) -> FuzzingStatistics {
+    let mut stats = FuzzingStatistics::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return stats;
+    };
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path
+            .extension()
+            .map_or(true, |ext| ext != SNAPSHOT_EXTENSION)
+        {
+            continue;
+        }
+        let snapshot = fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<FuzzingStatistics>(&data).ok());
+        if let Some(snapshot) = snapshot {
+            stats.merge(snapshot);
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_thread_snapshots() {
+        let dir = std::env::temp_dir().join(format!("trident-stats-test-{}", std::process::id()));
+        prepare(&dir).unwrap();
+
+        let mut first = ThreadStats::new(&dir);
+        first.stats.increase_iterations(2);
+        first.stats.increase_invoked("Deposit".to_string());
+        first.stats.increase_successful("Deposit".to_string());
+        first.flush();
+
+        let mut second = ThreadStats::new(&dir);
+        second.stats.increase_iterations(1);
+        second.stats.increase_invoked("Deposit".to_string());
+        second.stats.increase_failed("Deposit".to_string());
+        second.flush();
+
+        // snapshots are overwritten, not appended
+        second.stats.increase_iterations(1);
+        second.flush();
+        fs::write(dir.join("partial.tmp"), "{").unwrap();
+
+        let stats = collect(&dir);
+        assert_eq!(stats.iterations, 3);
+        assert_eq!(stats.sequence_lengths.get(&1), Some(&2));
+        let deposit = &stats.instructions["Deposit"];
+        assert_eq!(deposit.invoked, 2);
+        assert_eq!(deposit.successful, 1);
+        assert_eq!(deposit.failed, 1);
+
+        drop(first);
+        drop(second);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    #[cfg(unix)]
+    fn test_absorb_snapshots_of_finished_processes() {
+        let dir =
+            std::env::temp_dir().join(format!("trident-stats-absorb-test-{}", std::process::id()));
+        prepare(&dir).unwrap();
+
+        let mut finished_process = std::process::Command::new("true").spawn().unwrap();
+        let finished_pid = finished_process.id();
+        finished_process.wait().unwrap();
+
+        let mut finished = FuzzingStatistics::new();
+        finished.increase_iterations(1);
+        finished.increase_invoked("Deposit".to_string());
+        for thread in 0..2 {
+            fs::write(
+                dir.join(format!("{finished_pid}-{thread}.{SNAPSHOT_EXTENSION}")),
+                serde_json::to_vec(&finished).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let mut running = ThreadStats::new(&dir);
+        running.stats.increase_iterations(1);
+        running.flush();
+
+        let snapshots: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(snapshots, vec![running.path.clone()]);
+
+        let stats = collect(&dir);
+        assert_eq!(stats.iterations, 3);
+        assert_eq!(stats.instructions["Deposit"].invoked, 2);
+
+        drop(running);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,24 +1,32 @@
 use std::{cell::RefCell, collections::HashMap};
 
-use anchor_lang::InstructionData;
 use solana_sdk::{
     instruction::Instruction, signature::Keypair, signer::Signer, transaction::Transaction,
 };
 
 use crate::{
     config::Config,
-    error::{FuzzClientErrorWithOrigin, Origin},
+    crash_classifier,
+    error::{FuzzClientError, FuzzClientErrorWithOrigin, Origin},
     fuzz_client::FuzzClient,
-    fuzz_stats::FuzzingStatistics,
+    ix_data::ToIxData,
     ix_ops::IxOps,
+    replay,
     snapshot::Snapshot,
+    stats_channel,
 };
 
 pub struct TransactionExecutor;
 
 impl TransactionExecutor {
+    /// Executes the fuzzed instruction within a transaction and checks its invariants, the same way
+    /// for every fuzzing engine as well as for the replay of inputs.
+    ///
+    /// Crashes panic at the location of the caller, i.e. within the fuzz instructions of the fuzz test,
+    /// so the crash reports point to the user code instead of Trident.
     #[allow(clippy::too_many_arguments)]
-    pub fn process_transaction_honggfuzz<I>(
+    #[track_caller]
+    pub fn process_transaction<I>(
         instruction_name: &str,
         client: &mut impl FuzzClient,
         ix: &I,
@@ -48,10 +56,16 @@ impl TransactionExecutor {
         let ixx = Instruction {
             program_id,
             accounts: account_metas,
-            data: data.data(),
+            data: data.to_ix_data(),
         };
 
-        let mut transaction = Transaction::new_with_payer(&[ixx], Some(&client.payer().pubkey()));
+        let mut instructions = Self::get_compute_budget_instructions(ix, config);
+        // the fuzzed instruction follows the ComputeBudget instructions
+        let ix_index = instructions.len() as u8;
+        instructions.push(ixx);
+
+        let mut transaction =
+            Transaction::new_with_payer(&instructions, Some(&client.payer().pubkey()));
 
         signers.push(client.payer().insecure_clone());
         let sig: Vec<&Keypair> = signers.iter().collect();
@@ -70,135 +84,48 @@ impl TransactionExecutor {
                 instruction_name.to_owned()
             ),
             None => {
-                if config.get_fuzzing_with_stats() {
-                    let mut stats_logger = FuzzingStatistics::new();
-
-                    stats_logger.increase_invoked(instruction_name.to_owned());
-
-                    let tx_result = client.process_transaction(transaction).map_err(|e| {
-                        e.with_origin(Origin::Instruction(instruction_name.to_owned()))
-                    });
-                    match tx_result {
-                        Ok(_) => {
-                            stats_logger.increase_successful(instruction_name.to_owned());
-
-                            snapshot.capture_after(client).unwrap();
-                            let (acc_before, acc_after) = snapshot.get_snapshot();
-
-                            if let Err(e) = ix.check(acc_before, acc_after, data).map_err(|e| {
-                                e.with_origin(Origin::Instruction(instruction_name.to_owned()))
-                            }) {
-                                stats_logger.increase_failed_check(instruction_name.to_owned());
-                                stats_logger.output_serialized();
-
-                                eprintln!("\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} instruction did not pass!",instruction_name.to_owned());
-                                panic!("{}", e)
-                            }
-                            stats_logger.output_serialized();
-                        }
-                        Err(e) => {
-                            stats_logger.increase_failed(instruction_name.to_owned());
-                            stats_logger.output_serialized();
+                // statistics are recorded only if the Trident CLI provided the stats directory
+                stats_channel::record(|stats| stats.increase_invoked(instruction_name.to_owned()));
 
-                            let raw_accounts = snapshot.get_before();
-                            ix.tx_error_handler(e, data, raw_accounts)?
-                        }
-                    }
-                } else {
-                    let tx_result = client.process_transaction(transaction).map_err(|e| {
+                let tx_result = client
+                    .process_transaction_with_signers(transaction, &sig)
+                    .map_err(|e| {
                         e.with_origin(Origin::Instruction(instruction_name.to_owned()))
+                            .with_program(ix_index, program_id)
                     });
-                    match tx_result {
-                        Ok(_) => {
-                            snapshot.capture_after(client).unwrap();
-                            let (acc_before, acc_after) = snapshot.get_snapshot();
-
-                            if let Err(e) = ix.check(acc_before, acc_after, data).map_err(|e| {
-                                e.with_origin(Origin::Instruction(instruction_name.to_owned()))
-                            }) {
-                                eprintln!("\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} instruction did not pass!",instruction_name.to_owned());
-                                panic!("{}", e)
-                            }
-                        }
-                        Err(e) => {
-                            let raw_accounts = snapshot.get_before();
-                            ix.tx_error_handler(e, data, raw_accounts)?
-                        }
-                    }
-                }
-            }
-        }
-        Ok(())
-    }
-    #[allow(clippy::too_many_arguments)]
-    pub fn process_transaction_afl<I>(
-        instruction_name: &str,
-        client: &mut impl FuzzClient,
-        ix: &I,
-        sent_txs: &mut HashMap<anchor_lang::solana_program::hash::Hash, ()>,
-        config: &Config,
-        accounts: &RefCell<I::IxAccounts>,
-    ) -> core::result::Result<(), FuzzClientErrorWithOrigin>
-    where
-        I: IxOps,
-    {
-        let program_id = ix.get_program_id();
-
-        let (mut signers, account_metas) = ix
-            .get_accounts(client, &mut accounts.borrow_mut())
-            .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
-            .expect("Accounts calculation expect");
-
-        let mut snapshot = Snapshot::new(&account_metas);
-
-        let data = ix
-            .get_data(client, &mut accounts.borrow_mut())
-            .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
-            .expect("Data calculation expect");
-
-        snapshot.capture_before(client).unwrap();
-
-        let ixx = Instruction {
-            program_id,
-            accounts: account_metas,
-            data: data.data(),
-        };
-
-        let mut transaction = Transaction::new_with_payer(&[ixx], Some(&client.payer().pubkey()));
-
-        signers.push(client.payer().insecure_clone());
-        let sig: Vec<&Keypair> = signers.iter().collect();
-        transaction.sign(&sig, client.get_last_blockhash());
-
-        let duplicate_tx = if config.get_allow_duplicate_txs() {
-            None
-        } else {
-            let message_hash = transaction.message().hash();
-            sent_txs.insert(message_hash, ())
-        };
-
-        match duplicate_tx {
-            Some(_) => eprintln!(
-                "\x1b[1;93mWarning\x1b[0m: Skipping duplicate instruction `{}`",
-                instruction_name.to_owned()
-            ),
-            None => {
-                let tx_result = client
-                    .process_transaction(transaction)
-                    .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())));
                 match tx_result {
                     Ok(_) => {
+                        stats_channel::record(|stats| {
+                            stats.increase_successful(instruction_name.to_owned())
+                        });
+
                         snapshot.capture_after(client).unwrap();
                         let (acc_before, acc_after) = snapshot.get_snapshot();
 
                         if let Err(e) = ix.check(acc_before, acc_after, data).map_err(|e| {
                             e.with_origin(Origin::Instruction(instruction_name.to_owned()))
                         }) {
+                            stats_channel::record(|stats| {
+                                stats.increase_failed_check(instruction_name.to_owned());
+                                stats.increase_crashes(instruction_name.to_owned());
+                            });
+
                             eprintln!("\x1b[31mCRASH DETECTED!\x1b[0m Custom check after the {} instruction did not pass!",instruction_name.to_owned());
+                            replay::record_check_failure(instruction_name, &e.fuzzing_error);
                             panic!("{}", e)
                         }
                     }
                     Err(e) => {
+                        stats_channel::record(|stats| {
+                            stats.increase_failed_with_error(
+                                instruction_name.to_owned(),
+                                e.client_error.summary(e.program.as_ref()),
+                            )
+                        });
+
+                        Self::check_divergence(&e, instruction_name);
+                        Self::check_crash(&e, ix, instruction_name, client, config);
+
                         let raw_accounts = snapshot.get_before();
                         ix.tx_error_handler(e, data, raw_accounts)?
                     }
@@ -207,4 +134,67 @@ impl TransactionExecutor {
         }
         Ok(())
     }
+
+    /// Divergences found during differential fuzzing are crashes, they cannot be handled by the `tx_error_handler`.
+    #[track_caller]
+    fn check_divergence(e: &FuzzClientErrorWithOrigin, instruction_name: &str) {
+        if let FuzzClientError::Divergence(_) = e.client_error {
+            eprintln!(
+                "\x1b[31mCRASH DETECTED!\x1b[0m Differential execution of the {} instruction diverged!",
+                instruction_name
+            );
+            stats_channel::record(|stats| stats.increase_crashes(instruction_name.to_owned()));
+            replay::record_crash(instruction_name, replay::DIVERGENCE);
+            panic!("{}", e)
+        }
+    }
+
+    /// Failures indicating a bug within the program (e.g. arithmetic overflow panics) are crashes unless
+    /// the instruction expects them, they cannot be handled by the `tx_error_handler`.
+    #[track_caller]
+    fn check_crash<I>(
+        e: &FuzzClientErrorWithOrigin,
+        ix: &I,
+        instruction_name: &str,
+        client: &impl FuzzClient,
+        config: &Config,
+    ) where
+        I: IxOps,
+    {
+        let Some(transaction_error) = e.client_error.transaction_error() else {
+            return;
+        };
+        let logs = client.get_last_transaction_logs();
+        if let Some(crash_kind) = crash_classifier::classify(&transaction_error, logs) {
+            if crash_classifier::is_crash(
+                crash_kind,
+                config.get_crash_kinds(),
+                &ix.get_expected_errors(),
+                ix.get_compute_budget().as_ref(),
+            ) {
+                eprintln!(
+                    "\x1b[31mCRASH DETECTED!\x1b[0m The {} instruction failed with {}!",
+                    instruction_name, crash_kind
+                );
+                for log in logs {
+                    eprintln!("{}", log);
+                }
+                stats_channel::record(|stats| stats.increase_crashes(instruction_name.to_owned()));
+                replay::record_crash(instruction_name, crash_kind);
+                panic!("{}", e)
+            }
+        }
+    }
+
+    /// Creates the ComputeBudget instructions to prepend to the fuzzed instruction. Settings provided by
+    /// the instruction take precedence over the settings from the Trident Manifest.
+    fn get_compute_budget_instructions<I>(ix: &I, config: &Config) -> Vec<Instruction>
+    where
+        I: IxOps,
+    {
+        ix.get_compute_budget()
+            .unwrap_or_default()
+            .or(config.get_compute_budget())
+            .get_instructions()
+    }
 }